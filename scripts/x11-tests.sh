@@ -0,0 +1,25 @@
+#!/bin/sh
+# Runs the X11 integration tests on a virtual X server with openbox as the window manager
+# Needs xvfb-run, openbox and xprop (Debian and Ubuntu packages: xvfb, openbox, x11-utils)
+set -eu
+
+cd "$(dirname "$0")/.."
+
+exec xvfb-run --auto-servernum --server-args="-screen 0 1920x1080x24" sh -eu -c '
+    openbox &
+    wm=$!
+    trap "kill $wm" EXIT
+
+    # The tests need the window manager to list the windows, so wait until it supports EWMH
+    tries=0
+    until xprop -root _NET_SUPPORTING_WM_CHECK | grep -q "window id"; do
+        tries=$((tries + 1))
+        if [ "$tries" -gt 50 ]; then
+            echo "openbox did not start" >&2
+            exit 1
+        fi
+        sleep 0.1
+    done
+
+    FULLSCREENIZER_X11_TESTS=1 cargo test --features x11 --test x11
+'
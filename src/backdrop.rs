@@ -0,0 +1,114 @@
+use winsafe::co::{ERROR, LWA, STOCK_BRUSH, SW, SWP, WM, WS, WS_EX};
+use winsafe::msg::WndMsg;
+use winsafe::prelude::Handle as _;
+use winsafe::{
+    AtomStr, COLORREF, HBRUSH, HINSTANCE, HWND, HwndPlace, IdMenu, POINT, RegisterClassEx, SIZE,
+    WNDCLASSEX, WString,
+};
+
+use crate::geometry::Rect;
+
+/// Name of the window class of the backdrops
+const CLASS_NAME: &str = "FullscreenizerBackdrop";
+
+/// A black window covering a monitor behind a fullscreenized window, hiding what the window leaves uncovered
+///
+/// The backdrop lets clicks through, never takes the focus and has no taskbar button.
+/// It is destroyed when this is dropped.
+pub struct Backdrop {
+    hwnd: HWND,
+}
+
+impl Backdrop {
+    /// Function to create a backdrop
+    /// # Arguments
+    /// * `rect` - The area to cover, usually a whole monitor, in virtual-desktop coordinates
+    /// # Returns
+    /// * The backdrop, which is not shown until it is placed, or an error message
+    pub fn new(rect: Rect) -> Result<Self, String> {
+        let hinstance = HINSTANCE::GetModuleHandle(None)
+            .map_err(|e| format!("GetModuleHandle failed with error: {e}"))?;
+        register_class(&hinstance)?;
+
+        // A layered window can be made transparent to clicks, while being drawn fully opaque
+        let hwnd = unsafe {
+            HWND::CreateWindowEx(
+                WS_EX::LAYERED | WS_EX::TRANSPARENT | WS_EX::TOOLWINDOW | WS_EX::NOACTIVATE,
+                AtomStr::from_str(CLASS_NAME),
+                Some("Fullscreenizer backdrop"),
+                WS::POPUP,
+                POINT::with(rect.left, rect.top),
+                SIZE::with(rect.width(), rect.height()),
+                None,
+                IdMenu::None,
+                &hinstance,
+                None,
+            )
+        }
+        .map_err(|e| format!("CreateWindowEx failed with error: {e}"))?;
+        let backdrop = Self { hwnd };
+
+        backdrop
+            .hwnd
+            .SetLayeredWindowAttributes(COLORREF::from_rgb(0, 0, 0), 255, LWA::ALPHA)
+            .map_err(|e| format!("SetLayeredWindowAttributes failed with error: {e}"))?;
+
+        Ok(backdrop)
+    }
+
+    /// Function to show the backdrop directly below a window in the z-order
+    /// # Arguments
+    /// * `hwnd` - The raw handle of the window covering part of the backdrop
+    /// # Returns
+    /// * An error message if the backdrop could not be moved
+    ///
+    /// A window placed below a window with the "stay on top" flag gets the flag too,
+    /// so the backdrop stays behind the window either way.
+    pub fn place_below(&self, hwnd: isize) -> Result<(), String> {
+        self.hwnd
+            .SetWindowPos(
+                HwndPlace::Hwnd(unsafe { HWND::from_ptr(hwnd as *mut _) }),
+                POINT::default(),
+                SIZE::default(),
+                SWP::NOMOVE | SWP::NOSIZE | SWP::NOACTIVATE,
+            )
+            .map_err(|e| format!("SetWindowPos failed with error: {e}"))?;
+        self.hwnd.ShowWindow(SW::SHOWNOACTIVATE);
+
+        Ok(())
+    }
+}
+
+impl Drop for Backdrop {
+    fn drop(&mut self) {
+        self.hwnd
+            .DestroyWindow()
+            .map_err(|e| eprintln!("Failed to remove a backdrop - DestroyWindow failed: {e}"))
+            .ok();
+    }
+}
+
+/// Function to register the window class of the backdrops, painted in black
+/// # Arguments
+/// * `hinstance` - The module of Fullscreenizer
+/// # Returns
+/// * An error message if the class could not be registered, which is not an error if it already was
+fn register_class(hinstance: &HINSTANCE) -> Result<(), String> {
+    let mut class_name = WString::from_str(CLASS_NAME);
+    let mut class = WNDCLASSEX::default();
+    class.lpfnWndProc = Some(backdrop_proc);
+    class.hInstance = unsafe { hinstance.raw_copy() };
+    class.hbrBackground = HBRUSH::GetStockObject(STOCK_BRUSH::BLACK)
+        .map_err(|e| format!("GetStockObject failed with error: {e}"))?;
+    class.set_lpszClassName(Some(&mut class_name));
+
+    match unsafe { RegisterClassEx(&class) } {
+        Ok(_) | Err(ERROR::CLASS_ALREADY_EXISTS) => Ok(()),
+        Err(e) => Err(format!("RegisterClassEx failed with error: {e}")),
+    }
+}
+
+/// Function called by the system for every message sent to a backdrop, which are all handled by default
+extern "system" fn backdrop_proc(hwnd: HWND, msg: WM, wparam: usize, lparam: isize) -> isize {
+    unsafe { hwnd.DefWindowProc(WndMsg::new(msg, wparam, lparam)) }
+}
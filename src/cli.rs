@@ -0,0 +1,475 @@
+use crate::scaling::ScaleMode;
+use crate::window_info::{self, WindowInfo};
+
+/// Exit code used when a window could not be modified
+pub const EXIT_FAILURE: u8 = 1;
+/// Exit code used when the arguments are invalid
+pub const EXIT_USAGE: u8 = 2;
+/// Exit code used when no window matched
+pub const EXIT_NO_MATCH: u8 = 3;
+/// Exit code used when several windows matched but only one was expected
+pub const EXIT_AMBIGUOUS: u8 = 4;
+
+/// Options followed by a value, which can also be attached with `=` (e.g. `--title=--foo`)
+const VALUE_OPTIONS: [&str; 8] = [
+    "--title",
+    "--class",
+    "--exe",
+    "--pid",
+    "--hwnd",
+    "--monitor",
+    "--scale",
+    "--rect",
+];
+
+pub const USAGE: &str = "\
+Usage: fullscreenizer [COMMAND]
+
+Without a command, the Fullscreenizer window is shown.
+
+Commands:
+  list [OPTIONS]       Print the top-level windows
+  apply [MATCHERS]     Fullscreenize the window matching all of the MATCHERS
+  restore [MATCHERS]   Restore the fullscreenized windows matching all of the MATCHERS,
+                       or every fullscreenized window if no MATCHERS are given
+  watch                Apply the profiles from the settings to new windows until stopped with Ctrl+C
+  help                 Print this message
+
+Matchers:
+  --title <TEXT>       The window title contains TEXT (case-insensitive)
+  --class <NAME>       The window class is NAME (case-insensitive)
+  --exe <NAME>         The executable is NAME (case-insensitive, the .exe extension is optional)
+  --pid <PID>          The window belongs to the process PID
+  --hwnd <HANDLE>      The window handle is HANDLE (decimal, or hexadecimal with a 0x prefix)
+
+Options for list:
+  --json               Print the windows as a JSON array
+  --ndjson             Print the windows as newline-delimited JSON, one object per line
+  --all                Also print invisible, untitled and excluded windows
+
+Options for apply:
+  --monitor <NAME>     Send the window to the monitor NAME (e.g. DISPLAY2) instead of its current monitor,
+                       or span it across the monitors of the span NAME from the settings (e.g. \"All monitors\")
+  --scale <MODE>       Size the window with MODE instead of the mode chosen in the settings:
+                       stretch (cover the monitor), fit (keep the aspect ratio),
+                       native (keep the size) or integer (scale the size by a whole number)
+  --rect <RECT>        Place the window at RECT on the monitor instead of scaling it: the name of a preset
+                       from the settings, a size and position (e.g. \"1280x720 centered\", \"50%x100% right\"
+                       or \"640x480 at 100,50\") or a named area (e.g. \"left half\" or \"top-right quadrant\")
+  --work-area          Fill the monitor's work area, leaving the taskbar visible, even if the settings do not
+  --topmost            Apply the \"stay on top\" flag to the window
+  --all                Apply to every matching window instead of failing when several windows match
+
+Values can also be attached with an equals sign (e.g. --exe=game.exe),
+which is needed for values starting with -- (e.g. --title=--foo).
+
+Exit codes:
+  0  Success
+  1  A window could not be modified
+  2  The arguments are invalid
+  3  No window matched
+  4  Several windows matched and --all was not given
+";
+
+/// A command given on the command line
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Command {
+    /// Print the top-level windows
+    List(ListOptions),
+    /// Fullscreenize the matching windows
+    Apply(ApplyOptions),
+    /// Restore the matching fullscreenized windows
+    Restore(WindowMatcher),
+    /// Apply the profiles to new windows until the process is stopped
+    Watch,
+    /// Print the usage
+    Help,
+}
+
+/// The format windows are printed in by the `list` command
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// One tab-separated line per window
+    #[default]
+    Text,
+    /// A JSON array
+    Json,
+    /// One JSON object per line
+    Ndjson,
+}
+
+/// The arguments of the `list` command
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ListOptions {
+    /// The format to print the windows in
+    pub format: OutputFormat,
+    /// Whether to also print invisible, untitled and excluded windows
+    pub all: bool,
+}
+
+/// The arguments of the `apply` command
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ApplyOptions {
+    /// The windows to fullscreenize
+    pub matcher: WindowMatcher,
+    /// The name of the monitor or span to send the window to, or `None` to keep it on its current monitor
+    pub monitor: Option<String>,
+    /// How to size the window, or `None` to use the mode chosen in the settings
+    pub scale: Option<ScaleMode>,
+    /// The preset or rectangle expression to place the window at instead of scaling it, if any
+    pub rect: Option<String>,
+    /// Whether to only fill the work area of the monitor, or `false` to use the choice made in the settings
+    pub work_area: bool,
+    /// Whether to apply the "stay on top" flag
+    pub topmost: bool,
+    /// Whether to apply to every matching window instead of failing when several windows match
+    pub all: bool,
+}
+
+/// Criteria used to select windows from the command line
+///
+/// A window matches if it satisfies every criterion that was given.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WindowMatcher {
+    pub title: Option<String>,
+    pub class: Option<String>,
+    pub exe: Option<String>,
+    pub pid: Option<u32>,
+    pub hwnd: Option<isize>,
+}
+
+impl WindowMatcher {
+    /// Function to check whether no criterion was given
+    pub const fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.class.is_none()
+            && self.exe.is_none()
+            && self.pid.is_none()
+            && self.hwnd.is_none()
+    }
+
+    /// Function to check whether a window satisfies every criterion
+    /// # Arguments
+    /// * `window` - The window
+    /// # Returns
+    /// * `true` if the window matches
+    pub fn matches(&self, window: &WindowInfo) -> bool {
+        self.title
+            .as_ref()
+            .is_none_or(|title| window.title.to_lowercase().contains(&title.to_lowercase()))
+            && self
+                .class
+                .as_ref()
+                .is_none_or(|class| window.class_name.eq_ignore_ascii_case(class))
+            && self.exe.as_ref().is_none_or(|exe| {
+                window
+                    .exe_name()
+                    .is_some_and(|exe_name| window_info::exe_name_matches(exe_name, exe))
+            })
+            && self.pid.is_none_or(|pid| window.process_id == pid)
+            && self.hwnd.is_none_or(|hwnd| window.hwnd == hwnd)
+    }
+}
+
+/// Function to parse the command line arguments
+/// # Arguments
+/// * `args` - The arguments, without the program name
+/// # Returns
+/// * The command to run, `None` if no command was given and the window should be shown,
+///   or an error message if the arguments are invalid
+pub fn parse_args<I>(args: I) -> Result<Option<Command>, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter();
+    let Some(command) = args.next() else {
+        return Ok(None);
+    };
+
+    let mut command = match command.as_str() {
+        "list" => Command::List(ListOptions::default()),
+        "apply" => Command::Apply(ApplyOptions::default()),
+        "restore" => Command::Restore(WindowMatcher::default()),
+        "watch" => Command::Watch,
+        "help" | "--help" | "-h" | "/?" => Command::Help,
+        _ => return Err(format!("Unknown command '{command}'")),
+    };
+
+    while let Some(arg) = args.next() {
+        let (arg, attached) = match arg.split_once('=') {
+            Some((option, value)) if VALUE_OPTIONS.contains(&option) => {
+                (option.to_owned(), Some(value.to_owned()))
+            }
+            _ => (arg, None),
+        };
+
+        let matcher = match &mut command {
+            Command::Apply(options) => {
+                match arg.as_str() {
+                    "--topmost" => {
+                        options.topmost = true;
+                        continue;
+                    }
+                    "--all" => {
+                        options.all = true;
+                        continue;
+                    }
+                    "--work-area" => {
+                        options.work_area = true;
+                        continue;
+                    }
+                    "--monitor" => {
+                        options.monitor = Some(value_of(&arg, attached, &mut args)?);
+                        continue;
+                    }
+                    "--scale" => {
+                        options.scale = Some(value_of(&arg, attached, &mut args)?.parse()?);
+                        continue;
+                    }
+                    "--rect" => {
+                        options.rect = Some(value_of(&arg, attached, &mut args)?);
+                        continue;
+                    }
+                    _ => {}
+                }
+                &mut options.matcher
+            }
+            Command::Restore(matcher) => matcher,
+            Command::List(options) => {
+                match arg.as_str() {
+                    "--json" => options.format = OutputFormat::Json,
+                    "--ndjson" => options.format = OutputFormat::Ndjson,
+                    "--all" => options.all = true,
+                    _ => return Err(format!("Unexpected argument '{arg}'")),
+                }
+                continue;
+            }
+            Command::Watch | Command::Help => {
+                return Err(format!("Unexpected argument '{arg}'"));
+            }
+        };
+
+        match arg.as_str() {
+            "--title" => matcher.title = Some(value_of(&arg, attached, &mut args)?),
+            "--class" => matcher.class = Some(value_of(&arg, attached, &mut args)?),
+            "--exe" => matcher.exe = Some(value_of(&arg, attached, &mut args)?),
+            "--pid" => {
+                let value = value_of(&arg, attached, &mut args)?;
+                matcher.pid = Some(
+                    value
+                        .parse()
+                        .map_err(|e| format!("Invalid process ID '{value}': {e}"))?,
+                );
+            }
+            "--hwnd" => matcher.hwnd = Some(parse_hwnd(&value_of(&arg, attached, &mut args)?)?),
+            _ => return Err(format!("Unexpected argument '{arg}'")),
+        }
+    }
+
+    if let Command::Apply(options) = &command
+        && options.matcher.is_empty()
+    {
+        return Err(
+            "apply needs at least one of --title, --class, --exe, --pid or --hwnd".to_owned(),
+        );
+    }
+
+    Ok(Some(command))
+}
+
+/// Function to get the value of an option
+/// # Arguments
+/// * `option` - The option (e.g. `--title`)
+/// * `attached` - The value attached to the option with `=`, if any, which may start with `--`
+/// * `args` - The remaining arguments, whose next one is the value if none was attached
+/// # Returns
+/// * The value, or an error message if the next argument is missing or is another option
+fn value_of(
+    option: &str,
+    attached: Option<String>,
+    args: &mut impl Iterator<Item = String>,
+) -> Result<String, String> {
+    attached.map_or_else(
+        || {
+            args.next()
+                .filter(|value| !value.starts_with("--"))
+                .ok_or_else(|| format!("{option} needs a value"))
+        },
+        Ok,
+    )
+}
+
+/// Function to parse a window handle
+/// # Arguments
+/// * `text` - The handle, in decimal or in hexadecimal with a `0x` prefix
+/// # Returns
+/// * The raw handle, or an error message
+pub fn parse_hwnd(text: &str) -> Result<isize, String> {
+    let result = text
+        .strip_prefix("0x")
+        .or_else(|| text.strip_prefix("0X"))
+        .map_or_else(|| text.parse(), |hex| isize::from_str_radix(hex, 16));
+
+    result.map_err(|e| format!("Invalid window handle '{text}': {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<Command>, String> {
+        parse_args(args.iter().map(|&arg| arg.to_owned()))
+    }
+
+    fn game_window() -> WindowInfo {
+        WindowInfo {
+            hwnd: 0x0002_0A3C,
+            title: "Super Game - Launcher".to_owned(),
+            class_name: "UnityWndClass".to_owned(),
+            process_id: 4242,
+            exe_path: Some(r"C:\Games\Super Game\SuperGame.exe".to_owned()),
+            visible: true,
+            ..WindowInfo::default()
+        }
+    }
+
+    #[test]
+    fn no_arguments_shows_the_window() {
+        assert_eq!(parse(&[]), Ok(None));
+    }
+
+    #[test]
+    fn parses_list_options() {
+        assert_eq!(
+            parse(&["list", "--ndjson", "--all"]),
+            Ok(Some(Command::List(ListOptions {
+                format: OutputFormat::Ndjson,
+                all: true,
+            })))
+        );
+        assert!(parse(&["list", "--title", "x"]).is_err());
+    }
+
+    #[test]
+    fn parses_apply_options() {
+        let command = parse(&[
+            "apply",
+            "--exe",
+            "game",
+            "--monitor",
+            "DISPLAY2",
+            "--scale",
+            "FIT",
+            "--hwnd",
+            "0x2A",
+            "--topmost",
+            "--work-area",
+        ]);
+        assert_eq!(
+            command,
+            Ok(Some(Command::Apply(ApplyOptions {
+                matcher: WindowMatcher {
+                    exe: Some("game".to_owned()),
+                    hwnd: Some(42),
+                    ..WindowMatcher::default()
+                },
+                monitor: Some("DISPLAY2".to_owned()),
+                scale: Some(ScaleMode::Fit),
+                work_area: true,
+                topmost: true,
+                ..ApplyOptions::default()
+            })))
+        );
+    }
+
+    #[test]
+    fn apply_needs_a_matcher() {
+        assert!(parse(&["apply", "--topmost"]).is_err());
+        assert_eq!(
+            parse(&["restore"]),
+            Ok(Some(Command::Restore(WindowMatcher::default())))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        assert!(parse(&["fullscreen"]).is_err());
+        assert!(parse(&["watch", "--all"]).is_err());
+        assert!(parse(&["apply", "--pid", "abc"]).is_err());
+        assert!(parse(&["apply", "--hwnd", "0xZZ"]).is_err());
+        assert!(parse(&["apply", "--exe", "game", "--scale", "huge"]).is_err());
+        assert!(parse(&["apply", "--exe"]).is_err());
+        assert!(parse(&["apply", "--topmost=yes", "--exe", "game"]).is_err());
+    }
+
+    #[test]
+    fn values_starting_with_dashes_must_be_attached() {
+        assert_eq!(
+            parse(&["restore", "--title", "--foo"]),
+            Err("--title needs a value".to_owned())
+        );
+        assert_eq!(
+            parse(&["restore", "--title=--foo", "--exe=game.exe"]),
+            Ok(Some(Command::Restore(WindowMatcher {
+                title: Some("--foo".to_owned()),
+                exe: Some("game.exe".to_owned()),
+                ..WindowMatcher::default()
+            })))
+        );
+        // Only the first equals sign separates the value
+        assert_eq!(
+            parse(&["restore", "--title=a=b"]),
+            Ok(Some(Command::Restore(WindowMatcher {
+                title: Some("a=b".to_owned()),
+                ..WindowMatcher::default()
+            })))
+        );
+    }
+
+    #[test]
+    fn parses_window_handles() {
+        assert_eq!(parse_hwnd("1234"), Ok(1234));
+        assert_eq!(parse_hwnd("0x1A2b"), Ok(0x1A2B));
+        assert_eq!(parse_hwnd("0XFF"), Ok(255));
+        assert!(parse_hwnd("").is_err());
+    }
+
+    #[test]
+    fn matches_every_given_criterion() {
+        let window = game_window();
+        let matcher = WindowMatcher {
+            title: Some("super GAME".to_owned()),
+            class: Some("unitywndclass".to_owned()),
+            exe: Some("supergame".to_owned()),
+            pid: Some(4242),
+            hwnd: Some(0x0002_0A3C),
+        };
+        assert!(matcher.matches(&window));
+        assert!(WindowMatcher::default().matches(&window));
+
+        let other_pid = WindowMatcher {
+            pid: Some(1),
+            ..matcher.clone()
+        };
+        assert!(!other_pid.matches(&window));
+        let partial_class = WindowMatcher {
+            class: Some("Unity".to_owned()),
+            ..WindowMatcher::default()
+        };
+        assert!(!partial_class.matches(&window));
+    }
+
+    #[test]
+    fn exe_criterion_needs_a_known_executable() {
+        let window = WindowInfo {
+            exe_path: None,
+            ..game_window()
+        };
+        let matcher = WindowMatcher {
+            exe: Some("SuperGame.exe".to_owned()),
+            ..WindowMatcher::default()
+        };
+        assert!(matcher.matches(&game_window()));
+        assert!(!matcher.matches(&window));
+    }
+}
@@ -0,0 +1,480 @@
+use core::fmt;
+use core::str::FromStr;
+use regex::{Regex, RegexBuilder};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Titles of system windows that are not listed unless the user changes the exclusions
+pub const DEFAULT_EXCLUDED_TITLES: [&str; 5] = [
+    "Program Manager",
+    "Windows Input Experience",
+    "PopupHost",
+    "System tray overflow window.",
+    "Task Switching",
+];
+
+/// Explanation of the exclusion format shown in the exclusion editor
+#[cfg_attr(not(windows), allow(dead_code))]
+pub const EXCLUSIONS_HELP: &str = "\
+One exclusion per line: <title|class|exe> <exact|glob|regex> <pattern>
+Matches ignore case. Globs support * and ?. Lines starting with # are ignored.
+Examples:
+    exe exact Discord.exe
+    title glob *Launcher*
+    class regex ^Chrome_WidgetWin_\\d+$
+";
+
+/// The property of a window an exclusion is checked against
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExclusionField {
+    /// The title of the window
+    Title,
+    /// The class name of the window
+    Class,
+    /// The file name of the executable that owns the window (e.g. `game.exe`)
+    Exe,
+}
+
+/// How the pattern of an exclusion is matched
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExclusionKind {
+    /// The whole value equals the pattern
+    Exact,
+    /// The whole value matches the pattern, where `*` matches any text and `?` any single character
+    Glob,
+    /// The value contains a match of the regular expression
+    Regex,
+}
+
+/// A single user-editable exclusion, as written in the exclusions file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExclusionRule {
+    pub field: ExclusionField,
+    pub kind: ExclusionKind,
+    pub pattern: String,
+}
+
+impl ExclusionRule {
+    pub const fn new(field: ExclusionField, kind: ExclusionKind, pattern: String) -> Self {
+        Self {
+            field,
+            kind,
+            pattern,
+        }
+    }
+}
+
+impl fmt::Display for ExclusionField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Title => "title",
+            Self::Class => "class",
+            Self::Exe => "exe",
+        })
+    }
+}
+
+impl FromStr for ExclusionField {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "title" => Ok(Self::Title),
+            "class" => Ok(Self::Class),
+            "exe" => Ok(Self::Exe),
+            _ => Err(format!(
+                "Unknown field '{s}', expected 'title', 'class' or 'exe'"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ExclusionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Exact => "exact",
+            Self::Glob => "glob",
+            Self::Regex => "regex",
+        })
+    }
+}
+
+impl FromStr for ExclusionKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "exact" => Ok(Self::Exact),
+            "glob" => Ok(Self::Glob),
+            "regex" => Ok(Self::Regex),
+            _ => Err(format!(
+                "Unknown match kind '{s}', expected 'exact', 'glob' or 'regex'"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ExclusionRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.field, self.kind, self.pattern)
+    }
+}
+
+impl FromStr for ExclusionRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().splitn(3, char::is_whitespace);
+        let field = parts.next().unwrap_or_default().parse()?;
+        let kind = parts
+            .next()
+            .ok_or_else(|| "Missing match kind".to_owned())?
+            .parse()?;
+        let pattern = parts.next().map(str::trim).unwrap_or_default();
+        if pattern.is_empty() {
+            return Err("Missing pattern".to_owned());
+        }
+
+        Ok(Self::new(field, kind, pattern.to_owned()))
+    }
+}
+
+/// A compiled exclusion pattern
+#[derive(Clone, Debug)]
+enum Pattern {
+    /// The lowercased text the value must equal
+    Exact(String),
+    /// A case-insensitive regular expression, also used for globs
+    Regex(Regex),
+}
+
+impl Pattern {
+    fn matches(&self, value: &str) -> bool {
+        match self {
+            Self::Exact(text) => value.to_lowercase() == *text,
+            Self::Regex(regex) => regex.is_match(value),
+        }
+    }
+}
+
+/// The exclusions that decide which windows are hidden from the window list
+#[derive(Clone, Debug)]
+pub struct ExclusionList {
+    rules: Vec<ExclusionRule>,
+    patterns: Vec<(ExclusionField, Pattern)>,
+}
+
+impl Default for ExclusionList {
+    /// The default exclusions hide the system windows listed in [`DEFAULT_EXCLUDED_TITLES`]
+    fn default() -> Self {
+        let rules: Vec<ExclusionRule> = DEFAULT_EXCLUDED_TITLES
+            .iter()
+            .map(|title| {
+                ExclusionRule::new(
+                    ExclusionField::Title,
+                    ExclusionKind::Exact,
+                    (*title).to_owned(),
+                )
+            })
+            .collect();
+        let patterns = rules
+            .iter()
+            .map(|rule| (rule.field, Pattern::Exact(rule.pattern.to_lowercase())))
+            .collect();
+
+        Self { rules, patterns }
+    }
+}
+
+impl ExclusionList {
+    /// Function to parse the exclusions file format
+    /// # Arguments
+    /// * `text` - One exclusion per line, blank lines and lines starting with `#` are ignored
+    /// # Returns
+    /// * The exclusion list, or an error message naming the first invalid line
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut list = Self {
+            rules: Vec::new(),
+            patterns: Vec::new(),
+        };
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            line.parse()
+                .and_then(|rule| list.add(rule))
+                .map_err(|e| format!("Line {}: {e}", index + 1))?;
+        }
+
+        Ok(list)
+    }
+
+    /// Function to add an exclusion, unless an identical one already exists
+    /// # Arguments
+    /// * `rule` - The exclusion
+    /// # Returns
+    /// * An error message if the pattern is invalid
+    pub fn add(&mut self, rule: ExclusionRule) -> Result<(), String> {
+        if self.rules.contains(&rule) {
+            return Ok(());
+        }
+
+        self.patterns.push((rule.field, compile(&rule)?));
+        self.rules.push(rule);
+        Ok(())
+    }
+
+    /// Function to write the exclusions in the exclusions file format, without the help comment
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn to_text(&self) -> String {
+        self.rules.iter().map(|rule| format!("{rule}\n")).collect()
+    }
+
+    /// Function to check whether any exclusion needs the executable name of a window
+    ///
+    /// Querying the executable requires opening the owning process, so callers skip it when it is not needed.
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn uses_exe(&self) -> bool {
+        self.rules
+            .iter()
+            .any(|rule| rule.field == ExclusionField::Exe)
+    }
+
+    /// Function to check whether a window is hidden by any exclusion
+    /// # Arguments
+    /// * `title` - The title of the window
+    /// * `class_name` - The class name of the window
+    /// * `exe_name` - The file name of the executable that owns the window, or `None` if it is unknown
+    /// # Returns
+    /// * `true` if an exclusion matches the window
+    pub fn is_excluded(&self, title: &str, class_name: &str, exe_name: Option<&str>) -> bool {
+        self.patterns.iter().any(|(field, pattern)| match field {
+            ExclusionField::Title => pattern.matches(title),
+            ExclusionField::Class => pattern.matches(class_name),
+            ExclusionField::Exe => exe_name.is_some_and(|exe_name| pattern.matches(exe_name)),
+        })
+    }
+
+    /// Function to check whether a window should be listed
+    /// # Arguments
+    /// * `title` - The title of the window
+    /// * `class_name` - The class name of the window
+    /// * `exe_name` - The file name of the executable that owns the window, or `None` if it is unknown
+    /// # Returns
+    /// * `true` if the window has a title and is not excluded
+    pub fn is_listed(&self, title: &str, class_name: &str, exe_name: Option<&str>) -> bool {
+        !title.is_empty() && !self.is_excluded(title, class_name, exe_name)
+    }
+}
+
+/// The exclusions are stored in the settings file as a list of lines in the exclusion format
+impl Serialize for ExclusionList {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.rules.iter().map(ToString::to_string))
+    }
+}
+
+impl<'de> Deserialize<'de> for ExclusionList {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut list = Self {
+            rules: Vec::new(),
+            patterns: Vec::new(),
+        };
+
+        for line in Vec::<String>::deserialize(deserializer)? {
+            line.parse()
+                .and_then(|rule| list.add(rule))
+                .map_err(|e| D::Error::custom(format!("Invalid exclusion '{line}': {e}")))?;
+        }
+
+        Ok(list)
+    }
+}
+
+/// Function to compile the pattern of an exclusion
+fn compile(rule: &ExclusionRule) -> Result<Pattern, String> {
+    let regex = match rule.kind {
+        ExclusionKind::Exact => return Ok(Pattern::Exact(rule.pattern.to_lowercase())),
+        ExclusionKind::Glob => glob_to_regex(&rule.pattern),
+        ExclusionKind::Regex => rule.pattern.clone(),
+    };
+
+    RegexBuilder::new(&regex)
+        .case_insensitive(true)
+        .build()
+        .map(Pattern::Regex)
+        .map_err(|e| format!("Invalid pattern '{}': {e}", rule.pattern))
+}
+
+/// Function to convert a glob into an equivalent anchored regular expression
+/// # Arguments
+/// * `glob` - The glob, where `*` matches any text and `?` any single character
+/// # Returns
+/// * The regular expression
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    let mut literal = String::new();
+
+    for c in glob.chars() {
+        if c == '*' || c == '?' {
+            regex.push_str(&regex::escape(&literal));
+            literal.clear();
+            regex.push_str(if c == '*' { ".*" } else { "." });
+        } else {
+            literal.push(c);
+        }
+    }
+    regex.push_str(&regex::escape(&literal));
+    regex.push('$');
+
+    regex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(text: &str) -> ExclusionList {
+        ExclusionList::parse(text).unwrap()
+    }
+
+    #[test]
+    fn rules_are_parsed_and_written_back() {
+        let rule: ExclusionRule = "  TITLE Glob   *My Launcher*  ".parse().unwrap();
+        assert_eq!(
+            rule,
+            ExclusionRule::new(
+                ExclusionField::Title,
+                ExclusionKind::Glob,
+                "*My Launcher*".to_owned()
+            )
+        );
+        assert_eq!(rule.to_string(), "title glob *My Launcher*");
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected() {
+        assert!("".parse::<ExclusionRule>().is_err());
+        assert!("name exact Game".parse::<ExclusionRule>().is_err());
+        assert!("title".parse::<ExclusionRule>().is_err());
+        assert!("title starts Game".parse::<ExclusionRule>().is_err());
+        assert_eq!(
+            "title exact   ".parse::<ExclusionRule>(),
+            Err("Missing pattern".to_owned())
+        );
+    }
+
+    #[test]
+    fn exact_rules_match_the_whole_value_ignoring_case() {
+        let exclusions = list("title exact Steam\nclass exact ConsoleWindowClass");
+        assert!(exclusions.is_excluded("STEAM", "", None));
+        assert!(!exclusions.is_excluded("Steam Friends", "", None));
+        assert!(exclusions.is_excluded("Terminal", "consolewindowclass", None));
+        assert!(!exclusions.is_excluded("Terminal", "ConsoleWindowClass2", None));
+    }
+
+    #[test]
+    fn glob_rules_match_the_whole_value() {
+        let exclusions = list("title glob *launcher*\nexe glob game?.exe");
+        assert!(exclusions.is_excluded("Epic Games Launcher", "", None));
+        assert!(!exclusions.is_excluded("Game", "", None));
+        assert!(exclusions.is_excluded("Game", "", Some("GAME2.EXE")));
+        assert!(!exclusions.is_excluded("Game", "", Some("game.exe")));
+        assert!(!exclusions.is_excluded("Game", "", Some("game10.exe")));
+    }
+
+    #[test]
+    fn globs_escape_regex_metacharacters() {
+        assert_eq!(glob_to_regex("a.b*(c)?"), r"^a\.b.*\(c\).$");
+
+        let exclusions = list("title glob [Debug] v1.0 (*)\nexe glob setup+.exe");
+        assert!(exclusions.is_excluded("[Debug] v1.0 (x64)", "", None));
+        assert!(!exclusions.is_excluded("D v1x0 (x64)", "", None));
+        assert!(exclusions.is_excluded("Installer", "", Some("setup+.exe")));
+        assert!(!exclusions.is_excluded("Installer", "", Some("setuppp.exe")));
+    }
+
+    #[test]
+    fn regex_rules_match_anywhere_ignoring_case() {
+        let exclusions = list(r"class regex ^chrome_widgetwin_\d+$");
+        assert!(exclusions.is_excluded("Browser", "Chrome_WidgetWin_1", None));
+        assert!(!exclusions.is_excluded("Browser", "Chrome_WidgetWin_", None));
+
+        let exclusions = list("title regex overlay");
+        assert!(exclusions.is_excluded("Game Bar OVERLAY window", "", None));
+    }
+
+    #[test]
+    fn comments_and_blank_lines_are_ignored() {
+        let exclusions = list("# Launchers\n\n   \n  # indented comment\ntitle exact Launcher\n");
+        assert_eq!(exclusions.to_text(), "title exact Launcher\n");
+        assert!(list("# only a comment\n").to_text().is_empty());
+    }
+
+    #[test]
+    fn invalid_lines_are_reported_with_their_number() {
+        let error = ExclusionList::parse("# Comment\ntitle exact Game\n\ntitle regex (unclosed")
+            .unwrap_err();
+        assert!(
+            error.starts_with("Line 4: Invalid pattern '(unclosed'"),
+            "{error}"
+        );
+
+        let error = ExclusionList::parse("title exact Game\nwindow exact Game").unwrap_err();
+        assert!(
+            error.starts_with("Line 2: Unknown field 'window'"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn duplicate_rules_are_added_once() {
+        let mut exclusions = list("title exact Game");
+        exclusions.add("title exact Game".parse().unwrap()).unwrap();
+        assert_eq!(exclusions.to_text(), "title exact Game\n");
+    }
+
+    #[test]
+    fn exe_rules_are_skipped_without_an_exe_name() {
+        let exclusions = list("exe exact game.exe");
+        assert!(exclusions.uses_exe());
+        assert!(exclusions.is_excluded("Game", "", Some("Game.exe")));
+        assert!(!exclusions.is_excluded("Game", "", None));
+
+        assert!(!list("title exact Game\nclass glob *").uses_exe());
+        assert!(!ExclusionList::default().uses_exe());
+    }
+
+    #[test]
+    fn default_exclusions_hide_system_windows() {
+        let exclusions = ExclusionList::default();
+        assert!(!exclusions.is_listed("Program Manager", "Progman", None));
+        assert!(!exclusions.is_listed("", "UnityWndClass", None));
+        assert!(exclusions.is_listed("Game", "UnityWndClass", None));
+    }
+
+    #[test]
+    fn serialize_round_trips() {
+        let exclusions = list("title exact Launcher\nexe glob *.tmp.exe\nclass regex ^Qt\\d+");
+        let json = serde_json::to_string(&exclusions).unwrap();
+        assert_eq!(
+            json,
+            r#"["title exact Launcher","exe glob *.tmp.exe","class regex ^Qt\\d+"]"#
+        );
+
+        let read: ExclusionList = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.to_text(), exclusions.to_text());
+        assert!(read.is_excluded("Editor", "Qt5QWindowIcon", None));
+
+        let error = serde_json::from_str::<ExclusionList>(r#"["title regex ("]"#).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Invalid exclusion 'title regex ('"),
+            "{error}"
+        );
+    }
+}
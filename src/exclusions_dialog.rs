@@ -0,0 +1,178 @@
+extern crate alloc;
+
+use alloc::sync::Arc;
+use std::sync::Mutex;
+
+use winsafe::IconRes;
+use winsafe::co::{ES, SS, TDCBF, WS};
+use winsafe::gui::{
+    Button, ButtonOpts, Edit, EditOpts, Label, LabelOpts, WindowModal, WindowModalOpts, dpi, dpi_x,
+    dpi_y,
+};
+use winsafe::prelude::{GuiEventsButton as _, GuiEventsWindow as _, GuiParent, GuiWindow as _};
+
+use crate::exclusions::{EXCLUSIONS_HELP, ExclusionList};
+use crate::locks::lock;
+use crate::theme;
+
+/// A modal window to edit the exclusions as text, one exclusion per line
+#[derive(Clone)]
+pub struct ExclusionsDialog {
+    wnd: WindowModal,
+    editor: Edit,
+    ok_btn: Button,
+    cancel_btn: Button,
+
+    dark_mode: bool,
+    // The exclusions accepted by the user, or `None` if the dialog was cancelled
+    result: Arc<Mutex<Option<ExclusionList>>>,
+}
+
+impl ExclusionsDialog {
+    /// Function to create the dialog
+    /// # Arguments
+    /// * `exclusions` - The exclusions initially shown in the editor
+    /// * `dark_mode` - Whether to use the dark theme
+    pub fn new(exclusions: &ExclusionList, dark_mode: bool) -> Self {
+        let wnd = WindowModal::new(WindowModalOpts {
+            title: "Exclusions",
+            size: dpi(460, 360),
+            ..Default::default()
+        });
+        theme::paint_modal(&wnd, dark_mode);
+
+        Label::new(
+            &wnd,
+            LabelOpts {
+                text: EXCLUSIONS_HELP,
+                position: dpi(10, 9),
+                size: dpi(440, 100),
+                control_style: SS::LEFT,
+                ..Default::default()
+            },
+        );
+
+        // The edit control expects Windows line endings
+        let text = exclusions.to_text().replace('\n', "\r\n");
+        let editor = Edit::new(
+            &wnd,
+            EditOpts {
+                text: &text,
+                position: dpi(10, 114),
+                width: dpi_x(440),
+                height: dpi_y(200),
+                control_style: ES::MULTILINE | ES::WANTRETURN | ES::AUTOVSCROLL | ES::AUTOHSCROLL,
+                window_style: WS::CHILD
+                    | WS::VISIBLE
+                    | WS::TABSTOP
+                    | WS::GROUP
+                    | WS::VSCROLL
+                    | WS::HSCROLL,
+                ..Default::default()
+            },
+        );
+
+        let ok_btn = Button::new(
+            &wnd,
+            ButtonOpts {
+                text: "&OK",
+                position: dpi(268, 324),
+                ..Default::default()
+            },
+        );
+
+        let cancel_btn = Button::new(
+            &wnd,
+            ButtonOpts {
+                text: "&Cancel",
+                position: dpi(362, 324),
+                ..Default::default()
+            },
+        );
+
+        let new_self = Self {
+            wnd,
+            editor,
+            ok_btn,
+            cancel_btn,
+            dark_mode,
+            result: Arc::new(Mutex::new(None)),
+        };
+
+        new_self.events();
+        new_self
+    }
+
+    /// Function to show the dialog and wait for it to be closed
+    /// # Arguments
+    /// * `parent` - The window that owns the dialog
+    /// # Returns
+    /// * The edited exclusions, or `None` if the dialog was cancelled
+    pub fn show(&self, parent: &impl GuiParent) -> Option<ExclusionList> {
+        self.wnd
+            .show_modal(parent)
+            .map_err(|e| eprintln!("Failed to show the exclusions dialog: {e}"))
+            .ok()?;
+
+        lock(&self.result).take()
+    }
+
+    fn events(&self) {
+        self.wnd.on().wm_create({
+            let self2 = self.clone();
+            move |_| {
+                theme::apply_theme(
+                    self2.wnd.hwnd(),
+                    &[
+                        self2.editor.hwnd(),
+                        self2.ok_btn.hwnd(),
+                        self2.cancel_btn.hwnd(),
+                    ],
+                    &[],
+                    self2.dark_mode,
+                );
+
+                Ok(0)
+            }
+        });
+
+        self.ok_btn.on().bn_clicked({
+            let self2 = self.clone();
+            move || {
+                let text = self2.editor.hwnd().GetWindowText()?;
+
+                match ExclusionList::parse(&text) {
+                    Ok(exclusions) => {
+                        *lock(&self2.result) = Some(exclusions);
+                        self2.wnd.close();
+                    }
+                    // Keep the dialog open so that the mistake can be fixed
+                    Err(e) => {
+                        self2
+                            .wnd
+                            .hwnd()
+                            .TaskDialog(
+                                Some("Invalid exclusions"),
+                                None,
+                                Some(&e),
+                                TDCBF::OK,
+                                IconRes::Error,
+                            )
+                            .map_err(|e| eprintln!("TaskDialog failed: {e}"))
+                            .ok();
+                    }
+                }
+
+                Ok(())
+            }
+        });
+
+        self.cancel_btn.on().bn_clicked({
+            let self2 = self.clone();
+            move || {
+                self2.wnd.close();
+                Ok(())
+            }
+        });
+    }
+}
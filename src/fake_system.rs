@@ -0,0 +1,291 @@
+use core::cell::RefCell;
+
+use crate::geometry::Rect;
+use crate::monitors::{Monitor, MonitorChoice, MonitorList};
+use crate::window_state::SavedPlacement;
+use crate::window_system::{TOPMOST_EX_STYLE, WindowSystem, ZOrder};
+
+/// `WS_OVERLAPPEDWINDOW | WS_VISIBLE`, the style of a new fake window
+const DEFAULT_STYLE: u32 = 0x00CF_0000 | 0x1000_0000;
+
+/// `WS_THICKFRAME` style bit, deciding whether a fake window has a border
+const THICKFRAME_STYLE: u32 = 0x0004_0000;
+
+/// Thickness of the border of a fake window, in pixels
+pub const BORDER_THICKNESS: i32 = 8;
+
+/// A window of the [`FakeSystem`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FakeWindow {
+    pub hwnd: isize,
+    pub title: String,
+    pub class_name: String,
+    pub process_id: u32,
+    pub exe_path: Option<String>,
+    pub rect: Rect,
+    pub style: u32,
+    pub ex_style: u32,
+    pub visible: bool,
+    pub placement: SavedPlacement,
+    pub icon: Option<u32>,
+    /// Whether the window was activated when it was last moved
+    pub activated: bool,
+}
+
+impl FakeWindow {
+    /// Function to create a visible, bordered window
+    /// # Arguments
+    /// * `hwnd` - The raw handle of the window
+    /// * `title` - The title of the window
+    /// * `exe_path` - The full path of the executable owning the window
+    pub fn new(hwnd: isize, title: &str, exe_path: &str) -> Self {
+        let rect = Rect::new(100, 100, 900, 700);
+
+        Self {
+            hwnd,
+            title: title.to_owned(),
+            class_name: "FakeWindowClass".to_owned(),
+            process_id: hwnd as u32,
+            exe_path: Some(exe_path.to_owned()),
+            rect,
+            style: DEFAULT_STYLE,
+            ex_style: 0,
+            visible: true,
+            placement: SavedPlacement {
+                normal_rect: rect,
+                ..SavedPlacement::default()
+            },
+            icon: None,
+            activated: false,
+        }
+    }
+}
+
+/// An in-memory window system, so that the window operations can be run without a desktop
+///
+/// Windows are kept in z-order, the first one being at the top.
+#[derive(Debug, Default)]
+pub struct FakeSystem {
+    windows: RefCell<Vec<FakeWindow>>,
+    monitors: Vec<Monitor>,
+}
+
+impl FakeSystem {
+    pub const fn new(monitors: Vec<Monitor>) -> Self {
+        Self {
+            windows: RefCell::new(Vec::new()),
+            monitors,
+        }
+    }
+
+    /// Function to open a window below the existing ones
+    pub fn add_window(&self, window: FakeWindow) {
+        self.windows.borrow_mut().push(window);
+    }
+
+    /// Function to close a window
+    pub fn close_window(&self, hwnd: isize) {
+        self.windows
+            .borrow_mut()
+            .retain(|window| window.hwnd != hwnd);
+    }
+
+    /// Function to show or hide a window
+    pub fn set_visible(&self, hwnd: isize, visible: bool) {
+        self.modify(hwnd, |window| window.visible = visible).ok();
+    }
+
+    /// Function to get a copy of a window, to check its state
+    pub fn window(&self, hwnd: isize) -> Option<FakeWindow> {
+        self.windows
+            .borrow()
+            .iter()
+            .find(|window| window.hwnd == hwnd)
+            .cloned()
+    }
+
+    /// Function to read a property of a window
+    /// # Returns
+    /// * The property, or an error message if the window does not exist
+    fn read<T>(&self, hwnd: isize, read: impl FnOnce(&FakeWindow) -> T) -> Result<T, String> {
+        self.windows
+            .borrow()
+            .iter()
+            .find(|window| window.hwnd == hwnd)
+            .map(read)
+            .ok_or_else(|| format!("There is no window {hwnd:#x}"))
+    }
+
+    /// Function to change a window
+    /// # Returns
+    /// * An error message if the window does not exist
+    fn modify(&self, hwnd: isize, modify: impl FnOnce(&mut FakeWindow)) -> Result<(), String> {
+        self.windows
+            .borrow_mut()
+            .iter_mut()
+            .find(|window| window.hwnd == hwnd)
+            .map(modify)
+            .ok_or_else(|| format!("There is no window {hwnd:#x}"))
+    }
+}
+
+impl WindowSystem for FakeSystem {
+    type Icon = u32;
+
+    fn windows(&self) -> Vec<isize> {
+        self.windows
+            .borrow()
+            .iter()
+            .map(|window| window.hwnd)
+            .collect()
+    }
+
+    fn is_window(&self, hwnd: isize) -> bool {
+        self.read(hwnd, |_| ()).is_ok()
+    }
+
+    fn is_visible(&self, hwnd: isize) -> bool {
+        self.read(hwnd, |window| window.visible).unwrap_or(false)
+    }
+
+    fn title(&self, hwnd: isize) -> Result<String, String> {
+        self.read(hwnd, |window| window.title.clone())
+    }
+
+    fn class_name(&self, hwnd: isize) -> Result<String, String> {
+        self.read(hwnd, |window| window.class_name.clone())
+    }
+
+    fn process_id(&self, hwnd: isize) -> u32 {
+        self.read(hwnd, |window| window.process_id).unwrap_or(0)
+    }
+
+    fn exe_path(&self, hwnd: isize) -> Option<String> {
+        self.read(hwnd, |window| window.exe_path.clone())
+            .ok()
+            .flatten()
+    }
+
+    fn rect(&self, hwnd: isize) -> Result<Rect, String> {
+        self.read(hwnd, |window| window.rect)
+    }
+
+    fn client_size(&self, hwnd: isize) -> Result<(i32, i32), String> {
+        self.read(hwnd, |window| {
+            let border = if window.style & THICKFRAME_STYLE == 0 {
+                0
+            } else {
+                BORDER_THICKNESS
+            };
+            (
+                window.rect.width() - 2 * border,
+                window.rect.height() - 2 * border,
+            )
+        })
+    }
+
+    fn monitor_name(&self, hwnd: isize) -> Option<String> {
+        let rect = self.rect(hwnd).ok()?;
+
+        MonitorList::new(self.monitors.clone())
+            .resolve(&MonitorChoice::Nearest, rect)
+            .map(|monitor| monitor.device_name.clone())
+    }
+
+    fn style(&self, hwnd: isize) -> u32 {
+        self.read(hwnd, |window| window.style).unwrap_or(0)
+    }
+
+    fn ex_style(&self, hwnd: isize) -> u32 {
+        self.read(hwnd, |window| window.ex_style).unwrap_or(0)
+    }
+
+    fn set_style(&self, hwnd: isize, style: u32) {
+        self.modify(hwnd, |window| window.style = style).ok();
+    }
+
+    fn set_ex_style(&self, hwnd: isize, ex_style: u32) {
+        self.modify(hwnd, |window| window.ex_style = ex_style).ok();
+    }
+
+    fn window_rect_for_client(&self, hwnd: isize, client: Rect) -> Result<Rect, String> {
+        let border = if self.read(hwnd, |window| window.style)? & THICKFRAME_STYLE == 0 {
+            0
+        } else {
+            BORDER_THICKNESS
+        };
+
+        Ok(Rect::new(
+            client.left - border,
+            client.top - border,
+            client.right + border,
+            client.bottom + border,
+        ))
+    }
+
+    fn set_position(
+        &self,
+        hwnd: isize,
+        rect: Option<Rect>,
+        z_order: ZOrder,
+        activate: bool,
+    ) -> Result<(), String> {
+        self.modify(hwnd, |window| {
+            if let Some(rect) = rect {
+                window.rect = rect;
+            }
+            match z_order {
+                ZOrder::Topmost => window.ex_style |= TOPMOST_EX_STYLE,
+                ZOrder::NoTopmost => window.ex_style &= !TOPMOST_EX_STYLE,
+                ZOrder::Top | ZOrder::After(_) => {}
+            }
+            window.activated = activate;
+        })?;
+
+        // Move the window in the z-order
+        let mut windows = self.windows.borrow_mut();
+        let Some(index) = windows.iter().position(|window| window.hwnd == hwnd) else {
+            return Ok(());
+        };
+        let window = windows.remove(index);
+        let insert_at = match z_order {
+            ZOrder::After(prev) => windows
+                .iter()
+                .position(|window| window.hwnd == prev)
+                .map_or(0, |prev| prev + 1),
+            ZOrder::Top | ZOrder::Topmost | ZOrder::NoTopmost => 0,
+        };
+        windows.insert(insert_at, window);
+
+        Ok(())
+    }
+
+    fn placement(&self, hwnd: isize) -> Result<SavedPlacement, String> {
+        self.read(hwnd, |window| window.placement)
+    }
+
+    fn set_placement(&self, hwnd: isize, placement: &SavedPlacement) -> Result<(), String> {
+        self.modify(hwnd, |window| {
+            window.placement = *placement;
+            window.rect = placement.normal_rect;
+        })
+    }
+
+    fn previous_window(&self, hwnd: isize) -> Option<isize> {
+        let windows = self.windows.borrow();
+        let index = windows.iter().position(|window| window.hwnd == hwnd)?;
+
+        index
+            .checked_sub(1)
+            .and_then(|prev| windows.get(prev))
+            .map(|window| window.hwnd)
+    }
+
+    fn monitors(&self) -> Vec<Monitor> {
+        self.monitors.clone()
+    }
+
+    fn icon(&self, hwnd: isize) -> Option<u32> {
+        self.read(hwnd, |window| window.icon).ok().flatten()
+    }
+}
@@ -0,0 +1,336 @@
+use serde::{Deserialize, Serialize};
+
+use crate::geometry::Rect;
+use crate::window_info::WindowInfo;
+
+/// Window classes of the windows created by game engines and libraries, and the name of the engine
+///
+/// Classes are matched ignoring case.
+pub const ENGINE_CLASSES: [(&str, &str); 15] = [
+    ("UnityWndClass", "Unity"),
+    ("UnrealWindow", "Unreal Engine"),
+    ("LaunchUnrealUWindowsClient", "Unreal Engine 3"),
+    ("SDL_app", "SDL"),
+    ("GLFW30", "GLFW"),
+    ("Valve001", "Source"),
+    ("SDL_Window", "Source 2"),
+    ("CryENGINE", "CryEngine"),
+    ("YYGameMakerYY", "GameMaker"),
+    ("RGSS Player", "RPG Maker"),
+    ("OgreD3D11Wnd", "Ogre"),
+    ("OgreGLWindow", "Ogre"),
+    ("LWJGL", "LWJGL"),
+    ("SFML_Window", "SFML"),
+    ("RiotWindowClass", "Riot"),
+];
+
+/// Window classes used by game engines that are too generic to recognize a game on their own, and the name of the engine
+///
+/// Godot names its window class `Engine`, which other applications may use too.
+pub const GENERIC_ENGINE_CLASSES: [(&str, &str); 1] = [("Engine", "Godot")];
+
+/// Folders games are installed in by their stores and launchers
+///
+/// Folders are matched anywhere in the path of the executable, ignoring case and the direction of slashes.
+pub const LIBRARY_FOLDERS: [&str; 9] = [
+    r"\steamapps\common\",
+    r"\Epic Games\",
+    r"\GOG Galaxy\Games\",
+    r"\GOG Games\",
+    r"\XboxGames\",
+    r"\Ubisoft Game Launcher\games\",
+    r"\EA Games\",
+    r"\Origin Games\",
+    r"\Riot Games\",
+];
+
+/// `WS_CAPTION` style bits
+const CAPTION_STYLE: u32 = 0x00C0_0000;
+/// `WS_THICKFRAME` style bit, which makes a window resizable
+const THICKFRAME_STYLE: u32 = 0x0004_0000;
+
+/// Weight of the evidence needed for a window to be considered a likely game
+const GAME_THRESHOLD: u32 = 2;
+
+/// Badge shown before the title of likely games in the window list
+pub const GAME_BADGE: &str = "\u{1F3AE}";
+
+/// A hint that a window belongs to a game
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Evidence {
+    /// The window class is used by a game engine, whose name is given
+    EngineClass(String),
+    /// The window class is used by a game engine, whose name is given, but also by other applications
+    GenericEngineClass(String),
+    /// The executable is installed in a game library folder, which is given
+    LibraryFolder(String),
+    /// The window has no border and covers a whole monitor
+    CoversMonitor,
+    /// The window cannot be resized, like games running at a fixed resolution
+    FixedSize,
+}
+
+impl Evidence {
+    /// Function to get how strongly the evidence points to a game
+    const fn weight(&self) -> u32 {
+        match self {
+            Self::EngineClass(_) | Self::LibraryFolder(_) => 2,
+            Self::GenericEngineClass(_) | Self::CoversMonitor | Self::FixedSize => 1,
+        }
+    }
+}
+
+/// The settings deciding which windows are considered likely games
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameDetection {
+    /// Whether to list likely games first, with a badge before their title
+    pub games_first: bool,
+    /// Window classes of games, in addition to [`ENGINE_CLASSES`]
+    pub classes: Vec<String>,
+    /// Folders games are installed in, in addition to [`LIBRARY_FOLDERS`]
+    pub folders: Vec<String>,
+}
+
+impl GameDetection {
+    /// Function to gather the hints that a window belongs to a game
+    /// # Arguments
+    /// * `window` - The window
+    /// * `monitor` - The rectangle of the monitor the window is on, or `None` if it is unknown
+    /// # Returns
+    /// * The hints found, which are empty for most applications
+    pub fn evidence(&self, window: &WindowInfo, monitor: Option<Rect>) -> Vec<Evidence> {
+        let mut evidence = Vec::new();
+
+        if let Some(engine) = self.engine(&window.class_name) {
+            evidence.push(Evidence::EngineClass(engine));
+        } else if let Some(engine) = find_engine(&GENERIC_ENGINE_CLASSES, &window.class_name) {
+            evidence.push(Evidence::GenericEngineClass(engine));
+        }
+        if let Some(folder) = window
+            .exe_path
+            .as_deref()
+            .and_then(|path| self.library_folder(path))
+        {
+            evidence.push(Evidence::LibraryFolder(folder));
+        }
+        if window.style & CAPTION_STYLE == 0 && monitor == Some(window.rect) {
+            evidence.push(Evidence::CoversMonitor);
+        }
+        if window.style & THICKFRAME_STYLE == 0 {
+            evidence.push(Evidence::FixedSize);
+        }
+
+        evidence
+    }
+
+    /// Function to check whether a window likely belongs to a game
+    /// # Arguments
+    /// * `window` - The window
+    /// * `monitor` - The rectangle of the monitor the window is on, or `None` if it is unknown
+    /// # Returns
+    /// * `true` if the hints found are strong enough, which a single engine class or library folder is,
+    ///   as is a window without a border covering its monitor that cannot be resized
+    pub fn is_likely_game(&self, window: &WindowInfo, monitor: Option<Rect>) -> bool {
+        self.evidence(window, monitor)
+            .iter()
+            .map(Evidence::weight)
+            .sum::<u32>()
+            >= GAME_THRESHOLD
+    }
+
+    /// Function to get the game engine that uses a window class
+    /// # Returns
+    /// * The name of the engine, or `custom` for the classes added in the settings, or `None` if the class is unknown
+    fn engine(&self, class_name: &str) -> Option<String> {
+        find_engine(&ENGINE_CLASSES, class_name).or_else(|| {
+            self.classes
+                .iter()
+                .any(|class| class.eq_ignore_ascii_case(class_name))
+                .then(|| "custom".to_owned())
+        })
+    }
+
+    /// Function to get the game library folder an executable is installed in
+    /// # Returns
+    /// * The folder, as listed in [`LIBRARY_FOLDERS`] or the settings, or `None` if it is not in a library folder
+    fn library_folder(&self, exe_path: &str) -> Option<String> {
+        let path = normalize_path(exe_path);
+
+        LIBRARY_FOLDERS
+            .iter()
+            .copied()
+            .chain(self.folders.iter().map(String::as_str))
+            .find(|folder| path.contains(&folder_pattern(folder)))
+            .map(str::to_owned)
+    }
+}
+
+/// Function to find the game engine using a window class in a table of classes
+/// # Arguments
+/// * `classes` - Window classes and the name of the engine using them
+/// * `class_name` - The window class, matched ignoring case
+/// # Returns
+/// * The name of the engine, or `None` if the class is not in the table
+fn find_engine(classes: &[(&str, &str)], class_name: &str) -> Option<String> {
+    classes
+        .iter()
+        .find(|(class, _)| class.eq_ignore_ascii_case(class_name))
+        .map(|(_, engine)| (*engine).to_owned())
+}
+
+/// Function to put a path in a form where folders can be found with a substring search
+/// # Returns
+/// * The lowercase path, with backslashes only
+fn normalize_path(path: &str) -> String {
+    path.to_lowercase().replace('/', "\\")
+}
+
+/// Function to get the text a path must contain to be inside a folder
+/// # Arguments
+/// * `folder` - A folder name or path, with or without surrounding slashes (e.g. `D:\Games` or `\steamapps\common\`)
+/// # Returns
+/// * The normalized folder, surrounded by backslashes unless it starts with a drive letter
+fn folder_pattern(folder: &str) -> String {
+    let folder = normalize_path(folder);
+    let folder = folder.trim_end_matches('\\');
+
+    if folder.starts_with('\\') || folder.contains(':') {
+        format!("{folder}\\")
+    } else {
+        format!("\\{folder}\\")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONITOR: Rect = Rect::new(0, 0, 1920, 1080);
+    /// `WS_OVERLAPPEDWINDOW`, the style of a bordered, resizable window
+    const BORDERED: u32 = 0x00CF_0000;
+    /// `WS_POPUP`, the style of a window without a border
+    const BORDERLESS: u32 = 0x8000_0000;
+
+    fn window(class_name: &str, exe_path: &str, style: u32, rect: Rect) -> WindowInfo {
+        WindowInfo {
+            class_name: class_name.to_owned(),
+            exe_path: Some(exe_path.to_owned()),
+            style,
+            rect,
+            ..WindowInfo::default()
+        }
+    }
+
+    fn app(class_name: &str) -> WindowInfo {
+        window(
+            class_name,
+            r"C:\Program Files\App\app.exe",
+            BORDERED,
+            Rect::new(100, 100, 900, 700),
+        )
+    }
+
+    #[test]
+    fn engine_classes_are_matched_ignoring_case() {
+        let detection = GameDetection::default();
+        assert_eq!(
+            detection.evidence(&app("unitywndclass"), Some(MONITOR)),
+            vec![Evidence::EngineClass("Unity".to_owned())]
+        );
+        assert!(detection.is_likely_game(&app("UnityWndClass"), Some(MONITOR)));
+        assert!(!detection.is_likely_game(&app("Chrome_WidgetWin_1"), Some(MONITOR)));
+    }
+
+    #[test]
+    fn generic_engine_classes_are_weak_evidence() {
+        let detection = GameDetection::default();
+        assert_eq!(
+            detection.evidence(&app("Engine"), Some(MONITOR)),
+            vec![Evidence::GenericEngineClass("Godot".to_owned())]
+        );
+        assert!(!detection.is_likely_game(&app("Engine"), Some(MONITOR)));
+
+        // A fixed size window of the class is a likely game
+        let fixed = window(
+            "Engine",
+            r"C:\Tools\tool.exe",
+            CAPTION_STYLE,
+            Rect::new(0, 0, 1280, 720),
+        );
+        assert!(detection.is_likely_game(&fixed, Some(MONITOR)));
+    }
+
+    #[test]
+    fn custom_classes_are_strong_evidence() {
+        let detection = GameDetection {
+            classes: vec!["MyEngineWindow".to_owned(), "Engine".to_owned()],
+            ..GameDetection::default()
+        };
+        assert_eq!(
+            detection.evidence(&app("myenginewindow"), None),
+            vec![Evidence::EngineClass("custom".to_owned())]
+        );
+        assert!(detection.is_likely_game(&app("Engine"), None));
+    }
+
+    #[test]
+    fn library_folders_are_matched_anywhere_in_the_path() {
+        let detection = GameDetection {
+            folders: vec![r"D:\Games".to_owned(), "Itch".to_owned()],
+            ..GameDetection::default()
+        };
+        let in_folder = |exe_path: &str| {
+            detection.evidence(
+                &window("AppWindow", exe_path, BORDERED, Rect::default()),
+                None,
+            )
+        };
+
+        assert_eq!(
+            in_folder("C:/Program Files (x86)/Steam/SteamApps/common/Game/game.exe"),
+            vec![Evidence::LibraryFolder(r"\steamapps\common\".to_owned())]
+        );
+        assert_eq!(
+            in_folder(r"d:\games\Game\game.exe"),
+            vec![Evidence::LibraryFolder(r"D:\Games".to_owned())]
+        );
+        assert_eq!(
+            in_folder(r"C:\Users\me\itch\game\game.exe"),
+            vec![Evidence::LibraryFolder("Itch".to_owned())]
+        );
+        assert!(in_folder(r"C:\Users\me\Downloads\Switch\app.exe").is_empty());
+        assert!(in_folder(r"E:\Games\game.exe").is_empty());
+    }
+
+    #[test]
+    fn a_borderless_fixed_size_window_covering_its_monitor_is_a_likely_game() {
+        let detection = GameDetection::default();
+        let fullscreen = window("AppWindow", r"C:\Apps\app.exe", BORDERLESS, MONITOR);
+        assert_eq!(
+            detection.evidence(&fullscreen, Some(MONITOR)),
+            vec![Evidence::CoversMonitor, Evidence::FixedSize]
+        );
+        assert!(detection.is_likely_game(&fullscreen, Some(MONITOR)));
+
+        // Covering another monitor is no evidence
+        let other_monitor = Rect::new(1920, 0, 3840, 1080);
+        assert!(!detection.is_likely_game(&fullscreen, Some(other_monitor)));
+        assert!(!detection.is_likely_game(&fullscreen, None));
+    }
+
+    #[test]
+    fn the_class_tables_have_no_duplicates() {
+        let classes: Vec<String> = ENGINE_CLASSES
+            .iter()
+            .chain(GENERIC_ENGINE_CLASSES.iter())
+            .map(|(class, _)| class.to_lowercase())
+            .collect();
+        for (index, class) in classes.iter().enumerate() {
+            assert!(
+                !classes.iter().skip(index + 1).any(|other| other == class),
+                "{class} is listed twice"
+            );
+        }
+    }
+}
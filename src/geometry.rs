@@ -0,0 +1,490 @@
+use serde::{Deserialize, Serialize};
+#[cfg(windows)]
+use winsafe::RECT;
+
+/// A rectangle in virtual-desktop (screen) coordinates
+///
+/// Monitors to the left of or above the primary monitor have negative coordinates,
+/// so no assumptions are made about the sign of any of the edges.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Rect {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+impl Rect {
+    pub const fn new(left: i32, top: i32, right: i32, bottom: i32) -> Self {
+        Self {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    pub const fn width(&self) -> i32 {
+        self.right - self.left
+    }
+
+    pub const fn height(&self) -> i32 {
+        self.bottom - self.top
+    }
+}
+
+#[cfg(windows)]
+impl From<RECT> for Rect {
+    fn from(rect: RECT) -> Self {
+        Self::new(rect.left, rect.top, rect.right, rect.bottom)
+    }
+}
+
+#[cfg(windows)]
+impl From<Rect> for RECT {
+    fn from(rect: Rect) -> Self {
+        Self {
+            left: rect.left,
+            top: rect.top,
+            right: rect.right,
+            bottom: rect.bottom,
+        }
+    }
+}
+
+/// Function to compute the rectangle a fullscreenized window should occupy
+/// # Arguments
+/// * `monitor` - The full area of the target monitor, in virtual-desktop coordinates
+/// # Returns
+/// * The window rectangle, in virtual-desktop coordinates
+///
+/// The monitor's offset is kept as-is, so a window on a secondary monitor stays on that monitor
+/// instead of being moved to the primary monitor's origin.
+pub const fn fullscreen_rect(monitor: Rect) -> Rect {
+    Rect::new(
+        monitor.left,
+        monitor.top,
+        monitor.left + monitor.width(),
+        monitor.top + monitor.height(),
+    )
+}
+
+/// Function to compute the rectangle of a given size centered in an area
+/// # Arguments
+/// * `area` - The area, in virtual-desktop coordinates
+/// * `width` - The width of the rectangle
+/// * `height` - The height of the rectangle
+/// # Returns
+/// * The rectangle, which sticks out of the area on both sides if it is larger than the area
+pub const fn centered_rect(area: Rect, width: i32, height: i32) -> Rect {
+    let left = area.left + (area.width() - width) / 2;
+    let top = area.top + (area.height() - height) / 2;
+
+    Rect::new(left, top, left + width, top + height)
+}
+
+/// Function to compute the largest rectangle with the aspect ratio of a window that fits in an area
+/// # Arguments
+/// * `area` - The area to fill, usually a monitor, in virtual-desktop coordinates
+/// * `width` - The width of the client area of the window
+/// * `height` - The height of the client area of the window
+/// # Returns
+/// * The rectangle, centered in the area, or the whole area if the window has no size
+///
+/// The rectangle fills either the width or the height of the area, leaving equal bars on the other two sides
+/// (e.g. a 4:3 window on a 21:9 monitor fills its height, with bars on the left and right).
+pub fn fit_rect(area: Rect, width: i32, height: i32) -> Rect {
+    if width <= 0 || height <= 0 {
+        return fullscreen_rect(area);
+    }
+
+    let area_width = i64::from(area.width());
+    let area_height = i64::from(area.height());
+    let width = i64::from(width);
+    let height = i64::from(height);
+
+    // Compare the aspect ratios without dividing, and round the scaled side to the nearest pixel
+    let (fit_width, fit_height) = if area_width * height <= area_height * width {
+        (area_width, (area_width * height + width / 2) / width)
+    } else {
+        ((area_height * width + height / 2) / height, area_height)
+    };
+
+    // The scaled sides are never larger than the sides of the area, so they fit in an i32
+    centered_rect(
+        area,
+        i32::try_from(fit_width).unwrap_or(area.width()),
+        i32::try_from(fit_height).unwrap_or(area.height()),
+    )
+}
+
+/// Function to compute the rectangle of a window kept at its size, or scaled by a whole number, centered in an area
+/// # Arguments
+/// * `area` - The area, usually a monitor, in virtual-desktop coordinates
+/// * `width` - The width of the client area of the window
+/// * `height` - The height of the client area of the window
+/// * `upscale` - Whether to scale the window by the largest whole number that fits in the area
+/// # Returns
+/// * The rectangle, centered in the area, or the whole area if the window has no size
+///
+/// A window larger than the area is fitted in it instead, as it would otherwise be cut off.
+/// Scaling by a whole number keeps every pixel of the window the same size (e.g. 640x480 becomes 2560x1920 on a 4K monitor).
+pub fn native_rect(area: Rect, width: i32, height: i32, upscale: bool) -> Rect {
+    if width <= 0 || height <= 0 {
+        return fullscreen_rect(area);
+    }
+
+    let factor = (area.width() / width).min(area.height() / height);
+    if factor == 0 {
+        return fit_rect(area, width, height);
+    }
+    let factor = if upscale { factor } else { 1 };
+
+    centered_rect(area, width * factor, height * factor)
+}
+
+/// Function to compute the rectangle shared by two rectangles
+/// # Returns
+/// * The shared rectangle, or `None` if the rectangles do not overlap
+pub fn intersection(a: Rect, b: Rect) -> Option<Rect> {
+    let shared = Rect::new(
+        a.left.max(b.left),
+        a.top.max(b.top),
+        a.right.min(b.right),
+        a.bottom.min(b.bottom),
+    );
+
+    (shared.width() > 0 && shared.height() > 0).then_some(shared)
+}
+
+/// Function to compute the area shared by two rectangles
+pub fn intersection_area(a: Rect, b: Rect) -> i64 {
+    let width = i64::from(a.right.min(b.right)) - i64::from(a.left.max(b.left));
+    let height = i64::from(a.bottom.min(b.bottom)) - i64::from(a.top.max(b.top));
+    if width <= 0 || height <= 0 {
+        0
+    } else {
+        width * height
+    }
+}
+
+/// Function to compute the smallest rectangle containing rectangles
+/// # Arguments
+/// * `rects` - The rectangles, usually monitors, in virtual-desktop coordinates
+/// # Returns
+/// * The bounding rectangle, or `None` if there are no rectangles
+pub fn union_rect(rects: &[Rect]) -> Option<Rect> {
+    rects.iter().copied().reduce(|union, rect| {
+        Rect::new(
+            union.left.min(rect.left),
+            union.top.min(rect.top),
+            union.right.max(rect.right),
+            union.bottom.max(rect.bottom),
+        )
+    })
+}
+
+/// Function to check whether rectangles cover their bounding rectangle exactly
+/// # Arguments
+/// * `rects` - The rectangles, usually monitors, in virtual-desktop coordinates
+/// # Returns
+/// * `true` if the rectangles form a rectangle without holes or overlaps,
+///   which is not the case for monitors of different sizes side by side or arranged in an L
+pub fn forms_rectangle(rects: &[Rect]) -> bool {
+    let Some(union) = union_rect(rects) else {
+        return false;
+    };
+
+    let overlapping = rects.iter().enumerate().any(|(index, &a)| {
+        rects
+            .iter()
+            .skip(index + 1)
+            .any(|&b| intersection_area(a, b) > 0)
+    });
+    let area: i64 = rects
+        .iter()
+        .map(|rect| i64::from(rect.width()) * i64::from(rect.height()))
+        .sum();
+
+    !overlapping && area == i64::from(union.width()) * i64::from(union.height())
+}
+
+/// Function to compute the rectangle a window spanning several monitors should occupy
+/// # Arguments
+/// * `monitors` - The full areas of the spanned monitors, in virtual-desktop coordinates
+/// * `bezels` - The pixels hidden by the bezels at each gap between monitors,
+///   first the gaps between columns from left to right, then the gaps between rows from top to bottom,
+///   where missing gaps have no bezel
+/// # Returns
+/// * The rectangle, or `None` if there are no monitors
+///
+/// Without bezels, the rectangle is the bounding rectangle of the monitors. Each bezel makes it larger
+/// by its size, centered on the monitors, which suits games rendering with a bezel-corrected resolution
+/// (e.g. three 1920x1080 monitors with 60 pixel bezels span 5880x1080, cutting 60 pixels off each side).
+pub fn span_rect(monitors: &[Rect], bezels: &[i32]) -> Option<Rect> {
+    let union = union_rect(monitors)?;
+
+    // The gaps are the edges of the monitors inside the bounding rectangle
+    let mut columns: Vec<i32> = monitors
+        .iter()
+        .map(|monitor| monitor.left)
+        .filter(|&left| left != union.left)
+        .collect();
+    columns.sort_unstable();
+    columns.dedup();
+    let mut rows: Vec<i32> = monitors
+        .iter()
+        .map(|monitor| monitor.top)
+        .filter(|&top| top != union.top)
+        .collect();
+    rows.sort_unstable();
+    rows.dedup();
+
+    // The bezels come from the settings, so the sizes saturate instead of overflowing
+    let extra_width = bezels
+        .iter()
+        .take(columns.len())
+        .fold(0_i32, |total, &bezel| total.saturating_add(bezel));
+    let extra_height = bezels
+        .iter()
+        .skip(columns.len())
+        .take(rows.len())
+        .fold(0_i32, |total, &bezel| total.saturating_add(bezel));
+
+    Some(centered_rect(
+        union,
+        union.width().saturating_add(extra_width),
+        union.height().saturating_add(extra_height),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fullscreen_rect_covers_primary_monitor() {
+        let primary = Rect::new(0, 0, 1920, 1080);
+        assert_eq!(fullscreen_rect(primary), primary);
+    }
+
+    #[test]
+    fn fullscreen_rect_keeps_offset_of_monitor_to_the_right() {
+        let right = Rect::new(1920, 0, 4480, 1440);
+        assert_eq!(fullscreen_rect(right), Rect::new(1920, 0, 4480, 1440));
+    }
+
+    #[test]
+    fn fullscreen_rect_keeps_negative_offset_of_monitor_to_the_left() {
+        let left = Rect::new(-1920, 0, 0, 1080);
+        let rect = fullscreen_rect(left);
+        assert_eq!(rect, Rect::new(-1920, 0, 0, 1080));
+        assert_eq!((rect.width(), rect.height()), (1920, 1080));
+    }
+
+    #[test]
+    fn fullscreen_rect_keeps_negative_offset_of_monitor_above() {
+        let above = Rect::new(0, -1440, 2560, 0);
+        assert_eq!(fullscreen_rect(above), Rect::new(0, -1440, 2560, 0));
+    }
+
+    #[test]
+    fn fullscreen_rect_keeps_offset_of_monitor_above_and_to_the_left() {
+        // A portrait monitor whose top-left corner is above and to the left of the primary monitor
+        let corner = Rect::new(-1080, -840, 0, 1080);
+        let rect = fullscreen_rect(corner);
+        assert_eq!(rect, corner);
+        assert_eq!((rect.width(), rect.height()), (1080, 1920));
+    }
+
+    #[test]
+    fn fit_rect_fills_the_height_for_narrower_windows() {
+        // A 4:3 window on a 21:9 monitor to the right of the primary monitor
+        let monitor = Rect::new(1920, 0, 5360, 1440);
+        let rect = fit_rect(monitor, 800, 600);
+        assert_eq!(rect, Rect::new(2680, 0, 4600, 1440));
+        assert_eq!((rect.width(), rect.height()), (1920, 1440));
+    }
+
+    #[test]
+    fn fit_rect_fills_the_width_for_wider_windows() {
+        // A 21:9 window on a 16:9 monitor
+        let monitor = Rect::new(0, 0, 1920, 1080);
+        let rect = fit_rect(monitor, 2560, 1080);
+        assert_eq!(rect, Rect::new(0, 135, 1920, 945));
+        assert_eq!((rect.width(), rect.height()), (1920, 810));
+    }
+
+    #[test]
+    fn fit_rect_covers_the_area_for_the_same_aspect_ratio() {
+        let monitor = Rect::new(-2560, 0, 0, 1440);
+        assert_eq!(fit_rect(monitor, 1280, 720), monitor);
+        assert_eq!(fit_rect(monitor, 2560, 1440), monitor);
+    }
+
+    #[test]
+    fn fit_rect_rounds_to_the_nearest_pixel() {
+        // 1080 * 1000 / 1001 is 1078.92, and 1920 * 1001 / 2000 is 960.96
+        let monitor = Rect::new(0, 0, 1920, 1080);
+        assert_eq!(fit_rect(monitor, 1000, 1001).width(), 1079);
+        assert_eq!(fit_rect(monitor, 2000, 1001).height(), 961);
+    }
+
+    #[test]
+    fn fit_rect_covers_the_area_for_windows_without_a_size() {
+        let monitor = Rect::new(0, 0, 1920, 1080);
+        assert_eq!(fit_rect(monitor, 0, 600), monitor);
+        assert_eq!(fit_rect(monitor, 800, -1), monitor);
+    }
+
+    #[test]
+    fn native_rect_centers_the_window_at_its_size() {
+        let monitor = Rect::new(1920, 0, 3840, 1080);
+        assert_eq!(
+            native_rect(monitor, 800, 600, false),
+            Rect::new(2480, 240, 3280, 840)
+        );
+    }
+
+    #[test]
+    fn native_rect_scales_by_the_largest_whole_number_that_fits() {
+        // 640x480 fits 4 times in the height of a 4K monitor, and 6 times in its width
+        let monitor = Rect::new(0, 0, 3840, 2160);
+        let rect = native_rect(monitor, 640, 480, true);
+        assert_eq!((rect.width(), rect.height()), (2560, 1920));
+        assert_eq!(rect, Rect::new(640, 120, 3200, 2040));
+
+        let rect = native_rect(monitor, 320, 240, true);
+        assert_eq!((rect.width(), rect.height()), (2880, 2160));
+
+        // A window that fits exactly is not scaled
+        assert_eq!(native_rect(monitor, 3840, 2160, true), monitor);
+    }
+
+    #[test]
+    fn native_rect_fits_windows_larger_than_the_area() {
+        let monitor = Rect::new(-1920, 0, 0, 1080);
+        for upscale in [false, true] {
+            assert_eq!(
+                native_rect(monitor, 2560, 1440, upscale),
+                fit_rect(monitor, 2560, 1440)
+            );
+            // Only taller than the monitor
+            let rect = native_rect(monitor, 1280, 1200, upscale);
+            assert_eq!(rect, fit_rect(monitor, 1280, 1200));
+            assert_eq!(rect.height(), 1080);
+        }
+    }
+
+    #[test]
+    fn native_rect_covers_the_area_for_windows_without_a_size() {
+        let monitor = Rect::new(0, 0, 1920, 1080);
+        assert_eq!(native_rect(monitor, 0, 0, true), monitor);
+    }
+
+    /// Function to get three 1920x1080 monitors side by side, the primary one in the middle
+    fn three_monitors() -> [Rect; 3] {
+        [
+            Rect::new(-1920, 0, 0, 1080),
+            Rect::new(0, 0, 1920, 1080),
+            Rect::new(1920, 0, 3840, 1080),
+        ]
+    }
+
+    #[test]
+    fn union_rect_bounds_all_rectangles() {
+        assert_eq!(
+            union_rect(&three_monitors()),
+            Some(Rect::new(-1920, 0, 3840, 1080))
+        );
+        // A smaller monitor above the primary monitor
+        assert_eq!(
+            union_rect(&[Rect::new(0, 0, 2560, 1440), Rect::new(320, -1080, 2240, 0)]),
+            Some(Rect::new(0, -1080, 2560, 1440))
+        );
+        assert_eq!(union_rect(&[]), None);
+    }
+
+    #[test]
+    fn forms_rectangle_accepts_monitors_in_a_grid() {
+        assert!(forms_rectangle(&three_monitors()));
+        assert!(forms_rectangle(&[
+            Rect::new(0, 0, 1920, 1080),
+            Rect::new(1920, 0, 3840, 1080),
+            Rect::new(0, 1080, 1920, 2160),
+            Rect::new(1920, 1080, 3840, 2160),
+        ]));
+        assert!(forms_rectangle(&[Rect::new(0, 0, 1920, 1080)]));
+    }
+
+    #[test]
+    fn forms_rectangle_rejects_holes_and_overlaps() {
+        // Three monitors arranged in an L
+        assert!(!forms_rectangle(&[
+            Rect::new(0, 0, 1920, 1080),
+            Rect::new(1920, 0, 3840, 1080),
+            Rect::new(0, 1080, 1920, 2160),
+        ]));
+        // Monitors of different heights side by side
+        assert!(!forms_rectangle(&[
+            Rect::new(0, 0, 2560, 1440),
+            Rect::new(2560, 0, 4480, 1080),
+        ]));
+        // Overlapping monitors whose total area is the area of their bounding rectangle, which has a hole
+        assert!(!forms_rectangle(&[
+            Rect::new(0, 0, 1920, 1080),
+            Rect::new(960, 0, 2880, 1080),
+            Rect::new(960, 1080, 2880, 2160),
+        ]));
+        assert!(!forms_rectangle(&[]));
+    }
+
+    #[test]
+    fn span_rect_without_bezels_is_the_bounding_rectangle() {
+        assert_eq!(
+            span_rect(&three_monitors(), &[]),
+            Some(Rect::new(-1920, 0, 3840, 1080))
+        );
+        assert_eq!(span_rect(&[], &[60]), None);
+    }
+
+    #[test]
+    fn span_rect_compensates_for_bezels_on_a_horizontal_span() {
+        let rect = span_rect(&three_monitors(), &[60, 60]).unwrap();
+        assert_eq!((rect.width(), rect.height()), (5880, 1080));
+        assert_eq!(rect, Rect::new(-1980, 0, 3900, 1080));
+
+        // The gaps between rows have no monitors here, so extra bezels are ignored
+        assert_eq!(span_rect(&three_monitors(), &[60, 60, 40]), Some(rect));
+    }
+
+    #[test]
+    fn span_rect_compensates_for_bezels_between_columns_and_rows() {
+        let grid = [
+            Rect::new(0, 0, 1920, 1080),
+            Rect::new(1920, 0, 3840, 1080),
+            Rect::new(0, 1080, 1920, 2160),
+            Rect::new(1920, 1080, 3840, 2160),
+        ];
+        assert_eq!(
+            span_rect(&grid, &[80, 40]),
+            Some(Rect::new(-40, -20, 3880, 2180))
+        );
+    }
+
+    #[test]
+    fn span_rect_saturates_on_huge_bezels() {
+        let monitors = [Rect::new(0, 0, 1920, 1080), Rect::new(1920, 0, 3840, 1080)];
+        let rect = span_rect(&monitors, &[i32::MAX]).unwrap();
+        assert_eq!(rect.width(), i32::MAX);
+        assert_eq!(rect.height(), 1080);
+
+        let grid = [
+            Rect::new(0, 0, 1920, 1080),
+            Rect::new(1920, 0, 3840, 1080),
+            Rect::new(0, 1080, 1920, 2160),
+        ];
+        let rect = span_rect(&grid, &[10, i32::MAX, i32::MAX]).unwrap();
+        assert_eq!((rect.width(), rect.height()), (3850, i32::MAX));
+    }
+}
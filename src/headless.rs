@@ -0,0 +1,344 @@
+use core::time::Duration;
+use std::process::ExitCode;
+use std::thread;
+
+use crate::cli::{
+    ApplyOptions, Command, EXIT_AMBIGUOUS, EXIT_FAILURE, EXIT_NO_MATCH, EXIT_USAGE, ListOptions,
+    OutputFormat, USAGE, WindowMatcher,
+};
+use crate::journal::{self, Journal};
+use crate::monitors::{MonitorChoice, MonitorList};
+use crate::rect_expr;
+use crate::scaling::Sizing;
+use crate::settings::{self, Settings};
+use crate::watcher::{self, ProfileWatcher};
+#[cfg(windows)]
+use crate::win32_system::Win32System;
+use crate::window_info::{self, WindowInfo};
+use crate::window_ops;
+use crate::window_system::WindowSystem;
+#[cfg(not(windows))]
+use crate::x11_system::X11System;
+
+/// Function to run a command given on the command line, without showing a window
+/// # Arguments
+/// * `command` - The command
+/// # Returns
+/// * The exit code of the process
+pub fn run(command: Command) -> ExitCode {
+    match command {
+        Command::List(options) => with_system(|system| list(system, &options)),
+        Command::Apply(options) => with_system(|system| apply(system, &options)),
+        Command::Restore(matcher) => with_system(|system| restore(system, &matcher)),
+        Command::Watch => with_system(watch),
+        Command::Help => {
+            print!("{USAGE}");
+            ExitCode::SUCCESS
+        }
+    }
+}
+
+/// Function to run a command with the window system of the platform
+/// # Arguments
+/// * `run` - The command
+/// # Returns
+/// * The exit code of the command, or a failure if the window system is not available
+fn with_system(run: impl FnOnce(&NativeSystem) -> ExitCode) -> ExitCode {
+    match connect() {
+        Ok(system) => run(&system),
+        Err(e) => {
+            eprintln!("Failed to access the windows - {e}");
+            ExitCode::from(EXIT_FAILURE)
+        }
+    }
+}
+
+/// The window system of the platform
+#[cfg(windows)]
+type NativeSystem = Win32System;
+#[cfg(not(windows))]
+type NativeSystem = X11System;
+
+/// Function to get the window system of the platform
+#[cfg(windows)]
+const fn connect() -> Result<NativeSystem, String> {
+    Ok(Win32System)
+}
+
+/// Function to get the window system of the platform
+#[cfg(not(windows))]
+fn connect() -> Result<NativeSystem, String> {
+    X11System::connect()
+}
+
+/// Function to print the top-level windows
+fn list(system: &impl WindowSystem, options: &ListOptions) -> ExitCode {
+    let Some(settings) = load_settings() else {
+        return ExitCode::from(EXIT_FAILURE);
+    };
+    let windows = window_ops::enumerate_windows(system, &settings.exclusions, options.all);
+
+    let output = match options.format {
+        OutputFormat::Text => Ok(windows
+            .iter()
+            .map(|window| {
+                format!(
+                    "{:#x}\t{}\t{}\t{}\t{}\n",
+                    window.hwnd,
+                    window.process_id,
+                    window.exe_name().unwrap_or("?"),
+                    window.class_name,
+                    window.title
+                )
+            })
+            .collect()),
+        OutputFormat::Json => window_info::to_json(&windows).map(|json| json + "\n"),
+        OutputFormat::Ndjson => window_info::to_ndjson(&windows),
+    };
+
+    match output {
+        Ok(output) => {
+            print!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::from(EXIT_FAILURE)
+        }
+    }
+}
+
+/// Function to fullscreenize the windows matching the command line
+fn apply(system: &impl WindowSystem, options: &ApplyOptions) -> ExitCode {
+    let Some(settings) = load_settings() else {
+        return ExitCode::from(EXIT_FAILURE);
+    };
+
+    let monitors = MonitorList::new(system.monitors()).with_spans(&settings.spans);
+    let choice = match options.monitor.as_deref() {
+        Some(name) => {
+            if let Some(monitor) = monitors.find(name) {
+                MonitorChoice::Device(monitor.device_name.clone())
+            } else if let Some(span) = monitors.find_span(name) {
+                MonitorChoice::Span(span.name.clone())
+            } else {
+                eprintln!("Unknown monitor '{name}'");
+                return ExitCode::from(EXIT_USAGE);
+            }
+        }
+        None => MonitorChoice::Nearest,
+    };
+    let sizing = match options.rect.as_deref() {
+        Some(rect) => match rect_expr::preset_or_expression(&settings.presets, rect) {
+            Ok(rect) => Sizing::Rect(rect),
+            Err(e) => {
+                eprintln!("{e}");
+                return ExitCode::from(EXIT_USAGE);
+            }
+        },
+        None => Sizing::Scale(options.scale.unwrap_or(settings.scale_mode)),
+    };
+    let windows: Vec<WindowInfo> =
+        window_ops::enumerate_windows(system, &settings.exclusions, false)
+            .into_iter()
+            .filter(|window| options.matcher.matches(window))
+            .collect();
+    if windows.is_empty() {
+        eprintln!("No window matches");
+        return ExitCode::from(EXIT_NO_MATCH);
+    }
+    if windows.len() > 1 && !options.all {
+        eprintln!("Several windows match, use --all to fullscreenize all of them:");
+        for window in &windows {
+            eprintln!("{:#x}\t{}", window.hwnd, window.title);
+        }
+        return ExitCode::from(EXIT_AMBIGUOUS);
+    }
+
+    let Some(mut journal) = open_journal() else {
+        return ExitCode::from(EXIT_FAILURE);
+    };
+
+    let placement = Placement {
+        choice,
+        sizing,
+        work_area: options.work_area || settings.work_area,
+        topmost: options.topmost,
+    };
+    let mut failed = false;
+    for window in &windows {
+        match apply_to(system, window, &monitors, &placement, &mut journal) {
+            Ok(()) => println!("Fullscreenized \"{}\"", window.title),
+            Err(e) => {
+                eprintln!("Failed to fullscreenize \"{}\" - {e}", window.title);
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        ExitCode::from(EXIT_FAILURE)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Where and how the windows matching the command line are fullscreenized
+struct Placement {
+    /// The monitor or span to send the windows to
+    choice: MonitorChoice,
+    /// How to size the windows
+    sizing: Sizing,
+    /// Whether to only fill the work area of the monitor
+    work_area: bool,
+    /// Whether to apply the "stay on top" flag
+    topmost: bool,
+}
+
+/// Function to fullscreenize a single window
+fn apply_to(
+    system: &impl WindowSystem,
+    window: &WindowInfo,
+    monitors: &MonitorList,
+    placement: &Placement,
+    journal: &mut Journal,
+) -> Result<(), String> {
+    // Get the dimensions of the chosen monitor or span
+    let area = monitors
+        .resolve_area(
+            &placement.choice,
+            system.rect(window.hwnd)?,
+            placement.work_area,
+        )
+        .ok_or_else(|| "Could not find a monitor to place the window on".to_owned())?;
+    if !area.rectangular {
+        eprintln!(
+            "Warning: the spanned monitors do not form a rectangle, so parts of \"{}\" will not be shown",
+            window.title
+        );
+    }
+
+    // The client size is read before the border is removed, as fitting keeps its aspect ratio
+    let target = window_ops::target_rect(system, window.hwnd, area.rect, &placement.sizing)?;
+
+    // Record the original state of the window so that it can be restored later
+    let state = window_ops::capture_state(system, window.hwnd)
+        .map_err(|e| format!("Could not save the window's state: {e}"))?;
+    journal
+        .record(state)
+        .map_err(|e| eprintln!("Failed to save the window's state - {e}"))
+        .ok();
+
+    window_ops::fullscreenize(system, window.hwnd, target, placement.topmost)
+}
+
+/// Function to restore the fullscreenized windows matching the command line
+fn restore(system: &impl WindowSystem, matcher: &WindowMatcher) -> ExitCode {
+    let Some(mut journal) = open_journal() else {
+        return ExitCode::from(EXIT_FAILURE);
+    };
+
+    // Find the fullscreenized windows that still exist and match
+    let mut matching = Vec::new();
+    let mut closed = Vec::new();
+    for state in journal.windows().iter() {
+        let Some(hwnd) = window_ops::find_window(system, state) else {
+            closed.push(state.hwnd);
+            continue;
+        };
+
+        let window = window_ops::window_info(system, hwnd);
+        if matcher.matches(&window) {
+            matching.push(window);
+        }
+    }
+
+    // Forget the windows that were closed
+    for hwnd in closed {
+        journal.take(hwnd);
+    }
+
+    if matching.is_empty() {
+        if matcher.is_empty() {
+            println!("No windows to restore");
+            return ExitCode::SUCCESS;
+        }
+
+        eprintln!("No fullscreenized window matches");
+        return ExitCode::from(EXIT_NO_MATCH);
+    }
+
+    let mut failed = false;
+    for window in matching {
+        let Some(state) = journal.take(window.hwnd) else {
+            continue;
+        };
+        match window_ops::restore(system, window.hwnd, &state) {
+            Ok(()) => println!("Restored \"{}\"", window.title),
+            Err(e) => {
+                eprintln!("Failed to restore \"{}\" - {e}", window.title);
+                failed = true;
+                // Keep tracking the window so that restoring can be retried
+                journal
+                    .record(state)
+                    .map_err(|e| eprintln!("Failed to update the journal: {e}"))
+                    .ok();
+            }
+        }
+    }
+
+    if failed {
+        ExitCode::from(EXIT_FAILURE)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Function to apply the profiles to new windows until the process is stopped
+fn watch(system: &impl WindowSystem) -> ExitCode {
+    let Some(settings) = load_settings() else {
+        return ExitCode::from(EXIT_FAILURE);
+    };
+    if settings.profiles.iter().next().is_none() {
+        eprintln!("There are no profiles in the settings");
+        return ExitCode::from(EXIT_USAGE);
+    }
+    let Some(mut journal) = open_journal() else {
+        return ExitCode::from(EXIT_FAILURE);
+    };
+
+    println!("Watching for new windows, press Ctrl+C to stop");
+    let mut watcher = ProfileWatcher::default();
+    loop {
+        for applied in watcher.poll(system, &settings, &mut journal) {
+            match applied.result {
+                Ok(_) => println!(
+                    "Applied profile '{}' to \"{}\"",
+                    applied.profile, applied.title
+                ),
+                Err(e) => eprintln!(
+                    "Failed to apply profile '{}' to \"{}\" - {e}",
+                    applied.profile, applied.title
+                ),
+            }
+        }
+
+        thread::sleep(Duration::from_millis(watcher::POLL_INTERVAL_MS.into()));
+    }
+}
+
+/// Function to load the settings, printing an error if they cannot be loaded
+fn load_settings() -> Option<Settings> {
+    Settings::load(settings::default_path())
+        .map_err(|e| eprintln!("Failed to load the settings - {e}"))
+        .ok()
+}
+
+/// Function to open the journal, printing an error if it cannot be loaded
+///
+/// The journal is not replaced when it cannot be loaded, so that the windows recorded in it are not lost.
+fn open_journal() -> Option<Journal> {
+    Journal::load(journal::default_path())
+        .map_err(|e| eprintln!("Failed to load the journal - {e}"))
+        .ok()
+}
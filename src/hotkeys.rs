@@ -0,0 +1,340 @@
+use core::fmt;
+use core::str::FromStr;
+
+use serde::de::Error as _;
+use serde::{Deserialize as _, Deserializer, Serializer};
+
+/// `MOD_ALT` modifier flag of `RegisterHotKey`
+pub const MOD_ALT: u16 = 0x0001;
+/// `MOD_CONTROL` modifier flag of `RegisterHotKey`
+pub const MOD_CONTROL: u16 = 0x0002;
+/// `MOD_SHIFT` modifier flag of `RegisterHotKey`
+pub const MOD_SHIFT: u16 = 0x0004;
+/// `MOD_WIN` modifier flag of `RegisterHotKey`
+pub const MOD_WIN: u16 = 0x0008;
+
+/// Names of the modifiers, in the order they are written
+const MODIFIER_NAMES: [(u16, &str); 4] = [
+    (MOD_CONTROL, "Ctrl"),
+    (MOD_ALT, "Alt"),
+    (MOD_SHIFT, "Shift"),
+    (MOD_WIN, "Win"),
+];
+
+/// Names of the keys that are not a letter, a digit or a function key, with their virtual key codes
+const KEY_NAMES: [(u16, &str); 25] = [
+    (0x08, "Backspace"),
+    (0x09, "Tab"),
+    (0x0D, "Enter"),
+    (0x13, "Pause"),
+    (0x1B, "Esc"),
+    (0x20, "Space"),
+    (0x21, "PageUp"),
+    (0x22, "PageDown"),
+    (0x23, "End"),
+    (0x24, "Home"),
+    (0x25, "Left"),
+    (0x26, "Up"),
+    (0x27, "Right"),
+    (0x28, "Down"),
+    (0x2C, "PrintScreen"),
+    (0x2D, "Insert"),
+    (0x2E, "Delete"),
+    (0x6A, "Multiply"),
+    (0x6B, "Add"),
+    (0x6D, "Subtract"),
+    (0x6E, "Decimal"),
+    (0x6F, "Divide"),
+    (0xBC, "Comma"),
+    (0xBD, "Minus"),
+    (0xBE, "Period"),
+];
+
+/// Other names accepted for some keys and modifiers
+const ALIASES: [(&str, &str); 8] = [
+    ("Control", "Ctrl"),
+    ("Windows", "Win"),
+    ("Escape", "Esc"),
+    ("Return", "Enter"),
+    ("Del", "Delete"),
+    ("Ins", "Insert"),
+    ("PgUp", "PageUp"),
+    ("PgDn", "PageDown"),
+];
+
+/// A global keyboard shortcut, written as `Ctrl+Alt+F`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Hotkey {
+    /// The `MOD_` flags of the modifiers that must be held
+    pub modifiers: u16,
+    /// The virtual key code of the key
+    pub key: u16,
+}
+
+impl Hotkey {
+    pub const fn new(modifiers: u16, key: u16) -> Self {
+        Self { modifiers, key }
+    }
+}
+
+impl fmt::Display for Hotkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (flag, name) in MODIFIER_NAMES {
+            if self.modifiers & flag != 0 {
+                write!(f, "{name}+")?;
+            }
+        }
+
+        match self.key {
+            // Letters and digits have the same code as their ASCII character
+            0x30..=0x39 | 0x41..=0x5A => write!(f, "{}", char::from(self.key as u8)),
+            // F1 to F24
+            0x70..=0x87 => write!(f, "F{}", self.key - 0x6F),
+            // Numpad 0 to 9
+            0x60..=0x69 => write!(f, "Numpad{}", self.key - 0x60),
+            key => match KEY_NAMES.iter().find(|&&(code, _)| code == key) {
+                Some((_, name)) => write!(f, "{name}"),
+                None => write!(f, "{key:#04X}"),
+            },
+        }
+    }
+}
+
+impl FromStr for Hotkey {
+    type Err = String;
+
+    /// Function to parse a hotkey
+    /// # Arguments
+    /// * `text` - The modifiers and the key, separated by `+` (e.g. `Ctrl+Alt+F`), in any case
+    /// # Returns
+    /// * The hotkey, or an error message
+    ///
+    /// At least one modifier is required, so that the key keeps working in other applications.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = 0;
+        let mut key = None;
+
+        for part in text.split('+').map(str::trim) {
+            if part.is_empty() {
+                return Err(format!("Invalid hotkey '{text}': a key is missing"));
+            }
+            if key.is_some() {
+                return Err(format!(
+                    "Invalid hotkey '{text}': the key must be written after the modifiers"
+                ));
+            }
+
+            let part = ALIASES
+                .iter()
+                .find(|(alias, _)| alias.eq_ignore_ascii_case(part))
+                .map_or(part, |&(_, name)| name);
+
+            match MODIFIER_NAMES
+                .iter()
+                .find(|(_, name)| name.eq_ignore_ascii_case(part))
+            {
+                Some(&(flag, _)) => modifiers |= flag,
+                None => {
+                    key =
+                        Some(parse_key(part).ok_or_else(|| {
+                            format!("Invalid hotkey '{text}': unknown key '{part}'")
+                        })?);
+                }
+            }
+        }
+
+        let Some(key) = key else {
+            return Err(format!("Invalid hotkey '{text}': a key is missing"));
+        };
+        if modifiers == 0 {
+            return Err(format!(
+                "Invalid hotkey '{text}': at least one of Ctrl, Alt, Shift or Win is needed"
+            ));
+        }
+
+        Ok(Self::new(modifiers, key))
+    }
+}
+
+/// Function to get the virtual key code of a key
+/// # Arguments
+/// * `name` - The name of the key (e.g. `F`, `7`, `F11`, `Numpad3` or `PageUp`), in any case
+/// # Returns
+/// * The virtual key code, or `None` if the key is unknown
+fn parse_key(name: &str) -> Option<u16> {
+    if let [byte] = name.as_bytes()
+        && byte.is_ascii_alphanumeric()
+    {
+        return Some(u16::from(byte.to_ascii_uppercase()));
+    }
+
+    let number = |prefix: &str| -> Option<u16> {
+        name.get(..prefix.len())
+            .filter(|start| start.eq_ignore_ascii_case(prefix))
+            .and_then(|_| name.get(prefix.len()..))
+            .and_then(|number| number.parse().ok())
+    };
+    if let Some(number @ 1..=24) = number("F") {
+        return Some(0x6F + number);
+    }
+    if let Some(number @ 0..=9) = number("Numpad") {
+        return Some(0x60 + number);
+    }
+
+    KEY_NAMES
+        .iter()
+        .find(|(_, key_name)| key_name.eq_ignore_ascii_case(name))
+        .map(|&(code, _)| code)
+}
+
+/// Function to write a hotkey that may be disabled to the settings file, as text
+///
+/// A disabled hotkey is written as an empty string, so that it is not replaced by the default when read back.
+pub fn serialize_optional<S: Serializer>(
+    hotkey: &Option<Hotkey>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match hotkey {
+        Some(hotkey) => serializer.collect_str(hotkey),
+        None => serializer.serialize_str(""),
+    }
+}
+
+/// Function to read a hotkey that may be disabled from the settings file
+pub fn deserialize_optional<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Hotkey>, D::Error> {
+    parse_optional(&String::deserialize(deserializer)?).map_err(D::Error::custom)
+}
+
+/// Function to parse a hotkey that may be disabled
+/// # Arguments
+/// * `text` - The hotkey, or an empty string if it is disabled
+/// # Returns
+/// * The hotkey, `None` if it is disabled, or an error message
+pub fn parse_optional(text: &str) -> Result<Option<Hotkey>, String> {
+    if text.trim().is_empty() {
+        Ok(None)
+    } else {
+        text.parse().map(Some)
+    }
+}
+
+/// Function to describe why a hotkey could not be registered
+/// # Arguments
+/// * `name` - What the hotkey does (e.g. `fullscreenize`)
+/// * `hotkey` - The hotkey
+/// * `already_registered` - Whether the system reported that another application uses the hotkey
+/// * `error` - The error reported by the system
+/// # Returns
+/// * The message shown to the user
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn conflict_message(
+    name: &str,
+    hotkey: Hotkey,
+    already_registered: bool,
+    error: &str,
+) -> String {
+    if already_registered {
+        format!(
+            "The {name} hotkey {hotkey} is already used by another application. \
+             Close that application or choose another hotkey in the settings."
+        )
+    } else {
+        format!("The {name} hotkey {hotkey} could not be registered: {error}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<Hotkey, String> {
+        text.parse()
+    }
+
+    #[test]
+    fn parses_modifiers_and_a_key() {
+        assert_eq!(
+            parse("Ctrl+Alt+F"),
+            Ok(Hotkey::new(MOD_CONTROL | MOD_ALT, 0x46))
+        );
+        assert_eq!(
+            parse("shift + win + 7"),
+            Ok(Hotkey::new(MOD_SHIFT | MOD_WIN, 0x37))
+        );
+        assert_eq!(parse("Alt+F11"), Ok(Hotkey::new(MOD_ALT, 0x7A)));
+        assert_eq!(parse("Ctrl+Numpad3"), Ok(Hotkey::new(MOD_CONTROL, 0x63)));
+        assert_eq!(parse("Ctrl+pageup"), Ok(Hotkey::new(MOD_CONTROL, 0x21)));
+    }
+
+    #[test]
+    fn accepts_aliases_of_modifiers_and_keys() {
+        assert_eq!(parse("Control+Windows+Escape"), parse("Ctrl+Win+Esc"));
+        assert_eq!(parse("Alt+Return"), parse("Alt+Enter"));
+        assert_eq!(parse("Ctrl+Del"), parse("Ctrl+Delete"));
+        assert_eq!(parse("Ctrl+PgDn"), parse("Ctrl+PageDown"));
+    }
+
+    #[test]
+    fn rejects_hotkeys_without_a_key() {
+        for text in ["", "Ctrl", "Ctrl+Alt", "Ctrl+", "Ctrl++F"] {
+            let error = parse(text).unwrap_err();
+            assert!(error.contains("a key is missing"), "{text}: {error}");
+        }
+    }
+
+    #[test]
+    fn rejects_hotkeys_without_a_modifier() {
+        let error = parse("F").unwrap_err();
+        assert!(
+            error.contains("at least one of Ctrl, Alt, Shift or Win"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_keys_and_misplaced_modifiers() {
+        assert!(parse("Ctrl+F25").unwrap_err().contains("unknown key 'F25'"));
+        assert!(parse("Ctrl+Numpad10").is_err());
+        assert!(parse("Ctrl+Hyper").is_err());
+        assert!(
+            parse("Ctrl+F+Alt")
+                .unwrap_err()
+                .contains("after the modifiers")
+        );
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for text in [
+            "Ctrl+Alt+F",
+            "Ctrl+Alt+R",
+            "Shift+Win+7",
+            "Ctrl+Alt+Shift+Win+F24",
+            "Alt+Numpad0",
+            "Ctrl+PrintScreen",
+            "Alt+Period",
+        ] {
+            let hotkey = parse(text).unwrap();
+            assert_eq!(hotkey.to_string(), text);
+            assert_eq!(parse(&hotkey.to_string()), Ok(hotkey));
+        }
+
+        // Modifiers are always written in the same order, whatever order they were typed in
+        assert_eq!(
+            parse("win+shift+alt+ctrl+a").unwrap().to_string(),
+            "Ctrl+Alt+Shift+Win+A"
+        );
+        // Keys without a name are written as their code
+        assert_eq!(Hotkey::new(MOD_ALT, 0xC0).to_string(), "Alt+0xC0");
+    }
+
+    #[test]
+    fn disabled_hotkeys_are_empty() {
+        assert_eq!(parse_optional(""), Ok(None));
+        assert_eq!(parse_optional("  "), Ok(None));
+        assert_eq!(parse_optional("Ctrl+Alt+F"), parse("Ctrl+Alt+F").map(Some));
+        assert!(parse_optional("F").is_err());
+    }
+}
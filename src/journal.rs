@@ -0,0 +1,336 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::paths;
+use crate::window_state::{ModifiedWindows, SavedWindowState};
+
+/// Name of the journal file in the config directory
+const JOURNAL_FILE_NAME: &str = "journal.json";
+
+/// Version of the journal file format
+const JOURNAL_VERSION: u32 = 1;
+
+/// The on-disk representation of the journal, holding the windows by reference when it is written
+#[derive(Serialize, Deserialize)]
+struct JournalFile<W> {
+    version: u32,
+    windows: W,
+}
+
+/// A record of the windows modified by Fullscreenizer that is kept on disk
+///
+/// The file is rewritten after every change, so that windows left modified by a crash
+/// or by closing Fullscreenizer can be restored the next time it starts.
+/// The file is shared by every running instance, such as the command line while the window is open,
+/// so it is read again before each change instead of being overwritten with the windows this instance knows of.
+#[derive(Debug, Default)]
+pub struct Journal {
+    /// Path of the journal file, or `None` to only keep the journal in memory
+    path: Option<PathBuf>,
+    windows: ModifiedWindows,
+    /// The windows recorded since the file was last written, which are added back whenever the file is read again
+    unsaved: ModifiedWindows,
+}
+
+impl Journal {
+    /// Function to load the journal from disk
+    /// # Arguments
+    /// * `path` - Path of the journal file, or `None` to only keep the journal in memory
+    /// # Returns
+    /// * The journal, which is empty if the file does not exist yet, or an error message
+    pub fn load(path: Option<PathBuf>) -> Result<Self, String> {
+        let windows = match path.as_deref() {
+            Some(path) => read(path)?,
+            None => ModifiedWindows::default(),
+        };
+
+        Ok(Self {
+            path,
+            windows,
+            unsaved: ModifiedWindows::default(),
+        })
+    }
+
+    pub const fn windows(&self) -> &ModifiedWindows {
+        &self.windows
+    }
+
+    /// Function to record the original state of a window before it is modified
+    /// # Arguments
+    /// * `state` - The original state of the window
+    /// # Returns
+    /// * An error message if the journal could not be written
+    ///
+    /// The state is kept in memory even if writing the journal fails, and written along with the next change.
+    pub fn record(&mut self, state: SavedWindowState) -> Result<(), String> {
+        let reloaded = self.reload();
+        if self.windows.record(state.clone()) {
+            self.unsaved.record(state);
+            // A file that could not be read is not overwritten, as the windows recorded in it would be lost
+            reloaded?;
+            self.save()?;
+        }
+
+        Ok(())
+    }
+
+    /// Function to stop tracking a window, usually after it was restored
+    /// # Arguments
+    /// * `hwnd` - The raw handle of the window
+    /// # Returns
+    /// * The original state of the window, or `None` if the window was not modified
+    ///
+    /// Failing to write the journal is only logged, as the window has already been handled.
+    pub fn take(&mut self, hwnd: isize) -> Option<SavedWindowState> {
+        let reloaded = self.reload();
+        self.unsaved.take(hwnd);
+        let state = self.windows.take(hwnd)?;
+        reloaded
+            .and_then(|()| self.save())
+            .map_err(|e| eprintln!("Failed to update the journal: {e}"))
+            .ok();
+
+        Some(state)
+    }
+
+    /// Function to replace the windows known to this instance with the windows recorded in the file
+    /// and the windows that could not be written to it yet
+    /// # Returns
+    /// * An error message if the file could not be read, in which case the known windows are kept
+    fn reload(&mut self) -> Result<(), String> {
+        if let Some(path) = self.path.as_deref() {
+            let mut windows = read(path)?;
+            for state in self.unsaved.iter() {
+                windows.record(state.clone());
+            }
+            self.windows = windows;
+        }
+
+        Ok(())
+    }
+
+    /// Function to write the journal to disk
+    fn save(&mut self) -> Result<(), String> {
+        let Some(path) = self.path.as_ref() else {
+            return Ok(());
+        };
+
+        paths::write_atomic(path, &serialize(&self.windows)?)?;
+        self.unsaved = ModifiedWindows::default();
+        Ok(())
+    }
+}
+
+/// Function to read the journal file
+/// # Arguments
+/// * `path` - Path of the journal file
+/// # Returns
+/// * The modified windows, which are empty if the file does not exist yet, or an error message
+fn read(path: &Path) -> Result<ModifiedWindows, String> {
+    match fs::read_to_string(path) {
+        Ok(text) => deserialize(&text),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(ModifiedWindows::default()),
+        Err(e) => Err(format!("Failed to read the journal: {e}")),
+    }
+}
+
+/// Function to get the path of the journal file
+/// # Returns
+/// * The path of the journal file in the config directory, or `None` if the config directory could not be determined
+pub fn default_path() -> Option<PathBuf> {
+    paths::config_dir().map(|dir| dir.join(JOURNAL_FILE_NAME))
+}
+
+/// Function to convert the modified windows into the journal file format
+/// # Arguments
+/// * `windows` - The modified windows
+/// # Returns
+/// * The contents of the journal file, or an error message
+pub fn serialize(windows: &ModifiedWindows) -> Result<String, String> {
+    serde_json::to_string_pretty(&JournalFile {
+        version: JOURNAL_VERSION,
+        windows,
+    })
+    .map_err(|e| format!("Failed to serialize the journal: {e}"))
+}
+
+/// Function to read the modified windows from the journal file format
+/// # Arguments
+/// * `text` - The contents of the journal file
+/// # Returns
+/// * The modified windows, or an error message if the journal is invalid or from a newer version
+pub fn deserialize(text: &str) -> Result<ModifiedWindows, String> {
+    let file: JournalFile<ModifiedWindows> =
+        serde_json::from_str(text).map_err(|e| format!("The journal is invalid: {e}"))?;
+
+    if file.version > JOURNAL_VERSION {
+        return Err(format!(
+            "The journal was written by a newer version of Fullscreenizer (version {})",
+            file.version
+        ));
+    }
+
+    Ok(file.windows)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+    use crate::geometry::Rect;
+    use crate::window_state::SavedPlacement;
+
+    fn state(hwnd: isize, title: &str) -> SavedWindowState {
+        SavedWindowState {
+            hwnd,
+            process_id: 1234,
+            class_name: "UnityWndClass".to_owned(),
+            title: title.to_owned(),
+            style: 0x14CF_0000,
+            ex_style: 0x0000_0100,
+            placement: SavedPlacement {
+                flags: 0,
+                show_cmd: 1,
+                min_position: (-1, -1),
+                max_position: (-1, -1),
+                normal_rect: Rect::new(-1800, 100, -1000, 700),
+            },
+            insert_after: 0,
+        }
+    }
+
+    /// Function to get the path of a journal file only used by one test
+    fn temp_path(name: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!(
+            "fullscreenizer-{}-{name}-{JOURNAL_FILE_NAME}",
+            process::id()
+        ));
+        fs::remove_file(&path).ok();
+        path
+    }
+
+    #[test]
+    fn serialize_round_trips() {
+        let mut windows = ModifiedWindows::default();
+        windows.record(state(0x1_0010, "Game"));
+        windows.record(state(0x2_0020, "Other game"));
+
+        let text = serialize(&windows).unwrap();
+        assert!(text.contains(&format!("\"version\": {JOURNAL_VERSION}")));
+        assert_eq!(deserialize(&text), Ok(windows));
+    }
+
+    #[test]
+    fn deserialize_rejects_newer_versions() {
+        let text = r#"{ "version": 2, "windows": [] }"#;
+        let error = deserialize(text).unwrap_err();
+        assert!(error.contains("newer version"), "{error}");
+    }
+
+    #[test]
+    fn deserialize_rejects_invalid_files() {
+        assert!(deserialize("").is_err());
+        assert!(deserialize(r#"{ "version": 1 }"#).is_err());
+        assert!(deserialize(r#"{ "version": 1, "windows": [{ "hwnd": 1 }] }"#).is_err());
+    }
+
+    #[test]
+    fn record_keeps_the_first_state() {
+        let mut journal = Journal::default();
+        journal.record(state(0x1_0010, "Before")).unwrap();
+        journal.record(state(0x1_0010, "After")).unwrap();
+
+        let titles: Vec<&str> = journal
+            .windows()
+            .iter()
+            .map(|state| state.title.as_str())
+            .collect();
+        assert_eq!(titles, ["Before"]);
+    }
+
+    #[test]
+    fn instances_sharing_the_file_keep_each_others_windows() {
+        let path = temp_path("shared");
+        let mut window = Journal::load(Some(path.clone())).unwrap();
+        let mut command_line = Journal::load(Some(path.clone())).unwrap();
+
+        command_line
+            .record(state(0x1_0010, "Applied from the command line"))
+            .unwrap();
+        window
+            .record(state(0x2_0020, "Fullscreenized in the window"))
+            .unwrap();
+
+        let on_disk = read(&path).unwrap();
+        assert!(on_disk.get(0x1_0010).is_some());
+        assert!(on_disk.get(0x2_0020).is_some());
+
+        // A window restored by one instance is no longer known to the other
+        assert!(command_line.take(0x2_0020).is_some());
+        assert_eq!(window.take(0x2_0020), None);
+        assert!(window.take(0x1_0010).is_some());
+        assert!(read(&path).unwrap().is_empty());
+
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn unreadable_files_are_not_overwritten() {
+        let path = temp_path("unreadable");
+        let mut journal = Journal::load(Some(path.clone())).unwrap();
+        fs::write(&path, r#"{ "version": 99, "windows": [] }"#).unwrap();
+
+        assert!(journal.record(state(0x1_0010, "Game")).is_err());
+        // The state is still kept in memory
+        assert!(journal.windows().get(0x1_0010).is_some());
+        assert!(fs::read_to_string(&path).unwrap().contains("99"));
+
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn windows_that_could_not_be_saved_are_kept_until_they_are() {
+        let path = temp_path("unsaved");
+        let mut journal = Journal::load(Some(path.clone())).unwrap();
+        // Writing fails while a directory is in the way of the temporary file
+        let mut temp_path = path.clone().into_os_string();
+        temp_path.push(format!(".{}.tmp", process::id()));
+        fs::create_dir(&temp_path).unwrap();
+        assert!(journal.record(state(0x1_0010, "Unsaved")).is_err());
+        assert_eq!(read(&path), Ok(ModifiedWindows::default()));
+
+        // Once the file can be written again, the next change writes the unsaved window along with the new one
+        fs::remove_dir(&temp_path).unwrap();
+        journal.record(state(0x2_0020, "Saved")).unwrap();
+        assert!(journal.windows().get(0x1_0010).is_some());
+        let on_disk = read(&path).unwrap();
+        assert!(on_disk.get(0x1_0010).is_some());
+        assert!(on_disk.get(0x2_0020).is_some());
+
+        // A window restored by another instance after it was saved is no longer added back
+        let mut other = Journal::load(Some(path.clone())).unwrap();
+        assert!(other.take(0x1_0010).is_some());
+        assert_eq!(journal.take(0x1_0010), None);
+
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn restored_windows_that_were_never_saved_are_forgotten() {
+        let path = temp_path("unsaved-restored");
+        let mut journal = Journal::load(Some(path.clone())).unwrap();
+        fs::write(&path, r#"{ "version": 99, "windows": [] }"#).unwrap();
+        assert!(journal.record(state(0x1_0010, "Unsaved")).is_err());
+        assert!(journal.take(0x1_0010).is_some());
+
+        fs::remove_file(&path).unwrap();
+        journal.record(state(0x2_0020, "Saved")).unwrap();
+        assert_eq!(read(&path).unwrap().get(0x1_0010), None);
+
+        fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,423 @@
+use core::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::monitors;
+use crate::window_info::WindowInfo;
+
+/// A column of the window list
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Column {
+    /// The title of the window, which is always shown
+    Title,
+    /// The executable name of the application
+    Exe,
+    /// The ID of the process that owns the window
+    ProcessId,
+    /// The class name of the window
+    Class,
+    /// The current size of the window
+    Size,
+    /// The monitor the window is on
+    Monitor,
+}
+
+impl Column {
+    /// The columns that can be shown after the title, in the order they are shown
+    pub const OPTIONAL: [Self; 5] = [
+        Self::Exe,
+        Self::ProcessId,
+        Self::Class,
+        Self::Size,
+        Self::Monitor,
+    ];
+
+    /// Function to get the header of the column
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Title => "Title",
+            Self::Exe => "Application",
+            Self::ProcessId => "PID",
+            Self::Class => "Class",
+            Self::Size => "Size",
+            Self::Monitor => "Monitor",
+        }
+    }
+
+    /// Function to get the width of the column before the user resizes it, in pixels at 96 DPI
+    pub const fn default_width(self) -> i32 {
+        match self {
+            Self::Title => 200,
+            Self::Exe | Self::Class => 120,
+            Self::ProcessId => 50,
+            Self::Size => 80,
+            Self::Monitor => 70,
+        }
+    }
+
+    /// Function to get the text shown in the column for a window
+    /// # Arguments
+    /// * `window` - The window
+    /// # Returns
+    /// * The text, which is empty if the property could not be queried
+    pub fn text(self, window: &WindowInfo) -> String {
+        match self {
+            Self::Title => window.title.clone(),
+            Self::Exe => window.exe_name().unwrap_or_default().to_owned(),
+            Self::ProcessId => window.process_id.to_string(),
+            Self::Class => window.class_name.clone(),
+            Self::Size => format!("{}x{}", window.rect.width(), window.rect.height()),
+            Self::Monitor => window
+                .monitor
+                .as_deref()
+                .map(monitors::short_device_name)
+                .unwrap_or_default()
+                .to_owned(),
+        }
+    }
+
+    /// Function to compare two windows by the property shown in the column
+    /// # Returns
+    /// * The order of the windows, where text is compared ignoring case and sizes are compared by area
+    pub fn compare(self, a: &WindowInfo, b: &WindowInfo) -> Ordering {
+        match self {
+            Self::ProcessId => a.process_id.cmp(&b.process_id),
+            Self::Size => {
+                let area = |window: &WindowInfo| {
+                    i64::from(window.rect.width()) * i64::from(window.rect.height())
+                };
+                area(a).cmp(&area(b))
+            }
+            Self::Title | Self::Exe | Self::Class | Self::Monitor => self
+                .text(a)
+                .to_lowercase()
+                .cmp(&self.text(b).to_lowercase()),
+        }
+    }
+}
+
+/// The order the window list is sorted in
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SortOrder {
+    /// The column the windows are sorted by
+    pub column: Column,
+    /// Whether the windows are sorted from the largest to the smallest value
+    #[serde(default)]
+    pub descending: bool,
+}
+
+/// The columns of the window list, kept in the settings
+///
+/// Without optional columns, the list shows the titles in a single column without a header, in z-order.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ListColumns {
+    /// The optional columns shown after the title
+    pub shown: Vec<Column>,
+    /// The widths of the columns resized by the user, in pixels at 96 DPI
+    pub widths: BTreeMap<Column, i32>,
+    /// The order chosen by clicking a column header, or `None` to list the windows in z-order
+    pub sort: Option<SortOrder>,
+}
+
+impl ListColumns {
+    /// Function to check whether only the titles are shown, in a single column without a header
+    pub const fn is_single_column(&self) -> bool {
+        self.shown.is_empty()
+    }
+
+    /// Function to get the columns shown in the window list
+    /// # Returns
+    /// * The title column, followed by the optional columns that are shown in their usual order
+    pub fn columns(&self) -> Vec<Column> {
+        let mut columns = vec![Column::Title];
+        columns.extend(
+            Column::OPTIONAL
+                .into_iter()
+                .filter(|column| self.shown.contains(column)),
+        );
+        columns
+    }
+
+    /// Function to check whether a column is shown
+    pub fn is_shown(&self, column: Column) -> bool {
+        column == Column::Title || self.shown.contains(&column)
+    }
+
+    /// Function to show or hide an optional column
+    ///
+    /// Hiding the column the list is sorted by puts the list back in z-order.
+    pub fn toggle(&mut self, column: Column) {
+        if column == Column::Title {
+            return;
+        }
+
+        if self.shown.contains(&column) {
+            self.shown.retain(|&shown| shown != column);
+            if self.sort.is_some_and(|sort| sort.column == column) {
+                self.sort = None;
+            }
+        } else {
+            self.shown.push(column);
+        }
+    }
+
+    /// Function to get the width of a column
+    /// # Returns
+    /// * The width chosen by the user, or the default width of the column, in pixels at 96 DPI
+    pub fn width(&self, column: Column) -> i32 {
+        self.widths
+            .get(&column)
+            .copied()
+            .unwrap_or_else(|| column.default_width())
+    }
+
+    /// Function to get the order the window list is sorted in
+    /// # Returns
+    /// * The order chosen by the user, or `None` if the list is in z-order
+    ///
+    /// The list is always in z-order when there is no header to click.
+    pub const fn sort_order(&self) -> Option<SortOrder> {
+        if self.is_single_column() {
+            None
+        } else {
+            self.sort
+        }
+    }
+
+    /// Function to change the order after a column header was clicked
+    ///
+    /// Clicking the header the list is sorted by reverses the order,
+    /// and clicking it a third time puts the list back in z-order.
+    pub fn sort_by(&mut self, column: Column) {
+        self.sort = match self.sort {
+            Some(sort) if sort.column == column && sort.descending => None,
+            Some(sort) if sort.column == column => Some(SortOrder {
+                column,
+                descending: true,
+            }),
+            _ => Some(SortOrder {
+                column,
+                descending: false,
+            }),
+        };
+    }
+
+    /// Function to get the header of a column, with an arrow if the list is sorted by it
+    pub fn header(&self, column: Column) -> String {
+        match self.sort_order() {
+            Some(sort) if sort.column == column => {
+                format!(
+                    "{} {}",
+                    column.label(),
+                    if sort.descending {
+                        '\u{25BC}'
+                    } else {
+                        '\u{25B2}'
+                    }
+                )
+            }
+            _ => column.label().to_owned(),
+        }
+    }
+
+    /// Function to sort windows in the chosen order
+    /// # Arguments
+    /// * `windows` - The windows in z-order, which are sorted in place
+    ///
+    /// Windows with equal values stay in z-order.
+    pub fn sort(&self, windows: &mut [WindowInfo]) {
+        if let Some(sort) = self.sort_order() {
+            windows.sort_by(|a, b| {
+                let order = sort.column.compare(a, b);
+                if sort.descending {
+                    order.reverse()
+                } else {
+                    order
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Rect;
+
+    fn window(hwnd: isize, title: &str, process_id: u32, width: i32, height: i32) -> WindowInfo {
+        WindowInfo {
+            hwnd,
+            title: title.to_owned(),
+            process_id,
+            rect: Rect::new(0, 0, width, height),
+            ..WindowInfo::default()
+        }
+    }
+
+    fn handles(windows: &[WindowInfo]) -> Vec<isize> {
+        windows.iter().map(|window| window.hwnd).collect()
+    }
+
+    fn columns(shown: &[Column], sort: Option<(Column, bool)>) -> ListColumns {
+        ListColumns {
+            shown: shown.to_vec(),
+            sort: sort.map(|(column, descending)| SortOrder { column, descending }),
+            ..ListColumns::default()
+        }
+    }
+
+    #[test]
+    fn columns_are_shown_in_their_usual_order() {
+        let mut list_columns = ListColumns::default();
+        assert!(list_columns.is_single_column());
+        assert_eq!(list_columns.columns(), [Column::Title]);
+
+        list_columns.toggle(Column::Monitor);
+        list_columns.toggle(Column::Exe);
+        list_columns.toggle(Column::Title);
+        assert!(!list_columns.is_single_column());
+        assert_eq!(
+            list_columns.columns(),
+            [Column::Title, Column::Exe, Column::Monitor]
+        );
+        assert!(list_columns.is_shown(Column::Title));
+        assert!(!list_columns.is_shown(Column::Class));
+
+        list_columns.toggle(Column::Exe);
+        assert_eq!(list_columns.columns(), [Column::Title, Column::Monitor]);
+    }
+
+    #[test]
+    fn hiding_the_sorted_column_clears_the_sort() {
+        let mut list_columns = columns(&[Column::Exe, Column::Size], Some((Column::Size, true)));
+        list_columns.toggle(Column::Exe);
+        assert_eq!(
+            list_columns.sort_order(),
+            Some(SortOrder {
+                column: Column::Size,
+                descending: true
+            })
+        );
+
+        list_columns.toggle(Column::Size);
+        assert_eq!(list_columns.sort, None);
+    }
+
+    #[test]
+    fn clicking_a_header_cycles_through_the_orders() {
+        let mut list_columns = columns(&[Column::Exe], None);
+        let order = |list_columns: &ListColumns| {
+            list_columns
+                .sort_order()
+                .map(|sort| (sort.column, sort.descending))
+        };
+
+        list_columns.sort_by(Column::Exe);
+        assert_eq!(order(&list_columns), Some((Column::Exe, false)));
+        list_columns.sort_by(Column::Exe);
+        assert_eq!(order(&list_columns), Some((Column::Exe, true)));
+        list_columns.sort_by(Column::Exe);
+        assert_eq!(order(&list_columns), None);
+
+        // Clicking another header sorts by it in ascending order
+        list_columns.sort_by(Column::Exe);
+        list_columns.sort_by(Column::Exe);
+        list_columns.sort_by(Column::Title);
+        assert_eq!(order(&list_columns), Some((Column::Title, false)));
+    }
+
+    #[test]
+    fn a_single_column_is_never_sorted() {
+        let mut list_columns = columns(&[], Some((Column::Title, false)));
+        assert_eq!(list_columns.sort_order(), None);
+        assert_eq!(list_columns.header(Column::Title), "Title");
+
+        let mut windows = vec![window(1, "b", 0, 1, 1), window(2, "a", 0, 1, 1)];
+        list_columns.sort(&mut windows);
+        assert_eq!(handles(&windows), [1, 2]);
+
+        list_columns.toggle(Column::Exe);
+        assert_eq!(list_columns.header(Column::Title), "Title \u{25B2}");
+        list_columns.sort(&mut windows);
+        assert_eq!(handles(&windows), [2, 1]);
+    }
+
+    #[test]
+    fn headers_show_the_direction_of_the_sorted_column() {
+        let list_columns = columns(&[Column::Exe], Some((Column::Exe, true)));
+        assert_eq!(list_columns.header(Column::Exe), "Application \u{25BC}");
+        assert_eq!(list_columns.header(Column::Title), "Title");
+    }
+
+    #[test]
+    fn windows_are_compared_by_the_column_value() {
+        let small_wide = window(1, "alpha", 20, 1000, 100);
+        let large_narrow = window(2, "Beta", 3, 500, 500);
+
+        assert_eq!(
+            Column::Size.compare(&small_wide, &large_narrow),
+            Ordering::Less
+        );
+        assert_eq!(
+            Column::ProcessId.compare(&small_wide, &large_narrow),
+            Ordering::Greater
+        );
+        assert_eq!(
+            Column::Title.compare(&small_wide, &large_narrow),
+            Ordering::Less
+        );
+        assert_eq!(
+            Column::Title.compare(&window(3, "GAME", 0, 1, 1), &window(4, "game", 0, 1, 1)),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn texts_are_shown_for_each_column() {
+        let window = WindowInfo {
+            class_name: "UnityWndClass".to_owned(),
+            exe_path: Some(r"C:\Games\game.exe".to_owned()),
+            monitor: Some(r"\\.\DISPLAY2".to_owned()),
+            ..window(1, "Game", 1234, 1920, 1080)
+        };
+
+        let texts: Vec<String> = Column::OPTIONAL
+            .iter()
+            .map(|column| column.text(&window))
+            .collect();
+        assert_eq!(
+            texts,
+            ["game.exe", "1234", "UnityWndClass", "1920x1080", "DISPLAY2"]
+        );
+        assert_eq!(Column::Exe.text(&WindowInfo::default()), "");
+    }
+
+    #[test]
+    fn sorting_keeps_equal_windows_in_z_order() {
+        let mut windows = vec![
+            window(1, "Game", 0, 800, 600),
+            window(2, "Launcher", 0, 1920, 1080),
+            window(3, "Editor", 0, 600, 800),
+            window(4, "Browser", 0, 1920, 1080),
+        ];
+
+        columns(&[Column::Size], Some((Column::Size, false))).sort(&mut windows);
+        assert_eq!(handles(&windows), [1, 3, 2, 4]);
+
+        columns(&[Column::Size], Some((Column::Size, true))).sort(&mut windows);
+        assert_eq!(handles(&windows), [2, 4, 1, 3]);
+    }
+
+    #[test]
+    fn widths_default_until_resized() {
+        let mut list_columns = ListColumns::default();
+        assert_eq!(list_columns.width(Column::Exe), 120);
+
+        list_columns.widths.insert(Column::Exe, 180);
+        assert_eq!(list_columns.width(Column::Exe), 180);
+        assert_eq!(list_columns.width(Column::Title), 200);
+    }
+}
@@ -0,0 +1,232 @@
+use std::collections::{HashMap, HashSet};
+
+/// A row of the window list
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ListRow {
+    /// The raw handle of the window shown in the row, which identifies the row
+    pub hwnd: isize,
+    /// The text of each column
+    pub texts: Vec<String>,
+}
+
+/// A change to make to the rows of a list
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RowChange<'a> {
+    /// Remove the row at this index
+    Remove(usize),
+    /// Insert a row at this index
+    Insert(usize, &'a ListRow),
+    /// Replace the text of the row at this index
+    Update(usize, &'a ListRow),
+}
+
+/// Function to get the changes turning the rows of a list into new rows
+/// # Arguments
+/// * `old` - The rows currently in the list
+/// * `new` - The rows the list should have, each window appearing once
+/// # Returns
+/// * The changes to make in order, where the indexes are valid once the previous changes are made
+///
+/// Rows are matched by their window, so that rows whose window is still listed are kept as they are,
+/// along with their selection and icon. Rows that changed place are removed and inserted again,
+/// keeping as many rows in place as possible.
+pub fn diff<'a>(old: &[ListRow], new: &'a [ListRow]) -> Vec<RowChange<'a>> {
+    let old_indexes: HashMap<isize, usize> = old
+        .iter()
+        .enumerate()
+        .map(|(index, row)| (row.hwnd, index))
+        .collect();
+
+    // The rows kept in place are the longest run of kept rows that are in the same order in both lists
+    let kept_in_order = longest_increasing(
+        &new.iter()
+            .filter_map(|row| old_indexes.get(&row.hwnd).copied())
+            .collect::<Vec<_>>(),
+    );
+
+    // Remove the other rows from the end, so that the indexes of the rows before them do not change
+    let mut changes: Vec<RowChange> = (0..old.len())
+        .rev()
+        .filter(|index| !kept_in_order.contains(index))
+        .map(RowChange::Remove)
+        .collect();
+
+    // The kept rows are now in the new order, so the rows can be inserted or updated from the top
+    for (index, row) in new.iter().enumerate() {
+        match old_indexes.get(&row.hwnd) {
+            Some(old_index) if kept_in_order.contains(old_index) => {
+                if old.get(*old_index).is_some_and(|old_row| old_row != row) {
+                    changes.push(RowChange::Update(index, row));
+                }
+            }
+            _ => changes.push(RowChange::Insert(index, row)),
+        }
+    }
+
+    changes
+}
+
+/// Function to find the longest increasing subsequence of a sequence of distinct numbers
+/// # Arguments
+/// * `values` - The numbers
+/// # Returns
+/// * The numbers in one of the longest subsequences where each number is larger than the previous one
+fn longest_increasing(values: &[usize]) -> HashSet<usize> {
+    // The index of the last value of the best subsequence of each length found so far
+    let mut tails: Vec<usize> = Vec::new();
+    // The index of the value before each value in its best subsequence
+    let mut previous: Vec<Option<usize>> = Vec::with_capacity(values.len());
+
+    for (index, value) in values.iter().enumerate() {
+        let length =
+            tails.partition_point(|&tail| values.get(tail).is_some_and(|tail| tail < value));
+        previous.push(
+            length
+                .checked_sub(1)
+                .and_then(|prev| tails.get(prev).copied()),
+        );
+        if let Some(tail) = tails.get_mut(length) {
+            *tail = index;
+        } else {
+            tails.push(index);
+        }
+    }
+
+    // Walk back from the end of the longest subsequence
+    let mut subsequence = HashSet::new();
+    let mut index = tails.last().copied();
+    while let Some(current) = index {
+        if let Some(&value) = values.get(current) {
+            subsequence.insert(value);
+        }
+        index = previous.get(current).copied().flatten();
+    }
+
+    subsequence
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(hwnd: isize, text: &str) -> ListRow {
+        ListRow {
+            hwnd,
+            texts: vec![text.to_owned()],
+        }
+    }
+
+    fn window(hwnd: isize) -> ListRow {
+        row(hwnd, &format!("Window {hwnd}"))
+    }
+
+    fn rows(hwnds: &[isize]) -> Vec<ListRow> {
+        hwnds.iter().map(|&hwnd| window(hwnd)).collect()
+    }
+
+    /// Function to make the changes to a list, as the window list does
+    fn apply(old: &[ListRow], changes: &[RowChange]) -> Vec<ListRow> {
+        let mut list = old.to_vec();
+        for change in changes {
+            match *change {
+                RowChange::Remove(index) => {
+                    list.remove(index);
+                }
+                RowChange::Insert(index, row) => list.insert(index, row.clone()),
+                RowChange::Update(index, row) => {
+                    if let Some(old_row) = list.get_mut(index) {
+                        old_row.clone_from(row);
+                    }
+                }
+            }
+        }
+        list
+    }
+
+    fn sorted(values: &HashSet<usize>) -> Vec<usize> {
+        let mut values: Vec<usize> = values.iter().copied().collect();
+        values.sort_unstable();
+        values
+    }
+
+    #[test]
+    fn unchanged_rows_need_no_changes() {
+        let old = rows(&[1, 2, 3]);
+        assert!(diff(&old, &old.clone()).is_empty());
+    }
+
+    #[test]
+    fn renamed_windows_are_updated_in_place() {
+        let old = rows(&[1, 2, 3]);
+        let renamed = row(2, "Renamed");
+        let new = vec![window(1), renamed.clone(), window(3)];
+        assert_eq!(diff(&old, &new), vec![RowChange::Update(1, &renamed)]);
+    }
+
+    #[test]
+    fn closed_windows_are_removed_from_the_end() {
+        let old = rows(&[1, 2, 3, 4]);
+        let new = rows(&[2, 4]);
+        assert_eq!(
+            diff(&old, &new),
+            vec![RowChange::Remove(2), RowChange::Remove(0)]
+        );
+    }
+
+    #[test]
+    fn opened_windows_are_inserted_at_their_place() {
+        let old = rows(&[1, 2]);
+        let new = rows(&[3, 1, 4, 2, 5]);
+        assert_eq!(
+            diff(&old, &new),
+            vec![
+                RowChange::Insert(0, &window(3)),
+                RowChange::Insert(2, &window(4)),
+                RowChange::Insert(4, &window(5)),
+            ]
+        );
+    }
+
+    #[test]
+    fn a_moved_window_is_the_only_row_removed_and_inserted() {
+        let old = rows(&[1, 2, 3, 4]);
+        let new = rows(&[4, 1, 2, 3]);
+        assert_eq!(
+            diff(&old, &new),
+            vec![RowChange::Remove(3), RowChange::Insert(0, &window(4))]
+        );
+    }
+
+    #[test]
+    fn the_changes_turn_the_old_rows_into_the_new_rows() {
+        let cases: [(&[isize], &[isize]); 6] = [
+            (&[], &[1, 2, 3]),
+            (&[1, 2, 3], &[]),
+            (&[1, 2, 3, 4, 5], &[5, 4, 3, 2, 1]),
+            (&[1, 2, 3, 4, 5], &[2, 6, 5, 1, 7, 3]),
+            (&[4, 8, 15, 16, 23, 42], &[42, 15, 4, 16, 8, 23]),
+            (&[1, 2, 3], &[3, 9, 1]),
+        ];
+
+        for (old, new) in cases {
+            let old = rows(old);
+            let mut new = rows(new);
+            if let Some(first) = new.first_mut() {
+                first.texts = vec!["Renamed".to_owned()];
+            }
+            assert_eq!(apply(&old, &diff(&old, &new)), new, "{old:?} -> {new:?}");
+        }
+    }
+
+    #[test]
+    fn longest_increasing_finds_a_longest_subsequence() {
+        assert_eq!(sorted(&longest_increasing(&[])), Vec::<usize>::new());
+        assert_eq!(sorted(&longest_increasing(&[0, 1, 2, 3])), vec![0, 1, 2, 3]);
+        assert_eq!(sorted(&longest_increasing(&[3, 2, 1, 0])).len(), 1);
+        assert_eq!(sorted(&longest_increasing(&[3, 0, 1, 4, 2])), vec![0, 1, 2]);
+        assert_eq!(
+            sorted(&longest_increasing(&[5, 1, 6, 2, 7, 3, 8])),
+            vec![1, 2, 3, 8]
+        );
+    }
+}
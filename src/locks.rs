@@ -0,0 +1,18 @@
+use std::sync::{Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Function to lock a mutex, even if a thread panicked while holding it
+///
+/// The window keeps using its shared state after a panic rather than failing every later action.
+pub fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Function to lock a read-write lock for reading, even if a thread panicked while holding it
+pub fn read<T>(rw_lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    rw_lock.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Function to lock a read-write lock for writing, even if a thread panicked while holding it
+pub fn write<T>(rw_lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    rw_lock.write().unwrap_or_else(PoisonError::into_inner)
+}
@@ -21,13 +21,108 @@
     clippy::unused_trait_names,
     clippy::useless_let_if_seq,
 )]
+// Without a window system only the tests use the modules
+#![cfg_attr(not(any(windows, feature = "x11")), allow(dead_code))]
 
+// The window only exists on Windows, so the modules and items only it uses are unused on other platforms
+#[cfg(windows)]
+mod backdrop;
+mod cli;
+mod exclusions;
+#[cfg(windows)]
+mod exclusions_dialog;
+// The in-memory window system the window operations are tested against
+#[cfg(test)]
+#[allow(dead_code, reason = "Each test only uses part of the fake")]
+mod fake_system;
+#[cfg_attr(not(windows), allow(dead_code))]
+mod game_detection;
+mod geometry;
+#[cfg(any(windows, feature = "x11"))]
+mod headless;
+mod hotkeys;
+mod journal;
+#[cfg_attr(not(windows), allow(dead_code))]
+mod list_columns;
+#[cfg_attr(not(windows), allow(dead_code))]
+mod list_diff;
+#[cfg(windows)]
+mod locks;
+mod monitors;
+#[cfg(windows)]
 mod my_window;
+mod paths;
+mod profiles;
+mod rect_expr;
+mod scaling;
+mod settings;
+#[cfg(windows)]
+mod settings_dialog;
+#[cfg(windows)]
+mod theme;
+#[cfg(windows)]
+mod tray;
+#[cfg_attr(not(windows), allow(dead_code))]
+mod tray_menu;
+#[cfg_attr(not(windows), allow(dead_code))]
+mod watchdog;
+mod watcher;
+#[cfg(windows)]
+mod win32_system;
+#[cfg(windows)]
+mod win_events;
+#[cfg_attr(not(windows), allow(dead_code))]
+mod window_filter;
+mod window_info;
+mod window_ops;
+mod window_state;
+mod window_system;
+#[cfg(all(not(windows), feature = "x11"))]
+mod x11_system;
 
+#[cfg(any(windows, feature = "x11"))]
+use std::env;
+use std::process::ExitCode;
+
+#[cfg(any(windows, feature = "x11"))]
+use cli::{EXIT_USAGE, USAGE};
+#[cfg(windows)]
 use my_window::MyWindow;
+#[cfg(windows)]
 use winsafe::{self as w, co, prelude::*};
 
-fn main() {
+#[cfg(any(windows, feature = "x11"))]
+fn main() -> ExitCode {
+    match cli::parse_args(env::args().skip(1)) {
+        // Run the command without showing a window
+        Ok(Some(command)) => headless::run(command),
+        // Show the window if no command was given
+        Ok(None) => run_gui(),
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            ExitCode::from(EXIT_USAGE)
+        }
+    }
+}
+
+#[cfg(not(any(windows, feature = "x11")))]
+fn main() -> ExitCode {
+    eprintln!(
+        "Fullscreenizer was built without a window system, enable the x11 feature to use it on Linux"
+    );
+    ExitCode::FAILURE
+}
+
+#[cfg(all(not(windows), feature = "x11"))]
+fn run_gui() -> ExitCode {
+    eprintln!(
+        "The Fullscreenizer window is only available on Windows, use a command instead\n\n{USAGE}"
+    );
+    ExitCode::from(EXIT_USAGE)
+}
+
+#[cfg(windows)]
+fn run_gui() -> ExitCode {
     if let Err(e) = MyWindow::new().run() {
         w::HWND::NULL
             .TaskDialog(
@@ -38,5 +133,8 @@ fn main() {
                 w::IconRes::Error,
             )
             .unwrap();
+        return ExitCode::FAILURE;
     }
+
+    ExitCode::SUCCESS
 }
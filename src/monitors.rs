@@ -0,0 +1,536 @@
+use core::iter;
+
+use serde::{Deserialize, Serialize};
+
+use crate::geometry::{self, Rect};
+
+/// A display monitor, as reported by the system
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Monitor {
+    /// The device name of the monitor (e.g. `\\.\DISPLAY1`)
+    pub device_name: String,
+    /// The full area of the monitor, in virtual-desktop coordinates
+    pub rect: Rect,
+    /// The area of the monitor left uncovered by the taskbar and docked app bars, in virtual-desktop coordinates
+    pub work_area: Rect,
+    /// Whether the monitor is the primary monitor
+    pub is_primary: bool,
+}
+
+/// Prefix of the device names of monitors
+const DEVICE_PREFIX: &str = "\\\\.\\";
+
+impl Monitor {
+    /// Function to get the device name of the monitor without the `\\.\` prefix (e.g. `DISPLAY1`)
+    pub fn short_name(&self) -> &str {
+        short_device_name(&self.device_name)
+    }
+
+    /// Function to get the text shown for the monitor in the monitor picker
+    /// # Returns
+    /// * The monitor's short device name, resolution and position (e.g. `DISPLAY2: 1920x1080 at (-1920, 0)`)
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn label(&self) -> String {
+        format!(
+            "{}: {}x{} at ({}, {}){}",
+            self.short_name(),
+            self.rect.width(),
+            self.rect.height(),
+            self.rect.left,
+            self.rect.top,
+            if self.is_primary { " (primary)" } else { "" }
+        )
+    }
+}
+
+/// Function to remove the `\\.\` prefix from the device name of a monitor
+/// # Arguments
+/// * `device_name` - The device name (e.g. `\\.\DISPLAY1`)
+/// # Returns
+/// * The device name without the prefix (e.g. `DISPLAY1`)
+pub fn short_device_name(device_name: &str) -> &str {
+    device_name.trim_start_matches(DEVICE_PREFIX)
+}
+
+/// The monitor a window should be sent to
+///
+/// In the settings file, a specific monitor is stored as its device name, a span as a table with its name
+/// (e.g. `{ span = "Triple" }`) and the nearest monitor is left out.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "Option<StoredChoice>", into = "Option<StoredChoice>")]
+pub enum MonitorChoice {
+    /// The monitor the window is currently on
+    #[default]
+    Nearest,
+    /// A specific monitor, identified by its device name
+    Device(String),
+    /// Several monitors, identified by the name of their span in the settings
+    Span(String),
+}
+
+/// A monitor choice other than the nearest monitor, as stored in the settings file
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum StoredChoice {
+    Device(String),
+    Span { span: String },
+}
+
+impl From<Option<StoredChoice>> for MonitorChoice {
+    fn from(choice: Option<StoredChoice>) -> Self {
+        match choice {
+            None => Self::Nearest,
+            Some(StoredChoice::Device(device_name)) => Self::Device(device_name),
+            Some(StoredChoice::Span { span }) => Self::Span(span),
+        }
+    }
+}
+
+impl From<MonitorChoice> for Option<StoredChoice> {
+    fn from(choice: MonitorChoice) -> Self {
+        match choice {
+            MonitorChoice::Nearest => None,
+            MonitorChoice::Device(device_name) => Some(StoredChoice::Device(device_name)),
+            MonitorChoice::Span(span) => Some(StoredChoice::Span { span }),
+        }
+    }
+}
+
+/// Monitors a window can be spanned across, as one large monitor
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MonitorSpan {
+    /// The name of the span, shown in the monitor picker
+    pub name: String,
+    /// The device names of the monitors, with or without the `\\.\` prefix, or empty to span all monitors
+    pub monitors: Vec<String>,
+    /// The pixels hidden by the bezels at each gap between monitors, as taken by [`geometry::span_rect`]
+    pub bezels: Vec<i32>,
+}
+
+impl MonitorSpan {
+    /// Function to get the span of all connected monitors, which the settings start with
+    pub fn all_monitors() -> Self {
+        Self {
+            name: "All monitors".to_owned(),
+            ..Self::default()
+        }
+    }
+}
+
+/// The area a window is sent to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TargetArea {
+    /// The area, in virtual-desktop coordinates
+    pub rect: Rect,
+    /// Whether the monitors of the area form a rectangle, which a single monitor always does
+    ///
+    /// If they do not, parts of a window covering the area are not shown on any monitor.
+    pub rectangular: bool,
+}
+
+/// The list of monitors shown in the monitor picker
+///
+/// The first entry of the picker is always [`MonitorChoice::Nearest`], followed by one entry per monitor,
+/// then one entry per span of at least two connected monitors.
+#[derive(Clone, Debug, Default)]
+pub struct MonitorList {
+    monitors: Vec<Monitor>,
+    spans: Vec<MonitorSpan>,
+}
+
+impl MonitorList {
+    /// Text of the picker entry for [`MonitorChoice::Nearest`]
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub const NEAREST_LABEL: &'static str = "Window's current monitor";
+
+    pub const fn new(monitors: Vec<Monitor>) -> Self {
+        Self {
+            monitors,
+            spans: Vec::new(),
+        }
+    }
+
+    /// Function to add the spans of the settings to the list
+    /// # Arguments
+    /// * `spans` - The spans, of which those spanning less than two connected monitors are left out
+    /// # Returns
+    /// * The list, with the spans
+    pub fn with_spans(mut self, spans: &[MonitorSpan]) -> Self {
+        self.spans = spans
+            .iter()
+            .filter(|span| self.span_monitors(span).len() >= 2)
+            .cloned()
+            .collect();
+        self
+    }
+
+    /// Function to get the entries of the monitor picker, in order
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn labels(&self) -> Vec<String> {
+        iter::once(Self::NEAREST_LABEL.to_owned())
+            .chain(self.monitors.iter().map(Monitor::label))
+            .chain(self.spans.iter().map(|span| self.span_label(span)))
+            .collect()
+    }
+
+    /// Function to get the text shown for a span in the monitor picker
+    /// # Returns
+    /// * The span's name and resolution (e.g. `Span: Triple (5760x1080)`)
+    #[cfg_attr(not(windows), allow(dead_code))]
+    fn span_label(&self, span: &MonitorSpan) -> String {
+        let rect = geometry::span_rect(&self.span_monitors(span), &span.bezels).unwrap_or_default();
+        format!("Span: {} ({}x{})", span.name, rect.width(), rect.height())
+    }
+
+    /// Function to convert an index in the monitor picker into a monitor choice
+    /// # Arguments
+    /// * `index` - The index of the selected picker entry
+    /// # Returns
+    /// * The matching choice, or [`MonitorChoice::Nearest`] if the index is out of range
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn choice_at(&self, index: usize) -> MonitorChoice {
+        let Some(index) = index.checked_sub(1) else {
+            return MonitorChoice::Nearest;
+        };
+
+        if let Some(monitor) = self.monitors.get(index) {
+            return MonitorChoice::Device(monitor.device_name.clone());
+        }
+        index
+            .checked_sub(self.monitors.len())
+            .and_then(|i| self.spans.get(i))
+            .map_or(MonitorChoice::Nearest, |span| {
+                MonitorChoice::Span(span.name.clone())
+            })
+    }
+
+    /// Function to convert a monitor choice into an index in the monitor picker
+    /// # Arguments
+    /// * `choice` - The monitor choice
+    /// # Returns
+    /// * The index of the picker entry, or `None` if the chosen monitor is no longer connected
+    ///   or the chosen span no longer exists
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn index_of(&self, choice: &MonitorChoice) -> Option<usize> {
+        match choice {
+            MonitorChoice::Nearest => Some(0),
+            MonitorChoice::Device(name) => self
+                .monitors
+                .iter()
+                .position(|monitor| monitor.device_name.eq_ignore_ascii_case(name))
+                .map(|i| i + 1),
+            MonitorChoice::Span(name) => self
+                .spans
+                .iter()
+                .position(|span| span.name.to_lowercase() == name.to_lowercase())
+                .map(|i| i + 1 + self.monitors.len()),
+        }
+    }
+
+    /// Function to find a monitor by name
+    /// # Arguments
+    /// * `name` - The device name of the monitor, with or without the `\\.\` prefix (e.g. `DISPLAY2`)
+    /// # Returns
+    /// * The monitor, or `None` if no connected monitor has that name
+    pub fn find(&self, name: &str) -> Option<&Monitor> {
+        let name = name.trim_start_matches(DEVICE_PREFIX);
+        self.monitors
+            .iter()
+            .find(|monitor| monitor.short_name().eq_ignore_ascii_case(name))
+    }
+
+    /// Function to find a span by name
+    /// # Arguments
+    /// * `name` - The name of the span, compared case-insensitively
+    /// # Returns
+    /// * The span, or `None` if no span of at least two connected monitors has that name
+    pub fn find_span(&self, name: &str) -> Option<&MonitorSpan> {
+        self.spans
+            .iter()
+            .find(|span| span.name.to_lowercase() == name.to_lowercase())
+    }
+
+    /// Function to get the monitors of a span
+    /// # Returns
+    /// * The full areas of the span's connected monitors, in the order they are listed
+    fn span_monitors(&self, span: &MonitorSpan) -> Vec<Rect> {
+        if span.monitors.is_empty() {
+            return self.monitors.iter().map(|monitor| monitor.rect).collect();
+        }
+
+        self.monitors
+            .iter()
+            .filter(|monitor| {
+                span.monitors
+                    .iter()
+                    .any(|name| short_device_name(name).eq_ignore_ascii_case(monitor.short_name()))
+            })
+            .map(|monitor| monitor.rect)
+            .collect()
+    }
+
+    /// Function to find the area a window should be sent to
+    /// # Arguments
+    /// * `choice` - The monitor choice
+    /// * `window` - The current rectangle of the window, in virtual-desktop coordinates
+    /// * `work_area` - Whether to only use the work area of a single monitor, leaving the taskbar uncovered
+    /// # Returns
+    /// * The chosen monitor or span, or `None` if there are no monitors
+    ///
+    /// If the chosen monitor is no longer connected or the chosen span no longer exists,
+    /// the monitor nearest to the window is used instead. Spans always cover their monitors entirely.
+    pub fn resolve_area(
+        &self,
+        choice: &MonitorChoice,
+        window: Rect,
+        work_area: bool,
+    ) -> Option<TargetArea> {
+        if let MonitorChoice::Span(name) = choice
+            && let Some(span) = self.find_span(name)
+        {
+            let monitors = self.span_monitors(span);
+            return geometry::span_rect(&monitors, &span.bezels).map(|rect| TargetArea {
+                rect,
+                rectangular: geometry::forms_rectangle(&monitors),
+            });
+        }
+
+        self.resolve(choice, window).map(|monitor| TargetArea {
+            rect: if work_area {
+                monitor.work_area
+            } else {
+                monitor.rect
+            },
+            rectangular: true,
+        })
+    }
+
+    /// Function to find the monitor a window should be sent to
+    /// # Arguments
+    /// * `choice` - The monitor choice
+    /// * `window` - The current rectangle of the window, in virtual-desktop coordinates
+    /// # Returns
+    /// * The chosen monitor, or `None` if there are no monitors
+    ///
+    /// If the chosen monitor is no longer connected, or a span is chosen,
+    /// the monitor nearest to the window is used instead.
+    pub fn resolve(&self, choice: &MonitorChoice, window: Rect) -> Option<&Monitor> {
+        if let MonitorChoice::Device(name) = choice
+            && let Some(monitor) = self
+                .monitors
+                .iter()
+                .find(|monitor| monitor.device_name.eq_ignore_ascii_case(name))
+        {
+            return Some(monitor);
+        }
+
+        self.nearest(window)
+    }
+
+    /// Function to find the monitor nearest to a window, mirroring `MONITOR_DEFAULTTONEAREST`
+    /// # Arguments
+    /// * `window` - The current rectangle of the window, in virtual-desktop coordinates
+    /// # Returns
+    /// * The monitor with the largest intersection with the window, or if there is none,
+    ///   the monitor closest to the window's center
+    pub fn nearest(&self, window: Rect) -> Option<&Monitor> {
+        let best_overlap = self
+            .monitors
+            .iter()
+            .map(|monitor| (monitor, geometry::intersection_area(monitor.rect, window)))
+            .filter(|&(_, area)| area > 0)
+            // Prefer the first monitor on ties, like the system does
+            .fold(
+                None::<(&Monitor, i64)>,
+                |best, (monitor, area)| match best {
+                    Some((_, best_area)) if best_area >= area => best,
+                    _ => Some((monitor, area)),
+                },
+            );
+        if let Some((monitor, _)) = best_overlap {
+            return Some(monitor);
+        }
+
+        let center_x = i64::from(window.left) + i64::from(window.width()) / 2;
+        let center_y = i64::from(window.top) + i64::from(window.height()) / 2;
+        self.monitors
+            .iter()
+            .min_by_key(|monitor| distance_squared(monitor.rect, center_x, center_y))
+    }
+}
+
+/// Function to compute the squared distance from a point to the closest point of a rectangle
+fn distance_squared(rect: Rect, x: i64, y: i64) -> i64 {
+    let dx = (i64::from(rect.left) - x).max(0) + (x - i64::from(rect.right)).max(0);
+    let dy = (i64::from(rect.top) - y).max(0) + (y - i64::from(rect.bottom)).max(0);
+    dx * dx + dy * dy
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(name: &str, rect: Rect, is_primary: bool) -> Monitor {
+        Monitor {
+            device_name: format!("{DEVICE_PREFIX}{name}"),
+            rect,
+            work_area: Rect::new(rect.left, rect.top, rect.right, rect.bottom - 40),
+            is_primary,
+        }
+    }
+
+    /// A primary monitor with a second monitor to its left
+    fn dual_monitors() -> MonitorList {
+        MonitorList::new(vec![
+            monitor("DISPLAY1", Rect::new(0, 0, 1920, 1080), true),
+            monitor("DISPLAY2", Rect::new(-1920, 0, 0, 1080), false),
+        ])
+        .with_spans(&[MonitorSpan::all_monitors()])
+    }
+
+    fn device(name: &str) -> MonitorChoice {
+        MonitorChoice::Device(format!("{DEVICE_PREFIX}{name}"))
+    }
+
+    #[test]
+    fn choice_at_lists_nearest_then_monitors_then_spans() {
+        let monitors = dual_monitors();
+        assert_eq!(monitors.choice_at(0), MonitorChoice::Nearest);
+        assert_eq!(monitors.choice_at(1), device("DISPLAY1"));
+        assert_eq!(monitors.choice_at(2), device("DISPLAY2"));
+        assert_eq!(
+            monitors.choice_at(3),
+            MonitorChoice::Span("All monitors".to_owned())
+        );
+        assert_eq!(monitors.choice_at(4), MonitorChoice::Nearest);
+        assert_eq!(monitors.labels().len(), 4);
+    }
+
+    #[test]
+    fn index_of_is_the_inverse_of_choice_at() {
+        let monitors = dual_monitors();
+        for index in 0..4 {
+            assert_eq!(monitors.index_of(&monitors.choice_at(index)), Some(index));
+        }
+    }
+
+    #[test]
+    fn index_of_ignores_case_and_misses_disconnected_monitors() {
+        let monitors = dual_monitors();
+        assert_eq!(monitors.index_of(&device("display2")), Some(2));
+        assert_eq!(
+            monitors.index_of(&MonitorChoice::Span("ALL MONITORS".to_owned())),
+            Some(3)
+        );
+        assert_eq!(monitors.index_of(&device("DISPLAY3")), None);
+        assert_eq!(
+            monitors.index_of(&MonitorChoice::Span("Triple".to_owned())),
+            None
+        );
+    }
+
+    #[test]
+    fn spans_of_a_single_monitor_are_left_out() {
+        let monitors =
+            MonitorList::new(vec![monitor("DISPLAY1", Rect::new(0, 0, 1920, 1080), true)])
+                .with_spans(&[MonitorSpan::all_monitors()]);
+        assert_eq!(monitors.labels().len(), 2);
+        assert_eq!(monitors.find_span("All monitors"), None);
+    }
+
+    #[test]
+    fn nearest_prefers_the_largest_overlap() {
+        let monitors = dual_monitors();
+        let straddling = Rect::new(-300, 100, 500, 700);
+        assert_eq!(
+            monitors.nearest(straddling).map(Monitor::short_name),
+            Some("DISPLAY1")
+        );
+        let mostly_left = Rect::new(-700, 100, 100, 700);
+        assert_eq!(
+            monitors.nearest(mostly_left).map(Monitor::short_name),
+            Some("DISPLAY2")
+        );
+    }
+
+    #[test]
+    fn nearest_falls_back_to_the_closest_monitor_off_screen() {
+        let monitors = dual_monitors();
+        let far_left = Rect::new(-5000, 200, -4000, 800);
+        assert_eq!(
+            monitors.nearest(far_left).map(Monitor::short_name),
+            Some("DISPLAY2")
+        );
+        let below_right = Rect::new(1800, 2000, 2400, 2400);
+        assert_eq!(
+            monitors.nearest(below_right).map(Monitor::short_name),
+            Some("DISPLAY1")
+        );
+        assert_eq!(MonitorList::default().nearest(far_left), None);
+    }
+
+    #[test]
+    fn resolve_area_uses_the_chosen_monitor_or_the_nearest() {
+        let monitors = dual_monitors();
+        let window = Rect::new(100, 100, 900, 700);
+
+        let chosen = monitors.resolve_area(&device("DISPLAY2"), window, false);
+        assert_eq!(
+            chosen.map(|area| area.rect),
+            Some(Rect::new(-1920, 0, 0, 1080))
+        );
+
+        let nearest = monitors.resolve_area(&MonitorChoice::Nearest, window, false);
+        assert_eq!(
+            nearest.map(|area| area.rect),
+            Some(Rect::new(0, 0, 1920, 1080))
+        );
+
+        let disconnected = monitors.resolve_area(&device("DISPLAY3"), window, false);
+        assert_eq!(disconnected, nearest);
+    }
+
+    #[test]
+    fn resolve_area_uses_the_work_area_of_single_monitors_only() {
+        let monitors = dual_monitors();
+        let window = Rect::new(100, 100, 900, 700);
+
+        let work_area = monitors.resolve_area(&device("DISPLAY2"), window, true);
+        assert_eq!(
+            work_area,
+            Some(TargetArea {
+                rect: Rect::new(-1920, 0, 0, 1040),
+                rectangular: true,
+            })
+        );
+
+        let span = monitors.resolve_area(
+            &MonitorChoice::Span("All monitors".to_owned()),
+            window,
+            true,
+        );
+        assert_eq!(
+            span,
+            Some(TargetArea {
+                rect: Rect::new(-1920, 0, 1920, 1080),
+                rectangular: true,
+            })
+        );
+    }
+
+    #[test]
+    fn resolve_area_falls_back_to_the_nearest_monitor_for_unknown_spans() {
+        let monitors = dual_monitors();
+        let window = Rect::new(-900, 100, -100, 700);
+        let area = monitors.resolve_area(&MonitorChoice::Span("Triple".to_owned()), window, false);
+        assert_eq!(
+            area.map(|area| area.rect),
+            Some(Rect::new(-1920, 0, 0, 1080))
+        );
+        assert_eq!(
+            MonitorList::default().resolve_area(&MonitorChoice::Nearest, window, false),
+            None
+        );
+    }
+}
@@ -1,31 +1,106 @@
 extern crate alloc;
 
 use alloc::sync::Arc;
+use core::iter;
 use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 use std::sync::{Mutex, MutexGuard, RwLock};
+use std::time::Instant;
 use winsafe::msg::WndMsg;
+use winsafe::msg::em::SetCueBanner;
 use winsafe::msg::wm::SetFont;
 
 use winsafe::co::{
-    BST, CHARSET, CLIP, FW, GCLP, HWND_PLACE, ICON_SZ, ILC, KEY, LVS, LVS_EX, LVSIL, MONITOR,
-    OUT_PRECIS, PITCH, QUALITY, REG_OPTION, SBB, SS, SWP, TDCBF, WM, WS, WS_EX,
+    BST, CBS, CHARSET, CLIP, DLGC, DLGID, ERROR, ES, FW, ILC, KEY, LVIF, LVIS, LVS, LVS_EX, LVSIL,
+    MOD, OUT_PRECIS, PITCH, QUALITY, REG_OPTION, SBB, SIZE_R, SS, SW, SWP, TDCBF, TPM, VK, WM, WS,
+    WS_EX,
 };
 use winsafe::guard::{DeleteObjectGuard, DestroyIconGuard, ImageListDestroyGuard};
 use winsafe::gui::{
-    Button, ButtonOpts, CheckBox, CheckBoxOpts, Horz, Icon, Label, LabelOpts, ListView,
-    ListViewOpts, Vert, WindowMain, WindowMainOpts, dpi,
+    Button, ButtonOpts, CheckBox, CheckBoxOpts, ComboBox, ComboBoxOpts, Edit, EditOpts, Horz, Icon,
+    Label, LabelOpts, ListView, ListViewOpts, Vert, WindowMain, WindowMainOpts, dpi, dpi_x,
+};
+use winsafe::msg::lvm::{
+    DeleteColumn, InsertItem, SetBkColor, SetImageList, SetTextBkColor, SetTextColor,
 };
-use winsafe::msg::lvm::{SetBkColor, SetImageList, SetTextBkColor, SetTextColor};
 use winsafe::prelude::{
-    GuiEventsButton as _, GuiEventsLabel as _, GuiEventsParent as _, GuiEventsWindow as _,
-    GuiWindow as _, Handle as _,
+    GuiEventsButton as _, GuiEventsEdit as _, GuiEventsLabel as _, GuiEventsListView as _,
+    GuiEventsParent as _, GuiEventsWindow as _, GuiWindow as _, Handle as _,
 };
 use winsafe::{
-    self as w, AdjustWindowRectExForDpi, COLORREF, DwmAttr, EnumWindows, HBRUSH, HFONT, HICON,
-    HIMAGELIST, HKEY, HWND, HwndPlace, IconRes, POINT, RECT, RegistryValue, SIZE,
+    self as w, GetCursorPos, HBRUSH, HFONT, HICON, HIMAGELIST, HKEY, HMENU, HWND, HwndPlace,
+    IconRes, IdPos, LVITEM, MenuItem, POINT, RECT, RegistryValue, SIZE, WString,
 };
 
+use crate::backdrop::Backdrop;
+use crate::exclusions::{ExclusionField, ExclusionKind, ExclusionList, ExclusionRule};
+use crate::exclusions_dialog::ExclusionsDialog;
+use crate::game_detection::GAME_BADGE;
+use crate::geometry::Rect;
+use crate::hotkeys;
+use crate::journal::{self, Journal};
+use crate::list_columns::Column;
+use crate::list_diff::{self, ListRow, RowChange};
+use crate::locks::{lock, read, write};
+use crate::monitors::{MonitorChoice, MonitorList};
+use crate::profiles::Profile;
+use crate::scaling::{ScaleMode, Sizing};
+use crate::settings::{self, AppChoices, Settings, Theme};
+use crate::settings_dialog::SettingsDialog;
+use crate::tray;
+use crate::tray_menu::{self, MAX_MENU_WINDOWS, TrayCommand};
+use crate::watchdog::{Decision, DesiredState, Watchdog};
+use crate::watcher::{self, ProfileWatcher};
+use crate::win_events::{self, WindowChangeHooks, WindowListHooks};
+use crate::win32_system::Win32System;
+use crate::window_filter;
+use crate::window_info::{self, WindowInfo};
+use crate::window_ops;
+use crate::window_system::{BORDERLESS_STYLE, WindowSystem as _};
+
+/// Command ID of the "Fullscreenize" item of the process list's context menu
+const CMD_FULLSCREENIZE: u16 = 1;
+/// Command ID of the "Restore" item of the process list's context menu
+const CMD_RESTORE: u16 = 2;
+/// Command ID of the "Exclude this application" item of the process list's context menu
+const CMD_EXCLUDE_EXE: u16 = 3;
+/// Command ID of the "Exclude this title" item of the process list's context menu
+const CMD_EXCLUDE_TITLE: u16 = 4;
+/// Command ID of the "Edit exclusions" item of the process list's context menu
+const CMD_EDIT_EXCLUSIONS: u16 = 5;
+/// Command ID of the "Save as profile" item of the process list's context menu
+const CMD_SAVE_PROFILE: u16 = 6;
+/// Command ID of the "Watchdog log" item of the process list's context menu
+const CMD_WATCHDOG_LOG: u16 = 7;
+/// Command ID of the first item of the "Columns" submenu of the process list's context menu,
+/// followed by one ID per optional column
+const CMD_COLUMN_FIRST: u16 = 100;
+
+/// Number of icons of windows that are no longer listed to keep cached before the process list is rebuilt
+const MAX_STALE_ICONS: usize = 64;
+
+/// ID of the timer checking for new windows to apply the profiles to
+const PROFILE_TIMER_ID: usize = 1;
+/// ID of the timer refreshing the process list once windows stopped changing
+const LIVE_UPDATE_TIMER_ID: usize = 2;
+
+/// Time to wait after a window was opened, closed or renamed before refreshing the process list, in milliseconds
+///
+/// Each change restarts the wait, so that a burst of changes causes a single refresh.
+const LIVE_UPDATE_DELAY_MS: u32 = 250;
+
+/// ID of the global hotkey fullscreenizing the foreground window
+const HOTKEY_FULLSCREENIZE_ID: i32 = 1;
+/// ID of the global hotkey restoring the foreground window
+const HOTKEY_RESTORE_ID: i32 = 2;
+
+/// Text of the preset picker entry sizing windows with the scaling picker instead of a preset
+const NO_PRESET_LABEL: &str = "No preset";
+
+/// `SPI_SETWORKAREA`, the `wParam` of the `WM_SETTINGCHANGE` message sent when the work area changes
+const SPI_SETWORKAREA: usize = 0x002F;
+
 #[inline(always)]
 fn dpi_scale_val(val: i32, dpi: u32) -> i32 {
     w::MulDiv(val, dpi as i32, 96)
@@ -36,38 +111,88 @@ fn dpi_scale(val: (i32, i32), dpi: u32) -> (i32, i32) {
     (dpi_scale_val(val.0, dpi), dpi_scale_val(val.1, dpi))
 }
 
+/// How a window filling the work area of its monitor was fullscreenized, to fit it again when the work area changes
+#[derive(Clone, Debug)]
+struct WorkAreaPlacement {
+    /// The monitor the window was sent to
+    choice: MonitorChoice,
+    /// How the window was sized on the monitor
+    sizing: Sizing,
+    /// The size of the client area of the window before it was fullscreenized
+    client_size: (i32, i32),
+    /// Whether the "stay on top" flag was applied to the window
+    topmost: bool,
+}
+
+/// The icon of a listed window, kept so that it is added to the image lists created when the process list is refreshed
+struct CachedIcon {
+    /// The raw handle of the window
+    hwnd: isize,
+    icon: DestroyIconGuard,
+    /// The index of the icon in the current image list, or `None` if it could not be added
+    index: Option<u32>,
+}
+
 #[derive(Clone)]
 pub struct MyWindow {
     // Window elements
     wnd: WindowMain,
     label: Label,
+    filter_edit: Edit,
     process_list: ListView,
     top_toggle: CheckBox,
     top_label: Label,
     btn_canvas: Label,
     refresh_btn: Button,
-    help_btn: Button,
+    settings_btn: Button,
+    restore_btn: Button,
     fullscreenize_btn: Button,
+    monitor_picker: ComboBox,
+    scale_picker: ComboBox,
+    preset_picker: ComboBox,
 
     // Settings
     is_dark_mode: Arc<AtomicBool>,
     use_icons: Arc<AtomicBool>,
-    excluded_apps: Arc<[String]>,
+    settings: Arc<Mutex<Settings>>,
+
+    // Tray icon
+    tray_visible: Arc<AtomicBool>,
+    exiting: Arc<AtomicBool>,
 
     // Shared resources
     app_font: Rc<RwLock<Option<DeleteObjectGuard<HFONT>>>>,
     app_dpi: Arc<AtomicU32>,
     background_hbrush: Arc<Mutex<Option<DeleteObjectGuard<HBRUSH>>>>,
+    field_hbrush: Arc<Mutex<Option<DeleteObjectGuard<HBRUSH>>>>,
     imagelist: Arc<Mutex<Option<ImageListDestroyGuard>>>,
-    window_icons: Arc<Mutex<Vec<DestroyIconGuard>>>,
+    window_icons: Arc<Mutex<Vec<CachedIcon>>>,
+    list_rows: Rc<RwLock<Vec<ListRow>>>,
+    list_has_icons: Arc<AtomicBool>,
+    shown_columns: Rc<RwLock<Vec<Column>>>,
+    monitors: Arc<Mutex<MonitorList>>,
+    journal: Arc<Mutex<Journal>>,
+    profile_watcher: Arc<Mutex<ProfileWatcher>>,
+    watchdog: Arc<Mutex<Watchdog>>,
+    window_change_hooks: Rc<RwLock<Option<WindowChangeHooks>>>,
+    window_list_hooks: Rc<RwLock<Option<WindowListHooks>>>,
+    backdrops: Rc<RwLock<HashMap<isize, Backdrop>>>,
+    work_area_windows: Rc<RwLock<HashMap<isize, WorkAreaPlacement>>>,
 }
 
 impl MyWindow {
     pub fn new() -> Self {
+        // The settings are loaded first, as they decide the initial state of some controls
+        // If they cannot be loaded, the defaults are used and not saved, so that the file is not overwritten
+        let settings = Settings::load(settings::default_path()).unwrap_or_else(|e| {
+            eprintln!("Failed to load the settings, changes will not be saved: {e}");
+            Settings::default()
+        });
+
         let wnd = WindowMain::new(WindowMainOpts {
             title: "Fullscreenizer",
             class_icon: Icon::Id(101),
-            size: dpi(305, 400),
+            size: dpi(390, 428),
             style: WS::OVERLAPPEDWINDOW | WS::CLIPCHILDREN,
             ..Default::default()
         });
@@ -86,11 +211,23 @@ impl MyWindow {
             },
         );
 
+        // Edit box to filter the process list
+        let filter_edit = Edit::new(
+            &wnd,
+            EditOpts {
+                position: dpi(200, 5),
+                width: dpi_x(170),
+                control_style: ES::AUTOHSCROLL | ES::NOHIDESEL,
+                window_style: WS::CHILD | WS::VISIBLE | WS::TABSTOP | WS::GROUP | WS::CLIPSIBLINGS,
+                ..Default::default()
+            },
+        );
+
         let process_list = ListView::new(
             &wnd,
             ListViewOpts {
-                position: dpi(8, 29),
-                size: dpi(289, 307),
+                position: dpi(8, 35),
+                size: dpi(289, 279),
                 // Make the single column very wide, so that the end of the column is never visible
                 columns: &[("", 32000)],
                 control_style: LVS::NOSORTHEADER
@@ -109,10 +246,14 @@ impl MyWindow {
         let top_toggle = CheckBox::new(
             &wnd,
             CheckBoxOpts {
-                position: dpi(8, 342),
+                position: dpi(8, 314),
                 size: dpi(20, 20),
                 window_style: WS::CHILD | WS::VISIBLE | WS::TABSTOP | WS::GROUP | WS::CLIPSIBLINGS,
-                check_state: BST::UNCHECKED,
+                check_state: if settings.topmost {
+                    BST::CHECKED
+                } else {
+                    BST::UNCHECKED
+                },
                 ..Default::default()
             },
         );
@@ -124,7 +265,7 @@ impl MyWindow {
             &wnd,
             LabelOpts {
                 text: "Apply \"stay on top\" flag to avoid taskbar flickering",
-                position: dpi(32, 342),
+                position: dpi(32, 314),
                 size: dpi(338, 20),
                 control_style: SS::LEFTNOWORDWRAP | SS::NOTIFY,
                 window_style: WS::CHILD | WS::VISIBLE,
@@ -132,13 +273,56 @@ impl MyWindow {
             },
         );
 
+        // Dropdown to choose the monitor the selected window is sent to
+        let monitor_picker = ComboBox::new(
+            &wnd,
+            ComboBoxOpts {
+                position: dpi(8, 340),
+                width: dpi_x(174),
+                control_style: CBS::DROPDOWNLIST,
+                window_style: WS::CHILD | WS::VISIBLE | WS::TABSTOP | WS::GROUP | WS::CLIPSIBLINGS,
+                items: &[MonitorList::NEAREST_LABEL],
+                selected_item: Some(0),
+                ..Default::default()
+            },
+        );
+
+        // Dropdown to choose how the selected window is sized on its monitor
+        let scale_labels = ScaleMode::ALL.map(ScaleMode::label);
+        let scale_picker = ComboBox::new(
+            &wnd,
+            ComboBoxOpts {
+                position: dpi(188, 340),
+                width: dpi_x(110),
+                control_style: CBS::DROPDOWNLIST,
+                window_style: WS::CHILD | WS::VISIBLE | WS::TABSTOP | WS::CLIPSIBLINGS,
+                items: &scale_labels,
+                selected_item: Some(0),
+                ..Default::default()
+            },
+        );
+
+        // Dropdown to choose a rectangle to place the selected window at instead of scaling it
+        let preset_picker = ComboBox::new(
+            &wnd,
+            ComboBoxOpts {
+                position: dpi(188, 366),
+                width: dpi_x(110),
+                control_style: CBS::DROPDOWNLIST,
+                window_style: WS::CHILD | WS::VISIBLE | WS::TABSTOP | WS::CLIPSIBLINGS,
+                items: &[NO_PRESET_LABEL],
+                selected_item: Some(0),
+                ..Default::default()
+            },
+        );
+
         // Label that will be the parent of the buttons
         // This will allow for the buttons' undrawn background color to be configured
         let btn_canvas = Label::new(
             &wnd,
             LabelOpts {
                 text: "",
-                position: dpi(8, 360),
+                position: dpi(8, 388),
                 size: dpi(290, 40),
                 window_style: WS::CHILD | WS::VISIBLE | WS::CLIPSIBLINGS,
                 window_ex_style: WS_EX::CONTROLPARENT,
@@ -150,16 +334,25 @@ impl MyWindow {
             &wnd,
             ButtonOpts {
                 text: "&Refresh",
-                position: dpi(13, 368),
+                position: dpi(13, 396),
+                ..Default::default()
+            },
+        );
+
+        let settings_btn = Button::new(
+            &wnd,
+            ButtonOpts {
+                text: "&Settings",
+                position: dpi(107, 396),
                 ..Default::default()
             },
         );
 
-        let help_btn = Button::new(
+        let restore_btn = Button::new(
             &wnd,
             ButtonOpts {
-                text: "&Help",
-                position: dpi(108, 368),
+                text: "Res&tore",
+                position: dpi(202, 396),
                 ..Default::default()
             },
         );
@@ -168,7 +361,7 @@ impl MyWindow {
             &wnd,
             ButtonOpts {
                 text: "&Fullscreenize",
-                position: dpi(202, 368),
+                position: dpi(292, 396),
                 ..Default::default()
             },
         );
@@ -177,18 +370,15 @@ impl MyWindow {
         // Whether dark mode is enabled
         let is_dark_mode = Arc::new(AtomicBool::new(false));
         // Whether to use icons in the process list
-        let use_icons = Arc::new(AtomicBool::new(true));
-        // Apps excluded from the process list
-        let excluded_apps = Arc::new(
-            [
-                "Program Manager",
-                "Windows Input Experience",
-                "PopupHost",
-                "System tray overflow window.",
-                "Task Switching",
-            ]
-            .map(String::from),
-        );
+        let use_icons = Arc::new(AtomicBool::new(settings.show_icons));
+        // The settings kept on disk, including the windows excluded from the process list
+        let settings = Arc::new(Mutex::new(settings));
+
+        /* Tray Icon */
+        // Whether the icon is shown in the notification area
+        let tray_visible = Arc::new(AtomicBool::new(false));
+        // Whether the window is closed from the tray menu, which exits even if closing to the tray is enabled
+        let exiting = Arc::new(AtomicBool::new(false));
 
         /* Shared Resources */
         // The application's font
@@ -198,29 +388,78 @@ impl MyWindow {
         let app_dpi = Arc::new(AtomicU32::new(120));
         // Stores the brush used to paint the background of the labels and window
         let background_hbrush = Arc::new(Mutex::new(None));
+        // Stores the brush used to paint the background of the filter edit box
+        let field_hbrush = Arc::new(Mutex::new(None));
         // The image list for the window icons
         let imagelist = Arc::new(Mutex::new(None));
-        // A vector to store the icons of the windows
+        // A vector to store the icons of the windows, along with the raw handle of their window
         let window_icons = Arc::new(Mutex::new(Vec::new()));
+        // The rows of the process list, compared with the listed windows when refreshing
+        let list_rows = Rc::new(RwLock::new(Vec::new()));
+        // Whether the rows of the process list were added with their icons
+        let list_has_icons = Arc::new(AtomicBool::new(false));
+        // The columns currently shown in the process list, starting with the single title column
+        let shown_columns = Rc::new(RwLock::new(vec![Column::Title]));
+        // The monitors shown in the monitor picker
+        let monitors = Arc::new(Mutex::new(MonitorList::default()));
+        // The original states of the windows that were fullscreenized, kept on disk
+        let journal = Arc::new(Mutex::new(
+            Journal::load(journal::default_path()).unwrap_or_else(|e| {
+                eprintln!("Failed to load the journal, modified windows will not be saved: {e}");
+                Journal::default()
+            }),
+        ));
+        // The windows the profiles were already applied to
+        let profile_watcher = Arc::new(Mutex::new(ProfileWatcher::default()));
+        // The fullscreenized windows kept in their fullscreen state
+        let watchdog = Arc::new(Mutex::new(Watchdog::default()));
+        // The hooks notifying the watchdog and the backdrops of window changes,
+        // installed while the watchdog is enabled or a backdrop is shown
+        let window_change_hooks = Rc::new(RwLock::new(None));
+        // The hooks notifying the process list of opened and closed windows, installed while live updates are enabled
+        let window_list_hooks = Rc::new(RwLock::new(None));
+        // The black backdrops shown behind fitted windows, by the raw handle of their window
+        let backdrops = Rc::new(RwLock::new(HashMap::new()));
+        // The fullscreenized windows filling the work area of their monitor, by their raw handle
+        let work_area_windows = Rc::new(RwLock::new(HashMap::new()));
 
         let new_self = Self {
             wnd,
             label,
+            filter_edit,
             process_list,
             top_toggle,
             top_label,
             btn_canvas,
             refresh_btn,
-            help_btn,
+            settings_btn,
+            restore_btn,
             fullscreenize_btn,
+            monitor_picker,
+            scale_picker,
+            preset_picker,
             is_dark_mode,
             use_icons,
-            excluded_apps,
+            settings,
+            tray_visible,
+            exiting,
             app_font,
             app_dpi,
             background_hbrush,
+            field_hbrush,
             imagelist,
             window_icons,
+            list_rows,
+            list_has_icons,
+            shown_columns,
+            monitors,
+            journal,
+            profile_watcher,
+            watchdog,
+            window_change_hooks,
+            window_list_hooks,
+            backdrops,
+            work_area_windows,
         };
 
         new_self.events();
@@ -268,11 +507,19 @@ impl MyWindow {
                 hfont: font.raw_copy(),
                 redraw: true,
             });
+            self.filter_edit.hwnd().SendMessage(SetFont {
+                hfont: font.raw_copy(),
+                redraw: true,
+            });
             self.refresh_btn.hwnd().SendMessage(SetFont {
                 hfont: font.raw_copy(),
                 redraw: true,
             });
-            self.help_btn.hwnd().SendMessage(SetFont {
+            self.settings_btn.hwnd().SendMessage(SetFont {
+                hfont: font.raw_copy(),
+                redraw: true,
+            });
+            self.restore_btn.hwnd().SendMessage(SetFont {
                 hfont: font.raw_copy(),
                 redraw: true,
             });
@@ -280,6 +527,18 @@ impl MyWindow {
                 hfont: font.raw_copy(),
                 redraw: true,
             });
+            self.monitor_picker.hwnd().SendMessage(SetFont {
+                hfont: font.raw_copy(),
+                redraw: true,
+            });
+            self.scale_picker.hwnd().SendMessage(SetFont {
+                hfont: font.raw_copy(),
+                redraw: true,
+            });
+            self.preset_picker.hwnd().SendMessage(SetFont {
+                hfont: font.raw_copy(),
+                redraw: true,
+            });
         }
 
         // Store the font in the shared resource so that its lifetime is extended beyond this function
@@ -293,61 +552,29 @@ impl MyWindow {
         let wnd = self.wnd.hwnd();
         let process_list = self.process_list.hwnd();
 
-        let theme = if dark_mode {
-            "DarkMode_Explorer"
-        } else {
-            "Explorer"
-        };
-
-        // Set the theme of the window title bar
-        wnd.DwmSetWindowAttribute(DwmAttr::UseImmersiveDarkMode(dark_mode))
-            .map_err(|e| eprintln!("DwmSetWindowAttribute failed: {e}"))
-            .ok();
-
-        // Set the theme on the elements in the window
-        wnd.SetWindowTheme(theme, None)
-            .map_err(|e| eprintln!("SetWindowTheme on window failed: {e}"))
-            .ok();
-        process_list
-            .SetWindowTheme(theme, None)
-            .map_err(|e| eprintln!("SetWindowTheme on process list failed: {e}"))
-            .ok();
-        self.top_toggle
-            .hwnd()
-            .SetWindowTheme(theme, None)
-            .map_err(|e| eprintln!("SetWindowTheme on top toggle failed: {e}"))
-            .ok();
-        self.refresh_btn
-            .hwnd()
-            .SetWindowTheme(theme, None)
-            .map_err(|e| eprintln!("SetWindowTheme on refresh button failed: {e}"))
-            .ok();
-        self.help_btn
-            .hwnd()
-            .SetWindowTheme(theme, None)
-            .map_err(|e| eprintln!("SetWindowTheme on help button failed: {e}"))
-            .ok();
-        self.fullscreenize_btn
-            .hwnd()
-            .SetWindowTheme(theme, None)
-            .map_err(|e| eprintln!("SetWindowTheme on fullscreenize button failed: {e}"))
-            .ok();
+        // Set the theme of the window title bar and of the elements in the window
+        theme::apply_theme(
+            wnd,
+            &[
+                process_list,
+                self.filter_edit.hwnd(),
+                self.top_toggle.hwnd(),
+                self.refresh_btn.hwnd(),
+                self.settings_btn.hwnd(),
+                self.restore_btn.hwnd(),
+                self.fullscreenize_btn.hwnd(),
+            ],
+            &[
+                self.monitor_picker.hwnd(),
+                self.scale_picker.hwnd(),
+                self.preset_picker.hwnd(),
+            ],
+            dark_mode,
+        );
 
-        let listview_bg_color = if dark_mode {
-            COLORREF::from_rgb(0x3C, 0x3C, 0x3C) // Dark gray
-        } else {
-            COLORREF::from_rgb(0xFF, 0xFF, 0xFF) // White
-        };
-        let wnd_bg_color = if dark_mode {
-            COLORREF::from_rgb(0x1E, 0x1E, 0x1E) // Very dark gray
-        } else {
-            COLORREF::from_rgb(0xF0, 0xF0, 0xF0) // Light gray
-        };
-        let text_color = if dark_mode {
-            COLORREF::from_rgb(0xF0, 0xF0, 0xF0) // Light gray
-        } else {
-            COLORREF::from_rgb(0x00, 0x00, 0x00) // Black
-        };
+        let listview_bg_color = theme::field_bg_color(dark_mode);
+        let wnd_bg_color = theme::window_bg_color(dark_mode);
+        let text_color = theme::text_color(dark_mode);
 
         // Set the background color of the listview
         unsafe {
@@ -376,6 +603,18 @@ impl MyWindow {
         .map_err(|e| eprintln!("SetTextColor failed: {e}"))
         .ok();
 
+        // Set the background brush used to paint the background of the filter edit box
+        match self.field_hbrush.lock() {
+            Ok(mut field_hbrush) => {
+                *field_hbrush = HBRUSH::CreateSolidBrush(listview_bg_color)
+                    .map_err(|e| eprintln!("CreateSolidBrush failed: {e}"))
+                    .ok();
+            }
+            Err(e) => {
+                eprintln!("Failed to lock field brush mutex: {e}");
+            }
+        }
+
         // Set the background brush used to paint the background of the labels
         match self.background_hbrush.lock() {
             Ok(mut background_hbrush) => {
@@ -397,31 +636,37 @@ impl MyWindow {
     }
 
     fn set_system_theme(&self, initialize: bool) {
-        // Check if dark mode is enabled using the registry
-        let dark_mode = HKEY::CURRENT_USER
-            .RegOpenKeyEx(
-                Some("Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize"),
-                REG_OPTION::default(),
-                KEY::READ,
-            )
-            .and_then(|key| key.RegQueryValueEx(Some("AppsUseLightTheme")))
-            .map_or_else(
-                |e| {
-                    eprintln!("Getting the system theme failed: {e}");
-                    // Default to light mode
-                    false
-                },
-                |result| {
-                    match result {
-                        // If the value is 1, light mode is enabled
-                        RegistryValue::Dword(value) => value != 1,
-                        _ => {
-                            // Default to light mode
-                            false
+        let theme = lock(&self.settings).theme;
+
+        // Check if dark mode is enabled using the registry, unless the theme was chosen in the settings
+        let dark_mode = match theme {
+            Theme::Light => false,
+            Theme::Dark => true,
+            Theme::System => HKEY::CURRENT_USER
+                .RegOpenKeyEx(
+                    Some("Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize"),
+                    REG_OPTION::default(),
+                    KEY::READ,
+                )
+                .and_then(|key| key.RegQueryValueEx(Some("AppsUseLightTheme")))
+                .map_or_else(
+                    |e| {
+                        eprintln!("Getting the system theme failed: {e}");
+                        // Default to light mode
+                        false
+                    },
+                    |result| {
+                        match result {
+                            // If the value is 1, light mode is enabled
+                            RegistryValue::Dword(value) => value != 1,
+                            _ => {
+                                // Default to light mode
+                                false
+                            }
                         }
-                    }
-                },
-            );
+                    },
+                ),
+        };
 
         // Only update the theme if it has changed, or if this is the initial call
         if !initialize && dark_mode == self.is_dark_mode.load(Ordering::Relaxed) {
@@ -440,228 +685,1633 @@ impl MyWindow {
         }
     }
 
-    fn refresh_process_list(
-        &self,
-        windows: &mut MutexGuard<Vec<HWND>>,
-        scan_windows: bool,
-    ) -> w::AnyResult<()> {
-        // Create an image list to store the icons
-        let image_list = HIMAGELIST::Create(
-            SIZE::from(dpi_scale((16, 16), self.app_dpi.load(Ordering::Relaxed))),
-            ILC::COLOR32,
-            0,
-            100,
-        )
-        .unwrap_or_else(|e| {
-            // If creating the image list failed, disable the use of icons
-            self.use_icons.store(false, Ordering::SeqCst);
-            eprintln!("Imagelist Creation failed {e}");
-            unsafe { ImageListDestroyGuard::new(HIMAGELIST::NULL) }
-        });
-
-        let use_icons = self.use_icons.load(Ordering::SeqCst);
+    fn refresh_monitor_picker(&self) {
+        let spans = lock(&self.settings).spans.clone();
+        let mut monitors = lock(&self.monitors);
+
+        // Remember the current choice so that it survives the refresh
+        let choice = self
+            .monitor_picker
+            .items()
+            .selected_index()
+            .map(|index| monitors.choice_at(index as usize))
+            .unwrap_or_default();
+
+        *monitors = MonitorList::new(Win32System.monitors()).with_spans(&spans);
+
+        // Repopulate the picker, falling back to the window's monitor if the chosen one is gone
+        let items = self.monitor_picker.items();
+        items.delete_all();
+        items.add(&monitors.labels());
+        items.select(Some(monitors.index_of(&choice).unwrap_or(0) as u32));
+    }
 
-        // Enumerate over all open windows
-        if scan_windows {
-            // Clear the process list, window vector, and icon cache
-            self.process_list.items().delete_all()?;
-            windows.clear();
-            if let Ok(mut window_icons) = self.window_icons.lock() {
-                window_icons.clear();
+    fn restore_previous_session(&self) {
+        // The journal is not kept locked while the dialogs are open, as messages are still handled behind them
+        let titles = {
+            let mut journal = lock(&self.journal);
+
+            // Forget the windows that were closed since the previous session
+            let closed: Vec<isize> = journal
+                .windows()
+                .iter()
+                .filter(|state| window_ops::find_window(&Win32System, state).is_none())
+                .map(|state| state.hwnd)
+                .collect();
+            for hwnd in closed {
+                journal.take(hwnd);
             }
 
-            EnumWindows(|hwnd: HWND| -> bool {
-                // Skip invisible windows
-                if !hwnd.IsWindowVisible() {
-                    return true;
-                }
-
-                // Get the window title and return if an error occurred
-                let Ok(title) = hwnd.GetWindowText() else {
-                    return true;
-                };
-                if title.is_empty() || self.excluded_apps.contains(&title) {
-                    return true;
-                }
+            if journal.windows().is_empty() {
+                return;
+            }
 
-                let icon_id = if use_icons {
-                    // Try multiple methods to get the window icon
-                    // - Try each method in order until one returns a valid icon
-                    // - Each method is a closure that takes a &HWND and returns an HICON
-                    // - If no method returns a valid icon, HICON::NULL is used
-                    let icon = unsafe {
-                        [
-                            /* Method 1: WM_GETICON Message */
-                            |hwnd: &HWND| {
-                                HICON::from_ptr(hwnd.SendMessage(WndMsg::new(
-                                    WM::GETICON,
-                                    ICON_SZ::SMALL.raw() as usize,
-                                    0,
-                                )) as *mut _)
-                            },
-                            /* Method 2: Use GetClassLongPtr to get the small icon */
-                            // See https://learn.microsoft.com/en-us/windows/win32/winmsg/wm-geticon#remarks
-                            |hwnd: &HWND| {
-                                HICON::from_ptr(hwnd.GetClassLongPtr(GCLP::HICONSM) as *mut _)
-                            },
-                            /* Method 3: Use GetClassLongPtr to get the large icon */
-                            |hwnd: &HWND| {
-                                HICON::from_ptr(hwnd.GetClassLongPtr(GCLP::HICON) as *mut _)
-                            },
-                        ]
-                    }
-                    .into_iter()
-                    .find_map(|func| match func(&hwnd) {
-                        // If the icon is valid, return it, otherwise continue to the next method
-                        icon if icon != HICON::NULL && icon != HICON::INVALID => Some(icon),
-                        _ => None,
-                    })
-                    // If no method returned a valid icon, return HICON::NULL
-                    // TODO: Implement support for UWP app icons, which are not accessible via the above methods
-                    .unwrap_or(HICON::NULL);
-
-                    // Add the icon to the image list
-                    match image_list.AddIcon(&icon) {
-                        Ok(id) => {
-                            // Cache the icon by adding it to the global vector
-                            if let Ok(mut window_icons) = self.window_icons.lock() {
-                                window_icons.push(unsafe { DestroyIconGuard::new(icon) });
-                            } else {
-                                eprintln!("Failed to lock window_icons mutex");
-                            }
+            journal
+                .windows()
+                .iter()
+                .map(|state| format!("\u{2022} {}", state.title))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let answer = self.wnd.hwnd().TaskDialog(
+            Some("Fullscreenizer"),
+            Some("Restore windows left modified by a previous session?"),
+            Some(&format!(
+                "These windows were fullscreenized when Fullscreenizer was last closed:\n\n{titles}\n\n\
+                 Choose No to keep them as they are, they can still be restored later with the Restore button."
+            )),
+            TDCBF::YES | TDCBF::NO,
+            IconRes::Warn,
+        );
+        if !matches!(answer, Ok(DLGID::YES)) {
+            return;
+        }
 
-                            Some(id)
-                        }
-                        Err(e) => {
-                            eprintln!("AddIcon failed: '{e}'",);
-                            None
-                        }
-                    }
-                } else {
-                    None
+        let mut errors = Vec::new();
+        {
+            let mut journal = lock(&self.journal);
+            let hwnds: Vec<isize> = journal.windows().iter().map(|state| state.hwnd).collect();
+            for hwnd in hwnds {
+                let Some(state) = journal.take(hwnd) else {
+                    continue;
                 };
-
-                // Add the window to the vector
-                windows.push(hwnd);
-
-                // Add the window to the list
-                self.process_list
-                    .items()
-                    .add(&[title], icon_id, ())
-                    .map_err(|e| {
-                        eprintln!("Failed to add item to process list - Add failed: {e}");
-                    })
-                    .ok();
-
-                // Return true to continue enumerating
-                true
-            })
-            .map_err(|e| eprintln!("EnumWindows failed: {e}"))
-            .ok();
-        } else {
-            // Add icons to the new image list from the icon cache
-            if use_icons && let Ok(window_icons) = self.window_icons.lock() {
-                for icon in window_icons.iter() {
-                    image_list
-                        .AddIcon(icon)
-                        .map_err(|e| {
-                            eprintln!("AddIcon failed {e}\n");
-                        })
+                let result = window_ops::find_window(&Win32System, &state)
+                    .ok_or_else(|| "The window was closed".to_owned())
+                    .and_then(|window| window_ops::restore(&Win32System, window, &state));
+                if let Err(e) = result {
+                    errors.push(format!("Failed to restore \"{}\" - {e}", state.title));
+                    // Keep tracking the window so that restoring can be retried
+                    journal
+                        .record(state)
+                        .map_err(|e| eprintln!("Failed to update the journal: {e}"))
                         .ok();
                 }
             }
         }
+        for error in errors {
+            show_error_message(&error);
+        }
+    }
 
-        // Set the image list for the listview
-        let _ = unsafe {
-            self.process_list.hwnd().SendMessage(SetImageList {
-                himagelist: if use_icons {
-                    Some(image_list.raw_copy())
-                } else {
-                    None
-                },
-                kind: LVSIL::SMALL,
-            })
+    /// Function to exclude a window of the process list from being listed
+    /// # Arguments
+    /// * `windows` - The windows shown in the process list
+    /// * `index` - The index of the window in the process list
+    /// * `field` - Whether to exclude the window's application or its title
+    fn exclude_window(&self, windows: &Mutex<Vec<HWND>>, index: i32, field: ExclusionField) {
+        // Copy the window out of the vector, as refreshing the process list locks it again
+        let window = lock(windows)
+            .get(index as usize)
+            .map(|hwnd| unsafe { hwnd.raw_copy() });
+        let Some(window) = window else {
+            show_error_message(
+                "Failed to exclude window - Could not get the clicked window from the list",
+            );
+            return;
         };
 
-        // Store the image list, dropping the old one
-        if let Ok(mut imagelist) = self.imagelist.lock() {
-            imagelist.replace(image_list);
-        }
+        let pattern = match field {
+            ExclusionField::Exe => window_ops::window_exe_name(&Win32System, window.ptr() as isize),
+            ExclusionField::Class => window.GetClassName().ok(),
+            ExclusionField::Title => window.GetWindowText().ok(),
+        };
+        let Some(pattern) = pattern else {
+            show_error_message(&format!(
+                "Failed to exclude window - Could not get the {field} of the window"
+            ));
+            return;
+        };
 
-        Ok(())
+        self.update_exclusions(|exclusions| {
+            exclusions.add(ExclusionRule::new(field, ExclusionKind::Exact, pattern))
+        });
     }
 
-    fn toggle_label_focus_rectangle(&self) -> Result<(), String> {
-        // Get the rectangle of the checkbox label relative to the window's client area
-        let ctrl_rect = match self.top_label.hwnd().GetWindowRect() {
-            Ok(rect) => match self
-                .wnd
-                .hwnd()
-                .ScreenToClient(POINT::with(rect.left, rect.top))
-            {
-                // Expand the rectangle slightly to make it more visible
-                Ok(pt) => RECT {
-                    left: pt.x - 2,
-                    top: pt.y - 1,
-                    right: pt.x + (rect.right - rect.left) + 1,
-                    bottom: pt.y + (rect.bottom - rect.top) + 1,
-                },
-                Err(e) => {
-                    eprintln!("ScreenToClient failed: {e}");
-                    return Err(format!("ScreenToClient failed: {e}").to_owned());
-                }
-            },
-            Err(e) => {
-                eprintln!("GetWindowRect failed: {e}");
-                return Err(format!("GetWindowRect failed: {e}").to_owned());
-            }
+    /// Function to save a profile fullscreenizing the application of a window of the process list
+    /// # Arguments
+    /// * `windows` - The windows shown in the process list
+    /// * `index` - The index of the window in the process list
+    ///
+    /// The profile uses the monitor, scaling and "stay on top" flag currently chosen, and replaces any profile
+    /// with the same name.
+    fn save_profile(&self, windows: &Mutex<Vec<HWND>>, index: i32) {
+        // Copy the window out of the vector, as the vector is locked while the process list is refreshed
+        let window = lock(windows)
+            .get(index as usize)
+            .map(|hwnd| unsafe { hwnd.raw_copy() });
+        let Some(exe_name) = window
+            .as_ref()
+            .and_then(|window| window_ops::window_exe_name(&Win32System, window.ptr() as isize))
+        else {
+            show_error_message(
+                "Failed to save profile - Could not get the application of the clicked window",
+            );
+            return;
         };
 
-        // Draw a focus rectangle around the checkbox label
-        // The focus rectangle does not draw over controls, so space is left between the checkbox and the label
-        self.wnd
-            .hwnd()
-            .GetDC()
-            .unwrap()
-            .DrawFocusRect(ctrl_rect)
-            .map_err(|e| format!("DrawFocusRect failed: {e}"))?;
+        let monitor = {
+            let monitors = lock(&self.monitors);
+            self.monitor_picker
+                .items()
+                .selected_index()
+                .map(|index| monitors.choice_at(index as usize))
+                .unwrap_or_default()
+        };
+        let profile = Profile {
+            name: window_info::strip_exe_extension(&exe_name).to_owned(),
+            exe: Some(exe_name),
+            monitor,
+            topmost: self.top_toggle.is_checked(),
+            scale: self.selected_scale(),
+            ..Profile::default()
+        };
 
-        Ok(())
+        self.update_settings(|settings| settings.profiles.insert(profile));
     }
 
-    fn events(&self) {
-        // Create a vector in a mutex to store the open windows
-        let windows: Arc<Mutex<Vec<HWND>>> = Arc::new(Mutex::new(Vec::new()));
+    /// Function to fullscreenize a window and record its original state
+    /// # Arguments
+    /// * `window` - The window
+    /// * `choice` - The monitor to send the window to
+    /// * `scale` - How to size the window on the monitor, unless a preset is selected
+    /// # Returns
+    /// * An error message if the window could not be fullscreenized
+    fn fullscreenize_window(
+        &self,
+        window: &HWND,
+        choice: &MonitorChoice,
+        scale: ScaleMode,
+    ) -> Result<(), String> {
+        // Get the dimensions of the chosen monitor or span
+        let work_area = lock(&self.settings).work_area;
+        let window_rect = window
+            .GetWindowRect()
+            .map_err(|e| format!("GetWindowRect failed with error: {e}"))?;
+        let area = {
+            let monitors = lock(&self.monitors);
+            monitors
+                .resolve_area(choice, Rect::from(window_rect), work_area)
+                .ok_or_else(|| "Could not find a monitor to place the window on".to_owned())?
+        };
+        if !area.rectangular && !self.confirm_uneven_span() {
+            return Ok(());
+        }
 
-        self.wnd.on().wm_create({
-            let self2 = self.clone();
-            move |create| -> w::AnyResult<i32> {
-                // Store the current DPI
-                self2
-                    .app_dpi
-                    .store(self2.wnd.hwnd().GetDpiForWindow(), Ordering::Relaxed);
+        // The client size is read before the border is removed, as the scaling modes other than stretching keep it
+        let hwnd = window.ptr() as isize;
+        let sizing = self.selected_sizing(scale);
+        let client_size = Win32System.client_size(hwnd)?;
+        let target = sizing.target_rect(area.rect, client_size);
+
+        // Record the original state of the window so that it can be restored later
+        let state = window_ops::capture_state(&Win32System, hwnd)
+            .map_err(|e| format!("Could not save the window's state: {e}"))?;
+        // The window is still fullscreenized if the journal could not be written,
+        // it just cannot be restored after Fullscreenizer is closed.
+        // The journal is unlocked before the error is shown, as the timers still run while the message box is open.
+        let recorded = lock(&self.journal).record(state);
+        if let Err(e) = recorded {
+            show_error_message(&format!("Failed to save the window's state - {e}"));
+        }
 
-                // Change the font in the buttons and label
-                self2.update_font();
+        let topmost = self.top_toggle.is_checked();
+        self.place_window(hwnd, area.rect, target, &sizing, topmost)?;
+
+        // Keep how the window was placed, so that it can be fitted to the work area again when the taskbar moves
+        {
+            let mut work_area_windows = write(&self.work_area_windows);
+            if work_area {
+                work_area_windows.insert(
+                    hwnd,
+                    WorkAreaPlacement {
+                        choice: choice.clone(),
+                        sizing,
+                        client_size,
+                        topmost,
+                    },
+                );
+            } else {
+                work_area_windows.remove(&hwnd);
+            }
+        }
 
-                // Set the theme of the window
-                self2.set_system_theme(true);
+        if let Some(exe_name) = window_ops::window_exe_name(&Win32System, hwnd) {
+            self.add_recent_app(&exe_name);
+        }
+        Ok(())
+    }
 
-                // Refresh the process list
-                self2.refresh_btn.trigger_click();
+    /// Function to move a window over its monitor or span, without its border, and keep it there
+    /// # Arguments
+    /// * `hwnd` - The raw handle of the window
+    /// * `area` - The monitor, work area or span the window is sent to
+    /// * `target` - The rectangle the window should cover, inside the area unless the window is too large
+    /// * `sizing` - How the window is sized in the area
+    /// * `topmost` - Whether to apply the "stay on top" flag to the window
+    /// # Returns
+    /// * An error message if the window could not be modified
+    fn place_window(
+        &self,
+        hwnd: isize,
+        area: Rect,
+        target: Rect,
+        sizing: &Sizing,
+        topmost: bool,
+    ) -> Result<(), String> {
+        // Remove the border and size the window to the rectangle the scaling mode or preset chose in the area
+        window_ops::fullscreenize(&Win32System, hwnd, target, topmost)?;
+
+        self.keep_window_placed(hwnd, area, target, sizing, topmost);
+        Ok(())
+    }
 
-                // Send a message to handle post-creation tasks
-                unsafe {
-                    self2
-                        .wnd
-                        .hwnd()
-                        .PostMessage(WndMsg::new(WM::APP, WM::USER.raw() as usize, 0))
-                        .map_err(|e| {
-                            eprintln!("Failed to post WM_APP message - PostMessage Failed: {e}");
-                        })
-                        .ok();
-                };
+    /// Function to cover the rest of the area behind a window that was just placed, and keep the window there
+    /// # Arguments
+    /// * `hwnd` - The raw handle of the window
+    /// * `area` - The monitor, work area or span the window was sent to
+    /// * `target` - The rectangle the window covers
+    /// * `sizing` - How the window is sized in the area
+    /// * `topmost` - Whether the "stay on top" flag was applied to the window
+    fn keep_window_placed(
+        &self,
+        hwnd: isize,
+        area: Rect,
+        target: Rect,
+        sizing: &Sizing,
+        topmost: bool,
+    ) {
+        // Hide the parts of the monitor or span the window leaves uncovered
+        let backdrop = lock(&self.settings).backdrop;
+        self.set_backdrop(
+            hwnd,
+            (backdrop && sizing.leaves_bars() && target != area).then_some(area),
+        );
+
+        match window_ops::desired_state(&Win32System, hwnd, topmost) {
+            Ok(desired) => self.watch_window(hwnd, desired),
+            Err(e) => eprintln!("Failed to start watching the window: {e}"),
+        }
+    }
+
+    /// Function to fit the windows filling a work area to the new work areas, after the taskbar or an app bar moved
+    fn refit_work_area_windows(&self) {
+        let windows: Vec<(isize, WorkAreaPlacement)> = {
+            let mut work_area_windows = write(&self.work_area_windows);
+
+            // Forget the windows that were closed
+            work_area_windows.retain(|&hwnd, _| Win32System.is_window(hwnd));
+            work_area_windows
+                .iter()
+                .map(|(&hwnd, placement)| (hwnd, placement.clone()))
+                .collect()
+        };
+        if windows.is_empty() {
+            return;
+        }
+
+        // The work areas are part of the monitor list
+        self.refresh_monitor_picker();
+
+        for (hwnd, placement) in windows {
+            let result = Win32System.rect(hwnd).and_then(|window_rect| {
+                let area = {
+                    let monitors = lock(&self.monitors);
+                    monitors
+                        .resolve_area(&placement.choice, window_rect, true)
+                        .ok_or_else(|| {
+                            "Could not find a monitor to place the window on".to_owned()
+                        })?
+                };
+                let target = placement
+                    .sizing
+                    .target_rect(area.rect, placement.client_size);
+
+                // The window is moved without activating it, as the user may be working in another window
+                let desired = DesiredState {
+                    rect: Win32System.window_rect_for_client(hwnd, target)?,
+                    style: BORDERLESS_STYLE,
+                    topmost: placement.topmost,
+                };
+                window_ops::reapply(&Win32System, hwnd, &desired)?;
+
+                self.keep_window_placed(
+                    hwnd,
+                    area.rect,
+                    target,
+                    &placement.sizing,
+                    placement.topmost,
+                );
+                Ok(())
+            });
+            if let Err(e) = result {
+                eprintln!("Failed to fit a window to the new work area: {e}");
+            }
+        }
+    }
+
+    /// Function to ask whether to span a window across monitors that do not form a rectangle
+    /// # Returns
+    /// * `true` if the user chose to span the window anyway
+    fn confirm_uneven_span(&self) -> bool {
+        let answer = self.wnd.hwnd().TaskDialog(
+            Some("Fullscreenizer"),
+            Some("The spanned monitors do not form a rectangle"),
+            Some(
+                "The window covers the rectangle around the monitors, \
+                 so the parts of it outside of the monitors will not be shown.\n\n\
+                 Span the window anyway?",
+            ),
+            TDCBF::YES | TDCBF::NO,
+            IconRes::Warn,
+        );
+
+        matches!(answer, Ok(DLGID::YES))
+    }
+
+    /// Function to remember the monitor and scaling mode chosen for an application in the settings
+    /// # Arguments
+    /// * `app` - The executable name of the application that was fullscreenized
+    /// * `choices` - The monitor and scaling mode chosen in the pickers
+    fn remember_choices(&self, app: &str, choices: AppChoices) {
+        let mut settings = lock(&self.settings);
+        if settings.remember_choices(app, choices) {
+            settings
+                .save()
+                .map_err(|e| eprintln!("Failed to save the choices made for {app}: {e}"))
+                .ok();
+        }
+    }
+
+    /// Function to list an application in the recent applications of the tray menu
+    /// # Arguments
+    /// * `exe_name` - The executable name of the application that was fullscreenized
+    fn add_recent_app(&self, exe_name: &str) {
+        let mut settings = lock(&self.settings);
+        if settings.recent_apps.add(exe_name) {
+            settings
+                .save()
+                .map_err(|e| eprintln!("Failed to save the recent applications: {e}"))
+                .ok();
+        }
+    }
+
+    /// Function to put a fullscreenized window back into its original state
+    /// # Arguments
+    /// * `window` - The window
+    /// # Returns
+    /// * An error message if the window was not fullscreenized or could not be restored
+    fn restore_window(&self, window: &HWND) -> Result<(), String> {
+        // Get the original state of the window
+        let mut journal = lock(&self.journal);
+        let Some(state) = journal.take(window.ptr() as isize) else {
+            return Err("The window was not fullscreenized by Fullscreenizer".to_owned());
+        };
+
+        // Stop watching the window first, so that restoring it is not seen as a drift
+        lock(&self.watchdog).unwatch(state.hwnd);
+        self.set_backdrop(state.hwnd, None);
+        write(&self.work_area_windows).remove(&state.hwnd);
+
+        window_ops::restore(&Win32System, state.hwnd, &state).inspect_err(|_| {
+            // Keep tracking the window so that restoring can be retried
+            journal
+                .record(state)
+                .map_err(|e| eprintln!("Failed to update the journal: {e}"))
+                .ok();
+        })
+    }
+
+    /// Function to keep a fullscreenized window in its fullscreen state
+    /// # Arguments
+    /// * `hwnd` - The raw handle of the window
+    /// * `desired` - The state the window was put in
+    fn watch_window(&self, hwnd: isize, desired: DesiredState) {
+        let title = unsafe { HWND::from_ptr(hwnd as *mut _) }
+            .GetWindowText()
+            .unwrap_or_default();
+
+        lock(&self.watchdog).watch(hwnd, &title, desired);
+        // Report the changes of the window from now on
+        self.update_watchdog();
+    }
+
+    /// Function to put a watched window back into its fullscreen state if its application reverted it
+    /// # Arguments
+    /// * `hwnd` - The raw handle of the window that changed
+    fn check_window(&self, hwnd: isize) {
+        let mut watchdog = lock(&self.watchdog);
+
+        if !Win32System.is_window(hwnd) {
+            watchdog.unwatch(hwnd);
+            return;
+        }
+
+        let actual = match window_ops::actual_state(&Win32System, hwnd) {
+            Ok(actual) => actual,
+            Err(e) => {
+                eprintln!("Failed to check a watched window: {e}");
+                return;
+            }
+        };
+
+        if let Decision::Reapply(desired) = watchdog.check(hwnd, &actual, Instant::now()) {
+            // Release the lock first, as changing the window triggers new change events
+            drop(watchdog);
+            window_ops::reapply(&Win32System, hwnd, &desired)
+                .map_err(|e| eprintln!("Failed to put a window back into fullscreen: {e}"))
+                .ok();
+        }
+    }
+
+    /// Function to check all watched windows, as some changes are not reported by the change events
+    fn check_watched_windows(&self) {
+        let watched = lock(&self.watchdog).watched();
+
+        for hwnd in watched {
+            self.check_window(hwnd);
+        }
+    }
+
+    /// Function to check whether the watchdog is enabled in the settings
+    fn watchdog_enabled(&self) -> bool {
+        lock(&self.settings).watchdog
+    }
+
+    /// Function to show or remove the backdrop behind a fullscreenized window
+    /// # Arguments
+    /// * `hwnd` - The raw handle of the window
+    /// * `monitor` - The monitor the backdrop should cover, or `None` to remove the backdrop of the window
+    fn set_backdrop(&self, hwnd: isize, monitor: Option<Rect>) {
+        {
+            let mut backdrops = write(&self.backdrops);
+
+            // Dropping a backdrop destroys its window
+            backdrops.remove(&hwnd);
+            if let Some(monitor) = monitor {
+                match Backdrop::new(monitor).and_then(|backdrop| {
+                    backdrop.place_below(hwnd)?;
+                    Ok(backdrop)
+                }) {
+                    Ok(backdrop) => {
+                        backdrops.insert(hwnd, backdrop);
+                    }
+                    // The window is still fitted without its backdrop
+                    Err(e) => eprintln!("Failed to show a backdrop: {e}"),
+                }
+            }
+        }
+
+        // The backdrops follow their window through the window change hooks
+        self.update_watchdog();
+    }
+
+    /// Function to keep the backdrops behind their window, and remove the backdrops of closed windows
+    /// # Arguments
+    /// * `changed` - The raw handle of the window that was moved or brought to the foreground, if any
+    fn update_backdrops(&self, changed: Option<isize>) {
+        let removed = {
+            let mut backdrops = write(&self.backdrops);
+
+            let count = backdrops.len();
+            backdrops.retain(|&hwnd, _| Win32System.is_window(hwnd));
+
+            // Bringing a window to the foreground puts it above its backdrop, which is moved back below it
+            if let Some(hwnd) = changed
+                && let Some(backdrop) = backdrops.get(&hwnd)
+            {
+                backdrop
+                    .place_below(hwnd)
+                    .map_err(|e| eprintln!("Failed to move a backdrop: {e}"))
+                    .ok();
+            }
+
+            backdrops.len() != count
+        };
+
+        if removed {
+            self.update_watchdog();
+        }
+    }
+
+    /// Function to check whether a backdrop is shown behind any window
+    fn has_backdrops(&self) -> bool {
+        !read(&self.backdrops).is_empty()
+    }
+
+    /// Function to install or remove the window change hooks as the watchdog is enabled or disabled,
+    /// and as backdrops are shown or removed
+    ///
+    /// The hooks only report the changes of the processes owning watched windows or windows with a backdrop.
+    fn update_watchdog(&self) {
+        let enabled = self.watchdog_enabled() || self.has_backdrops();
+        let mut hooks = write(&self.window_change_hooks);
+
+        if !enabled {
+            // Dropping the hooks removes them
+            *hooks = None;
+        } else if hooks.is_none() {
+            match WindowChangeHooks::install(self.wnd.hwnd()) {
+                Ok(installed) => *hooks = Some(installed),
+                // Watched windows are still checked every second without the hooks
+                Err(e) => eprintln!("Failed to watch window changes: {e}"),
+            }
+        }
+
+        if let Some(hooks) = hooks.as_ref() {
+            let mut tracked = lock(&self.watchdog).watched();
+            tracked.extend(read(&self.backdrops).keys());
+            hooks.track(
+                tracked
+                    .into_iter()
+                    .map(|hwnd| Win32System.process_id(hwnd))
+                    .collect(),
+            );
+        }
+    }
+
+    /// Function to start or stop updating the process list as windows change, as chosen in the settings
+    fn update_live_updates(&self) {
+        let enabled = lock(&self.settings).live_updates;
+        let mut hooks = write(&self.window_list_hooks);
+
+        if !enabled {
+            // Dropping the hooks removes them
+            *hooks = None;
+        } else if hooks.is_none() {
+            match WindowListHooks::install(self.wnd.hwnd()) {
+                Ok(installed) => *hooks = Some(installed),
+                // The process list can still be refreshed with the Refresh button
+                Err(e) => eprintln!("Failed to watch for opened and closed windows: {e}"),
+            }
+        }
+    }
+
+    /// Function to show how often the watchdog had to put windows back into fullscreen
+    fn show_watchdog_log(&self) {
+        let report = lock(&self.watchdog).report();
+
+        self.wnd
+            .hwnd()
+            .TaskDialog(
+                Some("Watchdog log"),
+                None,
+                Some(&report),
+                TDCBF::OK,
+                IconRes::Info,
+            )
+            .map_err(|e| eprintln!("TaskDialog failed: {e}"))
+            .ok();
+    }
+
+    /// Function to register the global hotkeys chosen in the settings
+    ///
+    /// The hotkeys registered before are unregistered first, and a message is shown
+    /// for every hotkey that could not be registered, usually because another application uses it.
+    fn register_hotkeys(&self) {
+        let hotkeys = {
+            let settings = lock(&self.settings);
+            [settings.fullscreenize_hotkey, settings.restore_hotkey]
+        };
+
+        let mut conflicts = Vec::new();
+        for ((id, name), hotkey) in [
+            (HOTKEY_FULLSCREENIZE_ID, "fullscreenize"),
+            (HOTKEY_RESTORE_ID, "restore"),
+        ]
+        .into_iter()
+        .zip(hotkeys)
+        {
+            // Unregistering fails if the hotkey was not registered, which is expected
+            self.wnd.hwnd().UnregisterHotKey(id).ok();
+
+            let Some(hotkey) = hotkey else {
+                continue;
+            };
+            // The hotkey is not repeated while it is held down
+            let modifiers = unsafe { MOD::from_raw(hotkey.modifiers) } | MOD::NOREPEAT;
+            if let Err(e) = self
+                .wnd
+                .hwnd()
+                .RegisterHotKey(id, modifiers, unsafe { VK::from_raw(hotkey.key) })
+            {
+                conflicts.push(hotkeys::conflict_message(
+                    name,
+                    hotkey,
+                    e == ERROR::HOTKEY_ALREADY_REGISTERED,
+                    &e.to_string(),
+                ));
+            }
+        }
+
+        if !conflicts.is_empty() {
+            show_error_message(&conflicts.join("\n\n"));
+        }
+    }
+
+    /// Function to run the action of a global hotkey on the foreground window
+    /// # Arguments
+    /// * `id` - The ID the hotkey was registered with
+    fn handle_hotkey(&self, id: i32) {
+        // The window of Fullscreenizer itself is never changed
+        let Some(window) = HWND::GetForegroundWindow().filter(|window| window != self.wnd.hwnd())
+        else {
+            return;
+        };
+
+        if id == HOTKEY_FULLSCREENIZE_ID {
+            // Use the monitor and scaling chosen for the application, as the pickers may show another window's choice
+            let app = window_ops::window_exe_name(&Win32System, window.ptr() as isize);
+            let choice = self.monitor_choice(app.as_deref());
+            let scale = self.scale_choice(app.as_deref());
+
+            let result = self.fullscreenize_window(&window, &choice, scale);
+            self.report_action(
+                result.map_err(|e| format!("Failed to fullscreenize window - {e}")),
+                &window,
+                "Fullscreenized",
+            );
+        } else if id == HOTKEY_RESTORE_ID {
+            let result = self.restore_window(&window);
+            self.report_action(
+                result.map_err(|e| format!("Failed to restore window - {e}")),
+                &window,
+                "Restored",
+            );
+        }
+    }
+
+    /// Function to report the result of an action that was not started from the window
+    /// # Arguments
+    /// * `result` - The result of the action, with the error message shown to the user
+    /// * `window` - The window the action was applied to
+    /// * `action` - What was done to the window (e.g. `Fullscreenized`)
+    ///
+    /// The result is shown as a notification when the tray icon is visible, otherwise only errors are shown.
+    fn report_action(&self, result: Result<(), String>, window: &HWND, action: &str) {
+        let tray_visible = self.tray_visible.load(Ordering::Relaxed);
+
+        match result {
+            Ok(()) if tray_visible => {
+                let title = window.GetWindowText().unwrap_or_default();
+                tray::show_balloon(self.wnd.hwnd(), &format!("{action} \"{title}\""), false);
+            }
+            Ok(()) => {}
+            Err(e) if tray_visible => tray::show_balloon(self.wnd.hwnd(), &e, true),
+            Err(e) => show_error_message(&e),
+        }
+    }
+
+    /// Function to show or hide the tray icon as chosen in the settings
+    fn update_tray_icon(&self) {
+        let tray_icon = lock(&self.settings).tray_icon;
+        if tray_icon == self.tray_visible.load(Ordering::Relaxed) {
+            return;
+        }
+
+        if tray_icon {
+            match tray::add_icon(self.wnd.hwnd()) {
+                Ok(()) => self.tray_visible.store(true, Ordering::Relaxed),
+                Err(e) => show_error_message(&format!("Failed to show the tray icon - {e}")),
+            }
+        } else {
+            tray::remove_icon(self.wnd.hwnd());
+            self.tray_visible.store(false, Ordering::Relaxed);
+            // Without the icon, a hidden window could not be shown again
+            self.show_from_tray();
+        }
+    }
+
+    /// Function to show the window after it was hidden in the tray
+    fn show_from_tray(&self) {
+        if self.wnd.hwnd().IsWindowVisible() {
+            return;
+        }
+
+        self.wnd.hwnd().ShowWindow(SW::RESTORE);
+        self.wnd.hwnd().SetForegroundWindow();
+    }
+
+    /// Function to show the context menu of the tray icon and run the chosen command
+    fn show_tray_menu(&self) -> w::AnyResult<()> {
+        let (windows, recent_apps) = {
+            let settings = lock(&self.settings);
+            (
+                window_ops::enumerate_windows(&Win32System, &settings.exclusions, false),
+                settings.recent_apps.clone(),
+            )
+        };
+        // Fullscreenizer's own window is not listed
+        let windows: Vec<WindowInfo> = windows
+            .into_iter()
+            .filter(|window| window.hwnd != self.wnd.hwnd().ptr() as isize)
+            .collect();
+
+        let window_labels: Vec<String> = windows
+            .iter()
+            .take(MAX_MENU_WINDOWS)
+            .map(|window| tray_menu::menu_label(&window.title))
+            .collect();
+        let windows_menu = HMENU::CreatePopupMenu()?;
+        windows_menu.append_item(
+            &window_labels
+                .iter()
+                .enumerate()
+                .map(|(index, label)| MenuItem::Entry {
+                    cmd_id: TrayCommand::Window(index).id(),
+                    text: label,
+                })
+                .collect::<Vec<_>>(),
+        )?;
+
+        let app_labels: Vec<String> = recent_apps.iter().map(tray_menu::app_label).collect();
+        let recent_apps_menu = HMENU::CreatePopupMenu()?;
+        recent_apps_menu.append_item(
+            &app_labels
+                .iter()
+                .enumerate()
+                .map(|(index, label)| MenuItem::Entry {
+                    cmd_id: TrayCommand::RecentApp(index).id(),
+                    text: label,
+                })
+                .collect::<Vec<_>>(),
+        )?;
+
+        let mut items = vec![
+            MenuItem::Entry {
+                cmd_id: TrayCommand::Show.id(),
+                text: "&Show Fullscreenizer",
+            },
+            MenuItem::Separator,
+        ];
+        if !window_labels.is_empty() {
+            items.push(MenuItem::Submenu {
+                submenu_hmenu: &windows_menu,
+                text: "&Fullscreenize",
+            });
+        }
+        if !app_labels.is_empty() {
+            items.push(MenuItem::Submenu {
+                submenu_hmenu: &recent_apps_menu,
+                text: "&Recent applications",
+            });
+        }
+        items.extend([
+            MenuItem::Separator,
+            MenuItem::Entry {
+                cmd_id: TrayCommand::Exit.id(),
+                text: "E&xit",
+            },
+        ]);
+        let menu = HMENU::CreatePopupMenu()?;
+        menu.append_item(&items)?;
+
+        // The menu is only closed by clicking elsewhere if the window is in the foreground
+        self.wnd.hwnd().SetForegroundWindow();
+        let chosen = menu.TrackPopupMenu(
+            TPM::RETURNCMD | TPM::RIGHTBUTTON,
+            GetCursorPos()?,
+            self.wnd.hwnd(),
+        )?;
+
+        match chosen.and_then(|id| TrayCommand::from_id(id as u16)) {
+            Some(TrayCommand::Show) => self.show_from_tray(),
+            Some(TrayCommand::Exit) => {
+                self.exiting.store(true, Ordering::Relaxed);
+                self.wnd.close();
+            }
+            Some(TrayCommand::Window(index)) => {
+                if let Some(window) = windows.get(index) {
+                    self.fullscreenize_from_tray(window);
+                }
+            }
+            Some(TrayCommand::RecentApp(index)) => {
+                let Some(app) = recent_apps.get(index) else {
+                    return Ok(());
+                };
+                match windows.iter().find(|window| {
+                    window
+                        .exe_name()
+                        .is_some_and(|exe_name| window_info::exe_name_matches(exe_name, app))
+                }) {
+                    Some(window) => self.fullscreenize_from_tray(window),
+                    None => self.report_action(
+                        Err(format!("No window of {app} is open")),
+                        self.wnd.hwnd(),
+                        "Fullscreenized",
+                    ),
+                }
+            }
+            None => {}
+        }
+
+        Ok(())
+    }
+
+    /// Function to fullscreenize a window chosen in the tray menu
+    /// # Arguments
+    /// * `window` - The window, as it was listed in the menu
+    fn fullscreenize_from_tray(&self, window: &WindowInfo) {
+        let hwnd = unsafe { HWND::from_ptr(window.hwnd as *mut _) };
+        let choice = self.monitor_choice(window.exe_name());
+        let scale = self.scale_choice(window.exe_name());
+
+        // The window may have been closed while the menu was open
+        let result = if hwnd.IsWindow() {
+            self.fullscreenize_window(&hwnd, &choice, scale)
+        } else {
+            Err("The window was closed".to_owned())
+        };
+        self.report_action(
+            result.map_err(|e| format!("Failed to fullscreenize \"{}\" - {e}", window.title)),
+            &hwnd,
+            "Fullscreenized",
+        );
+    }
+
+    /// Function to apply the profiles to the windows that appeared since the last check
+    fn apply_profiles(&self) {
+        let applied = {
+            let settings = lock(&self.settings);
+            let mut journal = lock(&self.journal);
+            let mut profile_watcher = lock(&self.profile_watcher);
+
+            profile_watcher.poll(&Win32System, &settings, &mut journal)
+        };
+
+        // Errors are not shown in a popup, as the user did not ask for these windows to be changed right now
+        let tray_visible = self.tray_visible.load(Ordering::Relaxed);
+        for applied in applied {
+            match applied.result {
+                Ok(desired) => {
+                    self.watch_window(applied.hwnd, desired);
+                    if let Some(exe_name) = applied.exe_name {
+                        self.add_recent_app(&exe_name);
+                    }
+                    if tray_visible {
+                        tray::show_balloon(
+                            self.wnd.hwnd(),
+                            &format!(
+                                "Applied profile '{}' to \"{}\"",
+                                applied.profile, applied.title
+                            ),
+                            false,
+                        );
+                    }
+                }
+                Err(e) => {
+                    let message = format!(
+                        "Failed to apply profile '{}' to \"{}\" - {e}",
+                        applied.profile, applied.title
+                    );
+                    eprintln!("{message}");
+                    if tray_visible {
+                        tray::show_balloon(self.wnd.hwnd(), &message, true);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Function to show the settings window and apply the changed settings
+    fn open_settings(&self) {
+        let monitors = lock(&self.monitors).clone();
+        let dark_mode = self.is_dark_mode.load(Ordering::Relaxed);
+        let dialog = SettingsDialog::new(&lock(&self.settings), monitors, dark_mode);
+
+        let Some(changed) = dialog.show(&self.wnd) else {
+            return;
+        };
+        let show_icons = changed.show_icons;
+        let topmost = changed.topmost;
+        if !self.update_settings(|settings| {
+            *settings = changed;
+            Ok(())
+        }) {
+            return;
+        }
+
+        // Apply the theme, which may have been changed from or to the system theme
+        self.set_system_theme(true);
+        self.wnd
+            .hwnd()
+            .InvalidateRect(None, true)
+            .map_err(|e| eprintln!("Failed to redraw the window - InvalidateRect Failed: {e}"))
+            .ok();
+
+        self.top_toggle.set_state(if topmost {
+            BST::CHECKED
+        } else {
+            BST::UNCHECKED
+        });
+        self.select_monitor(None);
+        self.select_scale(None);
+        self.register_hotkeys();
+        self.update_tray_icon();
+        self.update_watchdog();
+        self.update_live_updates();
+
+        // Refresh the process list to apply the icon setting and the exclusions
+        self.use_icons.store(show_icons, Ordering::SeqCst);
+        self.refresh_btn.trigger_click();
+    }
+
+    /// Function to select the monitor chosen for an application in the monitor picker
+    /// # Arguments
+    /// * `app` - The executable name of the application, or `None` to select the default monitor
+    ///
+    /// The default monitor from the settings is selected if no monitor was chosen for the application yet.
+    fn select_monitor(&self, app: Option<&str>) {
+        let choice = self.monitor_choice(app);
+
+        let index = lock(&self.monitors).index_of(&choice);
+        if let Some(index) = index {
+            self.monitor_picker.items().select(Some(index as u32));
+        }
+    }
+
+    /// Function to get the monitor chosen for an application
+    /// # Arguments
+    /// * `app` - The executable name of the application, or `None` to get the default monitor
+    /// # Returns
+    /// * The monitor last chosen for the application, or the default monitor from the settings
+    fn monitor_choice(&self, app: Option<&str>) -> MonitorChoice {
+        let settings = lock(&self.settings);
+
+        app.and_then(|app| settings.app_choices(app)).map_or_else(
+            || settings.default_monitor.clone(),
+            |choices| choices.monitor.clone(),
+        )
+    }
+
+    /// Function to select the scaling mode chosen for an application in the scaling picker
+    /// # Arguments
+    /// * `app` - The executable name of the application, or `None` to select the default mode
+    ///
+    /// The default mode from the settings is selected if no mode was chosen for the application yet.
+    fn select_scale(&self, app: Option<&str>) {
+        let scale = self.scale_choice(app);
+
+        if let Some(index) = ScaleMode::ALL.iter().position(|&mode| mode == scale) {
+            self.scale_picker.items().select(Some(index as u32));
+        }
+    }
+
+    /// Function to get the scaling mode chosen for an application
+    /// # Arguments
+    /// * `app` - The executable name of the application, or `None` to get the default mode
+    /// # Returns
+    /// * The mode last chosen for the application, or the default mode from the settings
+    fn scale_choice(&self, app: Option<&str>) -> ScaleMode {
+        let settings = lock(&self.settings);
+
+        app.and_then(|app| settings.app_choices(app))
+            .and_then(|choices| choices.scale)
+            .unwrap_or(settings.scale_mode)
+    }
+
+    /// Function to get the scaling mode selected in the scaling picker
+    fn selected_scale(&self) -> ScaleMode {
+        self.scale_picker
+            .items()
+            .selected_index()
+            .and_then(|index| ScaleMode::ALL.get(index as usize).copied())
+            .unwrap_or_default()
+    }
+
+    /// Function to fill the preset picker with the presets from the settings, selecting the saved preset
+    ///
+    /// The scaling picker is disabled while a preset is selected, as the preset decides the size of the window.
+    fn refresh_preset_picker(&self) {
+        let (names, selected) = {
+            let settings = lock(&self.settings);
+            let names: Vec<String> = iter::once(NO_PRESET_LABEL.to_owned())
+                .chain(settings.presets.iter().map(|preset| preset.name.clone()))
+                .collect();
+            let selected = settings
+                .preset
+                .as_deref()
+                .and_then(|name| {
+                    settings
+                        .presets
+                        .iter()
+                        .position(|preset| preset.name.to_lowercase() == name.to_lowercase())
+                })
+                .map_or(0, |index| index + 1);
+            (names, selected)
+        };
+
+        let items = self.preset_picker.items();
+        items.delete_all();
+        items.add(&names);
+        items.select(Some(selected as u32));
+        self.scale_picker.hwnd().EnableWindow(selected == 0);
+    }
+
+    /// Function to get how the window is sized according to the scaling and preset pickers
+    /// # Arguments
+    /// * `scale` - The scaling mode chosen for the window, used if no preset is selected
+    /// # Returns
+    /// * The selected preset, or the scaling mode
+    fn selected_sizing(&self, scale: ScaleMode) -> Sizing {
+        let preset = self
+            .preset_picker
+            .items()
+            .selected_index()
+            .and_then(|index| (index as usize).checked_sub(1))
+            .and_then(|index| lock(&self.settings).presets.get(index).cloned());
+
+        preset.map_or(Sizing::Scale(scale), |preset| Sizing::Rect(preset.rect))
+    }
+
+    /// Function to show the exclusion editor and apply the edited exclusions
+    fn edit_exclusions(&self) {
+        let dark_mode = self.is_dark_mode.load(Ordering::Relaxed);
+        let dialog = ExclusionsDialog::new(&lock(&self.settings).exclusions, dark_mode);
+
+        if let Some(edited) = dialog.show(&self.wnd) {
+            self.update_exclusions(|exclusions| {
+                *exclusions = edited;
+                Ok(())
+            });
+        }
+    }
+
+    /// Function to change the exclusions, save them and refresh the process list
+    /// # Arguments
+    /// * `change` - The change to make, which returns an error message if it failed
+    fn update_exclusions(&self, change: impl FnOnce(&mut ExclusionList) -> Result<(), String>) {
+        if self.update_settings(|settings| change(&mut settings.exclusions)) {
+            // Refresh the process list to apply the new exclusions
+            self.refresh_btn.trigger_click();
+        }
+    }
+
+    /// Function to change the settings and save them
+    /// # Arguments
+    /// * `change` - The change to make, which returns an error message if it failed
+    /// # Returns
+    /// * `true` if the settings were changed, even if they could not be saved
+    fn update_settings(&self, change: impl FnOnce(&mut Settings) -> Result<(), String>) -> bool {
+        // The settings are unlocked before an error is shown, as the timers still run while the message box is open
+        let result = {
+            let mut settings = lock(&self.settings);
+            change(&mut settings).map(|()| settings.save())
+        };
+
+        match result {
+            Err(e) => {
+                show_error_message(&format!("Failed to change the settings - {e}"));
+                false
+            }
+            // The new settings are still used for this session if they could not be saved
+            Ok(Err(e)) => {
+                show_error_message(&format!("Failed to save the settings - {e}"));
+                true
+            }
+            Ok(Ok(())) => true,
+        }
+    }
+
+    /// Function to rebuild the columns of the process list from the settings
+    ///
+    /// Without optional columns, the titles are shown in a single column that is wider than the list and has no header.
+    /// The process list must be refreshed afterwards, as the rows do not have the text of the new columns.
+    fn update_columns(&self) {
+        let list_columns = lock(&self.settings).list_columns.clone();
+        let app_dpi = self.app_dpi.load(Ordering::Relaxed);
+        let mut shown_columns = write(&self.shown_columns);
+
+        // Show the header only when there are several columns, so that the single-column look is kept by default
+        let header_styles = (LVS::NOCOLUMNHEADER | LVS::NOSORTHEADER).raw();
+        let style = self.process_list.hwnd().style().raw();
+        let style = if list_columns.is_single_column() {
+            style | header_styles
+        } else {
+            style & !header_styles
+        };
+        self.process_list
+            .hwnd()
+            .set_style(unsafe { WS::from_raw(style) });
+
+        // Remove every column but the first one, from the last one so that the indexes stay valid
+        for index in (1..shown_columns.len()).rev() {
+            unsafe {
+                self.process_list.hwnd().SendMessage(DeleteColumn {
+                    index: index as u32,
+                })
+            }
+            .map_err(|e| eprintln!("Failed to remove a column - DeleteColumn Failed: {e}"))
+            .ok();
+        }
+
+        // The first column always holds the titles
+        let title_column = self.process_list.cols().get(0);
+        if list_columns.is_single_column() {
+            // Make the single column very wide, so that the end of the column is never visible
+            title_column.set_text("").ok();
+            title_column.set_width(32000).ok();
+        } else {
+            title_column
+                .set_text(&list_columns.header(Column::Title))
+                .ok();
+            title_column
+                .set_width(dpi_scale_val(list_columns.width(Column::Title), app_dpi))
+                .ok();
+        }
+
+        let columns = list_columns.columns();
+        for &column in columns.iter().skip(1) {
+            self.process_list
+                .cols()
+                .add(
+                    &list_columns.header(column),
+                    dpi_scale_val(list_columns.width(column), app_dpi),
+                )
+                .map_err(|e| eprintln!("Failed to add the {} column: {e}", column.label()))
+                .ok();
+        }
+
+        *shown_columns = columns;
+    }
+
+    /// Function to remember the widths of the columns of the process list in the settings
+    ///
+    /// The widths are saved at 96 DPI, so that they are kept when the DPI changes.
+    fn save_column_widths(&self) {
+        let shown_columns = read(&self.shown_columns).clone();
+        // The width of the single title column is not chosen by the user
+        if shown_columns.len() < 2 {
+            return;
+        }
+
+        let app_dpi = self.app_dpi.load(Ordering::Relaxed);
+        let widths: Vec<(Column, i32)> = shown_columns
+            .iter()
+            .enumerate()
+            .filter_map(|(index, &column)| {
+                let width = self.process_list.cols().get(index as u32).width().ok()?;
+                Some((column, w::MulDiv(width as i32, 96, app_dpi as i32)))
+            })
+            .collect();
+
+        // Avoid writing the settings when no column was resized
+        let list_columns = lock(&self.settings).list_columns.clone();
+        if widths
+            .iter()
+            .all(|&(column, width)| list_columns.width(column) == width)
+        {
+            return;
+        }
+
+        self.update_settings(|settings| {
+            settings.list_columns.widths.extend(widths);
+            Ok(())
+        });
+    }
+
+    /// Function to show or hide an optional column of the process list
+    /// # Arguments
+    /// * `column` - The column
+    fn toggle_column(&self, column: Column) {
+        self.save_column_widths();
+        if self.update_settings(|settings| {
+            settings.list_columns.toggle(column);
+            Ok(())
+        }) {
+            self.update_columns();
+            self.refresh_btn.trigger_click();
+        }
+    }
+
+    /// Function to sort the process list after a column header was clicked
+    /// # Arguments
+    /// * `index` - The index of the clicked column
+    fn sort_by_column(&self, index: usize) {
+        let column = read(&self.shown_columns).get(index).copied();
+        let Some(column) = column else {
+            return;
+        };
+
+        self.save_column_widths();
+        if self.update_settings(|settings| {
+            settings.list_columns.sort_by(column);
+            Ok(())
+        }) {
+            // Rebuild the columns to move the sort arrow to the clicked header
+            self.update_columns();
+            self.refresh_btn.trigger_click();
+        }
+    }
+
+    /// Function to refresh the process list
+    /// # Arguments
+    /// * `windows` - The windows shown in the process list, in the order of its rows
+    /// * `scan_windows` - Whether to look for opened, closed and changed windows,
+    ///   or to only recreate the icons after a DPI change
+    ///
+    /// Only the rows of changed windows are added, removed or updated,
+    /// so that the selection, the scroll position and the icons of the other rows are kept.
+    fn refresh_process_list(
+        &self,
+        windows: &mut MutexGuard<Vec<HWND>>,
+        scan_windows: bool,
+    ) -> w::AnyResult<()> {
+        // Create an image list to store the icons
+        let image_list = HIMAGELIST::Create(
+            SIZE::from(dpi_scale((16, 16), self.app_dpi.load(Ordering::Relaxed))),
+            ILC::COLOR32,
+            0,
+            100,
+        )
+        .unwrap_or_else(|e| {
+            // If creating the image list failed, disable the use of icons
+            self.use_icons.store(false, Ordering::SeqCst);
+            eprintln!("Imagelist Creation failed {e}");
+            unsafe { ImageListDestroyGuard::new(HIMAGELIST::NULL) }
+        });
+
+        let use_icons = self.use_icons.load(Ordering::SeqCst);
+
+        // Add icons to the new image list from the icon cache, so that the rows keep their icon
+        // The rows refer to their icon by index, so the list is rebuilt if an icon got another index
+        let mut icons_moved = false;
+        if use_icons {
+            for cached in lock(&self.window_icons).iter_mut() {
+                let index = image_list
+                    .AddIcon(&cached.icon)
+                    .map_err(|e| {
+                        eprintln!("AddIcon failed {e}\n");
+                    })
+                    .ok();
+                icons_moved |= index != cached.index;
+                cached.index = index;
+            }
+        }
+
+        // Enumerate over all open windows
+        let new_rows = (scan_windows || icons_moved).then(|| self.listed_rows());
+
+        let mut list_rows = write(&self.list_rows);
+        let mut window_icons = lock(&self.window_icons);
+
+        // Start over when the icons were turned on or off or got other indexes, as the rows already in the list
+        // would keep their old icon, or when most of the cached icons belong to windows that are no longer listed
+        if let Some(new_rows) = &new_rows
+            && (self.list_has_icons.swap(use_icons, Ordering::Relaxed) != use_icons
+                || window_icons.len() > 2 * new_rows.len() + MAX_STALE_ICONS
+                || icons_moved)
+        {
+            self.process_list.items().delete_all()?;
+            windows.clear();
+            list_rows.clear();
+            window_icons.clear();
+        }
+
+        if let Some(new_rows) = new_rows {
+            // Remember the selected window, to select it again if its row is moved
+            let selected = self
+                .process_list
+                .items()
+                .iter_selected()
+                .next()
+                .and_then(|item| list_rows.get(item.index() as usize))
+                .map(|row| row.hwnd);
+
+            for change in list_diff::diff(&list_rows, &new_rows) {
+                match change {
+                    RowChange::Remove(index) => {
+                        self.process_list
+                            .items()
+                            .get(index as u32)
+                            .delete()
+                            .map_err(|e| {
+                                eprintln!(
+                                    "Failed to remove item from process list - Delete failed: {e}"
+                                );
+                            })
+                            .ok();
+                        windows.remove(index);
+                    }
+                    RowChange::Insert(index, row) => {
+                        let icon_id = if use_icons {
+                            Self::window_icon_index(&mut window_icons, &image_list, row.hwnd)
+                        } else {
+                            None
+                        };
+
+                        self.insert_row(index, row, icon_id)
+                            .map_err(|e| {
+                                eprintln!(
+                                    "Failed to add item to process list - InsertItem failed: {e}"
+                                );
+                            })
+                            .ok();
+                        windows.insert(index, unsafe { HWND::from_ptr(row.hwnd as *mut _) });
+                    }
+                    RowChange::Update(index, row) => {
+                        let item = self.process_list.items().get(index as u32);
+                        for (column, text) in row.texts.iter().enumerate() {
+                            item.set_text(column as u32, text)
+                                .map_err(|e| {
+                                    eprintln!("Failed to update item of process list - SetItemText failed: {e}");
+                                })
+                                .ok();
+                        }
+                    }
+                }
+            }
+            *list_rows = new_rows;
+
+            // Select the window again if its row was moved
+            if let Some(index) =
+                selected.and_then(|hwnd| list_rows.iter().position(|row| row.hwnd == hwnd))
+            {
+                let item = self.process_list.items().get(index as u32);
+                if !item.is_selected() {
+                    item.select(true)
+                        .and_then(|()| item.ensure_visible())
+                        .map_err(|e| eprintln!("Failed to select the moved window: {e}"))
+                        .ok();
+                }
+            }
+        }
+
+        // Set the image list for the listview
+        let _ = unsafe {
+            self.process_list.hwnd().SendMessage(SetImageList {
+                himagelist: if use_icons {
+                    Some(image_list.raw_copy())
+                } else {
+                    None
+                },
+                kind: LVSIL::SMALL,
+            })
+        };
+
+        // Store the image list, dropping the old one
+        if let Ok(mut imagelist) = self.imagelist.lock() {
+            imagelist.replace(image_list);
+        }
+
+        Ok(())
+    }
+
+    /// Function to get the rows the process list should have
+    /// # Returns
+    /// * The rows of the listed windows, in the order chosen in the settings
+    fn listed_rows(&self) -> Vec<ListRow> {
+        let settings = lock(&self.settings);
+
+        let query = self.filter_edit.hwnd().GetWindowText().unwrap_or_default();
+
+        // The other properties of the windows are only queried if they are shown, filtered or used to recognize games
+        let list_columns = &settings.list_columns;
+        let game_detection = &settings.game_detection;
+        let query_all = !list_columns.is_single_column()
+            || !query.trim().is_empty()
+            || game_detection.games_first;
+        let mut listed: Vec<WindowInfo> =
+            window_ops::listed_windows(&Win32System, &settings.exclusions)
+                .into_iter()
+                .map(|(hwnd, title)| {
+                    if query_all {
+                        window_ops::window_info(&Win32System, hwnd)
+                    } else {
+                        WindowInfo {
+                            hwnd,
+                            title,
+                            ..WindowInfo::default()
+                        }
+                    }
+                })
+                .collect();
+        list_columns.sort(&mut listed);
+
+        // List the likely games first, keeping the chosen order within the games and within the other windows
+        let games: HashSet<isize> = if game_detection.games_first {
+            let monitors = lock(&self.monitors).clone();
+            listed
+                .iter()
+                .filter(|window| {
+                    let monitor = window
+                        .monitor
+                        .as_deref()
+                        .and_then(|name| monitors.find(name))
+                        .map(|monitor| monitor.rect);
+                    game_detection.is_likely_game(window, monitor)
+                })
+                .map(|window| window.hwnd)
+                .collect()
+        } else {
+            HashSet::new()
+        };
+        listed.sort_by_key(|window| !games.contains(&window.hwnd));
+
+        // The windows matching the filter best are listed first, in the order above when they match equally well
+        let listed = window_filter::filter(&query, listed);
+
+        let columns = list_columns.columns();
+        listed
+            .iter()
+            .map(|window| {
+                let mut texts: Vec<String> =
+                    columns.iter().map(|column| column.text(window)).collect();
+                // Show a badge before the title of the likely games
+                if games.contains(&window.hwnd)
+                    && let Some(title) = texts.first_mut()
+                {
+                    *title = format!("{GAME_BADGE} {title}");
+                }
+
+                ListRow {
+                    hwnd: window.hwnd,
+                    texts,
+                }
+            })
+            .collect()
+    }
+
+    /// Function to get the index of the icon of a window in the image list of the process list
+    /// # Arguments
+    /// * `window_icons` - The icon cache
+    /// * `image_list` - The image list, which the icon is added to if it is not cached yet
+    /// * `hwnd` - The raw handle of the window
+    /// # Returns
+    /// * The index of the icon, or `None` if it could not be added to the image list
+    fn window_icon_index(
+        window_icons: &mut Vec<CachedIcon>,
+        image_list: &HIMAGELIST,
+        hwnd: isize,
+    ) -> Option<u32> {
+        // Reuse the icon extracted when the window was listed before
+        if let Some(cached) = window_icons.iter().find(|cached| cached.hwnd == hwnd) {
+            return cached.index;
+        }
+
+        // If no method returned a valid icon, HICON::NULL is used
+        // TODO: Implement support for UWP app icons, which are not accessible via the methods used
+        let icon = Win32System.icon(hwnd).unwrap_or(HICON::NULL);
+
+        // Add the icon to the image list
+        match image_list.AddIcon(&icon) {
+            Ok(index) => {
+                // Cache the icon, so that it is added to the image lists created later
+                window_icons.push(CachedIcon {
+                    hwnd,
+                    icon: unsafe { DestroyIconGuard::new(icon) },
+                    index: Some(index),
+                });
+                Some(index)
+            }
+            Err(e) => {
+                eprintln!("AddIcon failed: '{e}'",);
+                None
+            }
+        }
+    }
+
+    /// Function to insert a row into the process list
+    /// # Arguments
+    /// * `index` - The index of the new row
+    /// * `row` - The row
+    /// * `icon_id` - The index of the icon of the row in the image list, or `None` to show no icon
+    /// # Returns
+    /// * An error if the row could not be inserted
+    fn insert_row(&self, index: usize, row: &ListRow, icon_id: Option<u32>) -> w::SysResult<()> {
+        let mut title = WString::from_str(row.texts.first().map_or("", String::as_str));
+
+        let mut item = LVITEM::default();
+        item.mask = LVIF::TEXT;
+        item.iItem = index as i32;
+        item.set_pszText(Some(&mut title));
+        if let Some(icon_id) = icon_id {
+            item.mask |= LVIF::IMAGE;
+            item.iImage = icon_id as i32;
+        }
+        let index = unsafe {
+            self.process_list
+                .hwnd()
+                .SendMessage(InsertItem { item: &item })
+        }?;
+
+        // The other columns are set once the row exists
+        let item = self.process_list.items().get(index);
+        for (column, text) in row.texts.iter().enumerate().skip(1) {
+            item.set_text(column as u32, text)?;
+        }
+
+        Ok(())
+    }
+
+    fn toggle_label_focus_rectangle(&self) -> Result<(), String> {
+        // Get the rectangle of the checkbox label relative to the window's client area
+        let ctrl_rect = match self.top_label.hwnd().GetWindowRect() {
+            Ok(rect) => match self
+                .wnd
+                .hwnd()
+                .ScreenToClient(POINT::with(rect.left, rect.top))
+            {
+                // Expand the rectangle slightly to make it more visible
+                Ok(pt) => RECT {
+                    left: pt.x - 2,
+                    top: pt.y - 1,
+                    right: pt.x + (rect.right - rect.left) + 1,
+                    bottom: pt.y + (rect.bottom - rect.top) + 1,
+                },
+                Err(e) => {
+                    eprintln!("ScreenToClient failed: {e}");
+                    return Err(format!("ScreenToClient failed: {e}").to_owned());
+                }
+            },
+            Err(e) => {
+                eprintln!("GetWindowRect failed: {e}");
+                return Err(format!("GetWindowRect failed: {e}").to_owned());
+            }
+        };
+
+        // Draw a focus rectangle around the checkbox label
+        // The focus rectangle does not draw over controls, so space is left between the checkbox and the label
+        self.wnd
+            .hwnd()
+            .GetDC()
+            .unwrap()
+            .DrawFocusRect(ctrl_rect)
+            .map_err(|e| format!("DrawFocusRect failed: {e}"))?;
+
+        Ok(())
+    }
+
+    fn events(&self) {
+        // Create a vector in a mutex to store the open windows
+        let windows: Arc<Mutex<Vec<HWND>>> = Arc::new(Mutex::new(Vec::new()));
+
+        self.wnd.on().wm_create({
+            let self2 = self.clone();
+            move |create| -> w::AnyResult<i32> {
+                // Store the current DPI
+                self2
+                    .app_dpi
+                    .store(self2.wnd.hwnd().GetDpiForWindow(), Ordering::Relaxed);
+
+                // Change the font in the buttons and label
+                self2.update_font();
+
+                // Set the theme of the window
+                self2.set_system_theme(true);
+
+                // Populate the monitor picker and select the default monitor and scaling
+                self2.refresh_monitor_picker();
+                self2.refresh_preset_picker();
+                self2.select_monitor(None);
+                self2.select_scale(None);
+
+                // Show the columns chosen in the settings
+                self2.update_columns();
+
+                // Explain the empty filter edit box, even while it has the focus
+                unsafe {
+                    self2.filter_edit.hwnd().SendMessage(SetCueBanner {
+                        show_focused: true,
+                        text: WString::from_str("Filter windows"),
+                    })
+                }
+                .map_err(|e| eprintln!("Failed to set the filter hint - SetCueBanner Failed: {e}"))
+                .ok();
+
+                // Refresh the process list
+                self2.refresh_btn.trigger_click();
+
+                // Send a message to handle post-creation tasks
+                unsafe {
+                    self2
+                        .wnd
+                        .hwnd()
+                        .PostMessage(WndMsg::new(WM::APP, WM::USER.raw() as usize, 0))
+                        .map_err(|e| {
+                            eprintln!("Failed to post WM_APP message - PostMessage Failed: {e}");
+                        })
+                        .ok();
+                };
 
                 // Call the default window procedure
                 unsafe { self2.wnd.hwnd().DefWindowProc(create) };
@@ -677,7 +2327,8 @@ impl MyWindow {
                 if msg.wparam == WM::USER.raw() as usize {
                     // Set the canvas as the button's parent
                     self2.refresh_btn.hwnd().SetParent(self2.btn_canvas.hwnd()).ok();
-                    self2.help_btn.hwnd().SetParent(self2.btn_canvas.hwnd()).ok();
+                    self2.settings_btn.hwnd().SetParent(self2.btn_canvas.hwnd()).ok();
+                    self2.restore_btn.hwnd().SetParent(self2.btn_canvas.hwnd()).ok();
                     self2.fullscreenize_btn.hwnd().SetParent(self2.btn_canvas.hwnd()).ok();
 
                     // Force the buttons to repaint
@@ -685,12 +2336,38 @@ impl MyWindow {
                     self2.refresh_btn.hwnd().InvalidateRect(None, true).map_err(|e| {
                         eprintln!("Failed to trigger a paint of the refresh button - InvalidateRect Failed: {e}");
                     }).ok();
-                    self2.help_btn.hwnd().InvalidateRect(None, true).map_err(|e| {
-                        eprintln!("Failed to trigger a paint of the help button - InvalidateRect Failed: {e}");
+                    self2.settings_btn.hwnd().InvalidateRect(None, true).map_err(|e| {
+                        eprintln!("Failed to trigger a paint of the settings button - InvalidateRect Failed: {e}");
+                    }).ok();
+                    self2.restore_btn.hwnd().InvalidateRect(None, true).map_err(|e| {
+                        eprintln!("Failed to trigger a paint of the restore button - InvalidateRect Failed: {e}");
                     }).ok();
                     self2.fullscreenize_btn.hwnd().InvalidateRect(None, true).map_err(|e| {
                         eprintln!("Failed to trigger a paint of the fullscreenize button - InvalidateRect Failed: {e}");
                     }).ok();
+
+                    // Offer to restore windows left modified by a previous session
+                    self2.restore_previous_session();
+
+                    // Start applying the profiles to new windows
+                    self2
+                        .wnd
+                        .hwnd()
+                        .SetTimer(PROFILE_TIMER_ID, watcher::POLL_INTERVAL_MS, None)
+                        .map_err(|e| eprintln!("Failed to start the profile watcher - SetTimer Failed: {e}"))
+                        .ok();
+
+                    // Listen for the global hotkeys
+                    self2.register_hotkeys();
+
+                    // Show the tray icon if it is enabled
+                    self2.update_tray_icon();
+
+                    // Start keeping fullscreenized windows in fullscreen if the watchdog is enabled
+                    self2.update_watchdog();
+
+                    // Start updating the process list as windows change if live updates are enabled
+                    self2.update_live_updates();
                 }
 
                 Ok(0)
@@ -699,7 +2376,12 @@ impl MyWindow {
 
         self.wnd.on().wm(WM::WININICHANGE, {
             let self2 = self.clone();
-            move |_| {
+            move |p| {
+                // The taskbar or a docked app bar was moved, resized or hidden
+                if p.wparam == SPI_SETWORKAREA {
+                    self2.refit_work_area_windows();
+                }
+
                 // Update the current theme
                 self2.set_system_theme(false);
 
@@ -717,6 +2399,16 @@ impl MyWindow {
             }
         });
 
+        self.wnd.on().wm(WM::DISPLAYCHANGE, {
+            let self2 = self.clone();
+            move |_| {
+                // Monitors may have been connected, disconnected or rearranged
+                self2.refresh_monitor_picker();
+
+                Ok(0)
+            }
+        });
+
         // Receive the button click events and forward them to the main window
         // This is necessary to ensure that the main window receives the button click events
         self.btn_canvas.on_subclass().wm(WM::COMMAND, {
@@ -738,6 +2430,9 @@ impl MyWindow {
             let self2 = self.clone();
             let windows = windows.clone();
             move |dpi_changed: WndMsg| {
+                // Remember the column widths at the old DPI, so that they are scaled to the new one
+                self2.save_column_widths();
+
                 // Store the new DPI of the window
                 // LOWORD and HIWORD of the wParam contains the X and Y DPI values, which should be the same
                 self2
@@ -746,35 +2441,159 @@ impl MyWindow {
 
                 // Change the font of the label
                 self2.update_font();
+                self2.update_columns();
+
+                // Refresh the process list without scanning for new windows
+                match windows.lock() {
+                    Ok(mut windows) => {
+                        // Refresh the process list
+                        self2.refresh_process_list(&mut windows, false)?;
+                    }
+                    Err(e) => {
+                        // Show a popup window with the error message
+                        show_error_message(
+                            format!("Failed to refresh process list - Mutex lock failed: {e}")
+                                .as_str(),
+                        );
+                    }
+                }
+
+                // Call the default window procedure
+                unsafe { self2.wnd.hwnd().DefWindowProc(dpi_changed) };
+
+                Ok(0)
+            }
+        });
+
+        // Show the window or the tray menu when the tray icon is clicked
+        self.wnd.on().wm(tray::callback_message(), {
+            let self2 = self.clone();
+            move |msg| {
+                // The mouse message is in the low word of the lParam
+                let mouse_msg = (msg.lparam & 0xFFFF) as u32;
+                if mouse_msg == WM::LBUTTONUP.raw() {
+                    self2.show_from_tray();
+                } else if mouse_msg == WM::RBUTTONUP.raw() {
+                    self2.show_tray_menu()?;
+                }
+
+                Ok(0)
+            }
+        });
+
+        // Hide the window in the tray instead of closing it, if chosen in the settings
+        self.wnd.on().wm_close({
+            let self2 = self.clone();
+            move || {
+                let close_to_tray = lock(&self2.settings).close_to_tray;
+                if close_to_tray
+                    && self2.tray_visible.load(Ordering::Relaxed)
+                    && !self2.exiting.load(Ordering::Relaxed)
+                {
+                    self2.wnd.hwnd().ShowWindow(SW::HIDE);
+                    return Ok(());
+                }
+
+                self2.wnd.hwnd().DestroyWindow()?;
+                Ok(())
+            }
+        });
+
+        // Remove the tray icon, as it would otherwise stay in the notification area until hovered
+        // and remember the column widths chosen by the user
+        self.wnd.on().wm_destroy({
+            let self2 = self.clone();
+            move || {
+                if self2.tray_visible.swap(false, Ordering::Relaxed) {
+                    tray::remove_icon(self2.wnd.hwnd());
+                }
+                // The list is still open here, as child windows are destroyed after their parent
+                self2.save_column_widths();
+                Ok(())
+            }
+        });
+
+        // Run the action of a global hotkey
+        self.wnd.on().wm(WM::HOTKEY, {
+            let self2 = self.clone();
+            move |msg| {
+                self2.handle_hotkey(msg.wparam as i32);
+                Ok(0)
+            }
+        });
+
+        // Apply the profiles to new windows
+        self.wnd.on().wm_timer(PROFILE_TIMER_ID, {
+            let self2 = self.clone();
+            move || {
+                self2.apply_profiles();
+                if self2.watchdog_enabled() {
+                    self2.check_watched_windows();
+                }
+                self2.update_backdrops(None);
+                Ok(())
+            }
+        });
+
+        // Check a watched window when it was moved, resized or brought to the foreground,
+        // and keep its backdrop behind it
+        self.wnd.on().wm(win_events::changed_message(), {
+            let self2 = self.clone();
+            move |msg| {
+                let hwnd = msg.wparam as isize;
+                if self2.watchdog_enabled() {
+                    self2.check_window(hwnd);
+                }
+                self2.update_backdrops(Some(hwnd));
+                Ok(0)
+            }
+        });
 
-                // Refresh the process list without scanning for new windows
-                match windows.lock() {
-                    Ok(mut windows) => {
-                        // Refresh the process list
-                        self2.refresh_process_list(&mut windows, false)?;
-                    }
-                    Err(e) => {
-                        // Show a popup window with the error message
-                        show_error_message(
-                            format!("Failed to refresh process list - Mutex lock failed: {e}")
-                                .as_str(),
-                        );
-                    }
+        // Refresh the process list once windows stopped changing for a moment
+        self.wnd.on().wm(win_events::list_changed_message(), {
+            let self2 = self.clone();
+            move |msg| {
+                // Closing a window that is not listed, such as a tooltip, does not change the process list
+                let hwnd = msg.wparam as isize;
+                let listed = read(&self2.list_rows).iter().any(|row| row.hwnd == hwnd);
+                if !listed && !Win32System.is_window(hwnd) {
+                    return Ok(0);
                 }
 
-                // Call the default window procedure
-                unsafe { self2.wnd.hwnd().DefWindowProc(dpi_changed) };
+                // Setting the timer again restarts it
+                self2
+                    .wnd
+                    .hwnd()
+                    .SetTimer(LIVE_UPDATE_TIMER_ID, LIVE_UPDATE_DELAY_MS, None)
+                    .map_err(|e| eprintln!("Failed to schedule a refresh - SetTimer Failed: {e}"))
+                    .ok();
 
                 Ok(0)
             }
         });
 
+        self.wnd.on().wm_timer(LIVE_UPDATE_TIMER_ID, {
+            let self2 = self.clone();
+            move || {
+                self2
+                    .wnd
+                    .hwnd()
+                    .KillTimer(LIVE_UPDATE_TIMER_ID)
+                    .map_err(|e| {
+                        eprintln!("Failed to stop the refresh timer - KillTimer Failed: {e}")
+                    })
+                    .ok();
+                self2.refresh_btn.trigger_click();
+                Ok(())
+            }
+        });
+
         self.wnd.on().wm_get_min_max_info({
             let self2 = self.clone();
             move |min_max| {
                 // Set the minimum size of the window
                 min_max.info.ptMinTrackSize =
-                    POINT::from(dpi_scale((244, 160), self2.app_dpi.load(Ordering::Relaxed)));
+                    POINT::from(dpi_scale((244, 188), self2.app_dpi.load(Ordering::Relaxed)));
 
                 Ok(())
             }
@@ -783,6 +2602,12 @@ impl MyWindow {
         self.wnd.on().wm_size({
             let self2 = self.clone();
             move |size| -> w::AnyResult<()> {
+                // Hide the window in the tray when it is minimized, if the tray icon is shown
+                if size.request == SIZE_R::MINIMIZED && self2.tray_visible.load(Ordering::Relaxed) {
+                    self2.wnd.hwnd().ShowWindow(SW::HIDE);
+                    return Ok(());
+                }
+
                 // Get the current dpi of the window
                 let app_dpi = self2.app_dpi.load(Ordering::Relaxed);
 
@@ -806,15 +2631,17 @@ impl MyWindow {
                     bottom: size.client_area.cy,
                 };
 
-                // Move the label to be in between the top of the window and the top of the process list
+                // Move the label to be in between the top of the window and the top of the process list,
+                // sharing the space with the filter edit box
+                let half_width = (new_size.right - new_size.left) / 2;
                 self2
                     .label
                     .hwnd()
                     .SetWindowPos(
                         HwndPlace::None,
-                        POINT::from(dpi_scale((8, 3), app_dpi)),
+                        POINT::from(dpi_scale((8, 7), app_dpi)),
                         SIZE::with(
-                            (new_size.right - new_size.left) - dpi_scale_val(16, app_dpi),
+                            half_width - dpi_scale_val(12, app_dpi),
                             dpi_scale_val(16, app_dpi),
                         ),
                         SWP::NOZORDER,
@@ -822,16 +2649,32 @@ impl MyWindow {
                     .map_err(|e| eprintln!("Failed to move label - SetWindowPos Failed: {e}"))
                     .ok();
 
+                // Move the filter edit box to the right of the label
+                self2
+                    .filter_edit
+                    .hwnd()
+                    .SetWindowPos(
+                        HwndPlace::None,
+                        POINT::with(half_width, dpi_scale_val(3, app_dpi)),
+                        SIZE::with(
+                            (new_size.right - new_size.left) - half_width - dpi_scale_val(7, app_dpi),
+                            dpi_scale_val(22, app_dpi),
+                        ),
+                        SWP::NOZORDER,
+                    )
+                    .map_err(|e| eprintln!("Failed to move filter edit box - SetWindowPos Failed: {e}"))
+                    .ok();
+
                 // Move the process list to be below the label
                 self2
                     .process_list
                     .hwnd()
                     .SetWindowPos(
                         HwndPlace::None,
-                        POINT::from(dpi_scale((6, 23), app_dpi)),
+                        POINT::from(dpi_scale((6, 29), app_dpi)),
                         SIZE::with(
                             (new_size.right - new_size.left) - dpi_scale_val(13, app_dpi),
-                            (new_size.bottom - new_size.top) - dpi_scale_val(86, app_dpi)
+                            (new_size.bottom - new_size.top) - dpi_scale_val(120, app_dpi)
                         ),
                         SWP::NOZORDER,
                     )
@@ -848,7 +2691,7 @@ impl MyWindow {
                         HwndPlace::None,
                         POINT::with(
                             dpi_scale_val(6, app_dpi),
-                            (new_size.bottom - new_size.top) - dpi_scale_val(57, app_dpi),
+                            (new_size.bottom - new_size.top) - dpi_scale_val(85, app_dpi),
                         ),
                         SIZE::from(dpi_scale((16, 16), app_dpi)),
                         SWP::NOZORDER,
@@ -865,7 +2708,7 @@ impl MyWindow {
                         POINT::with(
                             // Leave a small gap between the checkbox and the label for the selection box
                             dpi_scale_val(24, app_dpi),
-                            (new_size.bottom - new_size.top) - dpi_scale_val(57, app_dpi),
+                            (new_size.bottom - new_size.top) - dpi_scale_val(85, app_dpi),
                         ),
                         SIZE::from(dpi_scale((270, 16), app_dpi)),
                         // Don't use the SWP::NOZORDER flag, otherwise the previous frame of the listview may be visible
@@ -876,17 +2719,91 @@ impl MyWindow {
                     })
                     .ok();
 
+                // Resize and move the monitor picker to be above the buttons, leaving room for the scaling and preset pickers
+                // The height of a combo box is the height of its dropdown list, the control itself is sized by its font
+                let scale_picker_width = dpi_scale_val(110, app_dpi);
+                let preset_picker_width = dpi_scale_val(110, app_dpi);
+                self2
+                    .monitor_picker
+                    .hwnd()
+                    .SetWindowPos(
+                        HwndPlace::None,
+                        POINT::with(
+                            dpi_scale_val(6, app_dpi),
+                            (new_size.bottom - new_size.top) - dpi_scale_val(62, app_dpi),
+                        ),
+                        SIZE::with(
+                            (new_size.right - new_size.left)
+                                - dpi_scale_val(25, app_dpi)
+                                - scale_picker_width
+                                - preset_picker_width,
+                            dpi_scale_val(200, app_dpi),
+                        ),
+                        SWP::NOZORDER,
+                    )
+                    .map_err(|e| {
+                        eprintln!("Failed to move monitor picker - SetWindowPos Failed: {e}");
+                    })
+                    .ok();
+
+                // Move the scaling picker to the right of the monitor picker
+                self2
+                    .scale_picker
+                    .hwnd()
+                    .SetWindowPos(
+                        HwndPlace::None,
+                        POINT::with(
+                            (new_size.right - new_size.left)
+                                - dpi_scale_val(13, app_dpi)
+                                - scale_picker_width
+                                - preset_picker_width,
+                            (new_size.bottom - new_size.top) - dpi_scale_val(62, app_dpi),
+                        ),
+                        SIZE::with(scale_picker_width, dpi_scale_val(200, app_dpi)),
+                        SWP::NOZORDER,
+                    )
+                    .map_err(|e| {
+                        eprintln!("Failed to move scaling picker - SetWindowPos Failed: {e}");
+                    })
+                    .ok();
+
+                // Move the preset picker to the right edge, above the Fullscreenize button
+                self2
+                    .preset_picker
+                    .hwnd()
+                    .SetWindowPos(
+                        HwndPlace::None,
+                        POINT::with(
+                            (new_size.right - new_size.left)
+                                - dpi_scale_val(7, app_dpi)
+                                - preset_picker_width,
+                            (new_size.bottom - new_size.top) - dpi_scale_val(62, app_dpi),
+                        ),
+                        SIZE::with(preset_picker_width, dpi_scale_val(200, app_dpi)),
+                        SWP::NOZORDER,
+                    )
+                    .map_err(|e| {
+                        eprintln!("Failed to move preset picker - SetWindowPos Failed: {e}");
+                    })
+                    .ok();
+
                 // Determine the new size of the buttons
                 let btn_size: SIZE =
-                    if new_size.right - new_size.left >= dpi_scale_val(305, app_dpi) {
+                    if new_size.right - new_size.left >= dpi_scale_val(390, app_dpi) {
                         SIZE::from(dpi_scale((88, 26), app_dpi))
                     } else {
                         SIZE::with(
-                            ((new_size.right - new_size.left) / 3) - dpi_scale_val(13, app_dpi),
+                            ((new_size.right - new_size.left) / 4) - dpi_scale_val(12, app_dpi),
                             dpi_scale_val(26, app_dpi),
                         )
                     };
 
+                // Spread the buttons evenly between the left and right edges of the window
+                let btn_gap = ((new_size.right - new_size.left)
+                    - dpi_scale_val(20, app_dpi)
+                    - (btn_size.cx * 4))
+                    / 3;
+
                 // Resize and move the button canvas
                 self2
                     .btn_canvas
@@ -904,18 +2821,31 @@ impl MyWindow {
 
                 // Resize and align the buttons
                 self2
-                    .help_btn
+                    .settings_btn
+                    .hwnd()
+                    .SetWindowPos(
+                        HwndPlace::None,
+                        POINT::with(dpi_scale_val(10, app_dpi) + btn_size.cx + btn_gap, 0),
+                        btn_size,
+                        SWP::NOZORDER,
+                    )
+                    .map_err(|e| eprintln!("Failed to move settings button - SetWindowPos Failed: {e}"))
+                    .ok();
+                self2
+                    .restore_btn
                     .hwnd()
                     .SetWindowPos(
                         HwndPlace::None,
                         POINT::with(
-                            ((new_size.right - new_size.left) / 2) - (btn_size.cx / 2),
+                            dpi_scale_val(10, app_dpi) + ((btn_size.cx + btn_gap) * 2),
                             0,
                         ),
                         btn_size,
                         SWP::NOZORDER,
                     )
-                    .map_err(|e| eprintln!("Failed to move help button - SetWindowPos Failed: {e}"))
+                    .map_err(|e| {
+                        eprintln!("Failed to move restore button - SetWindowPos Failed: {e}");
+                    })
                     .ok();
                 self2
                     .refresh_btn
@@ -962,19 +2892,15 @@ impl MyWindow {
         self.wnd.on().wm_ctl_color_static({
             let self2 = self.clone();
             move |ctl| {
-                // Light mode background color and dark mode text color
-                let mut color = COLORREF::from_rgb(0xF0, 0xF0, 0xF0);
                 let is_dark_mode = self2.is_dark_mode.load(Ordering::Relaxed);
+                let color = theme::window_bg_color(is_dark_mode);
 
                 if is_dark_mode {
                     // Set the text color of the label to white
                     let _old_color = ctl
                         .hdc
-                        .SetTextColor(color)
+                        .SetTextColor(theme::text_color(true))
                         .map_err(|e| eprintln!("SetTextColor on the label failed: {e}"));
-
-                    // Set the color to the dark mode background color
-                    color = COLORREF::from_rgb(0x1E, 0x1E, 0x1E);
                 }
 
                 // Set the background color of the label's text
@@ -1008,16 +2934,15 @@ impl MyWindow {
                         Ok(mut background_hbrush) => {
                             // Create the brush if it does not exist
                             if background_hbrush.is_none() {
-                                HBRUSH::CreateSolidBrush(COLORREF::from_rgb(0x1E, 0x1E, 0x1E))
-                                    .map_or_else(
-                                        |e| {
-                                            eprintln!("CreateSolidBrush failed: {e}");
-                                        },
-                                        |hbrush| {
-                                            // Set the brush in the Arc Mutex
-                                            *background_hbrush = Some(hbrush);
-                                        },
-                                    );
+                                HBRUSH::CreateSolidBrush(theme::window_bg_color(true)).map_or_else(
+                                    |e| {
+                                        eprintln!("CreateSolidBrush failed: {e}");
+                                    },
+                                    |hbrush| {
+                                        // Set the brush in the Arc Mutex
+                                        *background_hbrush = Some(hbrush);
+                                    },
+                                );
                             }
 
                             // If the brush exists, use it to paint the window background
@@ -1078,19 +3003,23 @@ impl MyWindow {
         self.process_list.on_subclass().wm_nc_calc_size({
             let self2 = self.clone();
             move |calc_size| {
+                let single_column = read(&self2.shown_columns).len() < 2;
+
                 // Hide the process list's horizontal scrollbar
-                // The scrollbar would otherwise appear since the process list's column is wider than the listview
+                // The scrollbar would otherwise appear since the process list's single column is wider than the listview
                 // Performing this in the WM_NCCALCSIZE handler prevents the scrollbar from flickering
-                self2
-                    .process_list
-                    .hwnd()
-                    .ShowScrollBar(SBB::HORZ, false)
-                    .map_err(|e| {
-                        eprintln!(
-                            "Failed to hide horizontal scrollbar - ShowScrollBar Failed: {e}"
-                        );
-                    })
-                    .ok();
+                if single_column {
+                    self2
+                        .process_list
+                        .hwnd()
+                        .ShowScrollBar(SBB::HORZ, false)
+                        .map_err(|e| {
+                            eprintln!(
+                                "Failed to hide horizontal scrollbar - ShowScrollBar Failed: {e}"
+                            );
+                        })
+                        .ok();
+                }
 
                 Ok(unsafe { self2.process_list.hwnd().DefWindowProc(calc_size) })
             }
@@ -1128,6 +3057,38 @@ impl MyWindow {
             }
         });
 
+        // Remember the state of the checkbox for the next launch
+        self.top_toggle.on().bn_clicked({
+            let self2 = self.clone();
+            move || {
+                let topmost = self2.top_toggle.is_checked();
+                self2.update_settings(|settings| {
+                    settings.topmost = topmost;
+                    Ok(())
+                });
+
+                Ok(())
+            }
+        });
+
+        self.preset_picker.on().cbn_sel_change({
+            let self2 = self.clone();
+            move || {
+                // Save the selected preset, which replaces the scaling mode until it is cleared
+                let index = self2.preset_picker.items().selected_index().unwrap_or(0) as usize;
+                self2.scale_picker.hwnd().EnableWindow(index == 0);
+                self2.update_settings(|settings| {
+                    settings.preset = index
+                        .checked_sub(1)
+                        .and_then(|index| settings.presets.get(index))
+                        .map(|preset| preset.name.clone());
+                    Ok(())
+                });
+
+                Ok(())
+            }
+        });
+
         // Toggle the checkbox state when the label is clicked
         self.top_label.on().stn_clicked({
             let self2 = self.clone();
@@ -1173,10 +3134,125 @@ impl MyWindow {
             }
         });
 
-        self.help_btn.on().bn_clicked({
+        self.process_list.on().lvn_item_changed({
+            let self2 = self.clone();
+            let windows = windows.clone();
+            move |item_changed| {
+                // Only handle items becoming selected
+                if !item_changed.uNewState.has(LVIS::SELECTED)
+                    || item_changed.uOldState.has(LVIS::SELECTED)
+                {
+                    return Ok(());
+                }
+
+                let windows = lock(&windows);
+
+                // Select the monitor and scaling last chosen for the application, or the defaults
+                let app = windows.get(item_changed.iItem as usize).and_then(|window| {
+                    window_ops::window_exe_name(&Win32System, window.ptr() as isize)
+                });
+                self2.select_monitor(app.as_deref());
+                self2.select_scale(app.as_deref());
+
+                Ok(())
+            }
+        });
+
+        // Narrow the process list as the filter is typed
+        self.filter_edit.on().en_change({
+            let self2 = self.clone();
+            move || {
+                self2.refresh_btn.trigger_click();
+                Ok(())
+            }
+        });
+
+        // Handle the Enter key in the filter edit box, which would otherwise be taken by the dialog navigation
+        self.filter_edit.on_subclass().wm(WM::GETDLGCODE, {
+            let self2 = self.clone();
+            move |msg| {
+                let key = msg.wparam;
+                let code = unsafe { self2.filter_edit.hwnd().DefSubclassProc(msg) };
+                if key == usize::from(VK::RETURN.raw()) {
+                    return Ok(code | DLGC::WANTALLKEYS.raw() as isize);
+                }
+                Ok(code)
+            }
+        });
+
+        // Fullscreenize the best match when Enter is pressed in the filter edit box
+        self.filter_edit.on_subclass().wm(WM::KEYDOWN, {
+            let self2 = self.clone();
+            move |msg| {
+                if msg.wparam != usize::from(VK::RETURN.raw()) {
+                    return Ok(unsafe { self2.filter_edit.hwnd().DefSubclassProc(msg) });
+                }
+
+                // The best match is the first row
+                let Some(top_match) = self2.process_list.items().iter().next() else {
+                    return Ok(0);
+                };
+                top_match
+                    .select(true)
+                    .map_err(|e| eprintln!("Failed to select the best match: {e}"))
+                    .ok();
+                self2.fullscreenize_btn.trigger_click();
+
+                Ok(0)
+            }
+        });
+
+        // Do not beep when Enter is pressed in the filter edit box, as single-line edit boxes do not accept it
+        self.filter_edit.on_subclass().wm(WM::CHAR, {
+            let self2 = self.clone();
+            move |msg| {
+                if msg.wparam == usize::from(b'\r') {
+                    return Ok(0);
+                }
+                Ok(unsafe { self2.filter_edit.hwnd().DefSubclassProc(msg) })
+            }
+        });
+
+        // Paint the background of the filter edit box with the theme colors
+        self.wnd.on().wm_ctl_color_edit({
+            let self2 = self.clone();
+            move |ctl| {
+                let is_dark_mode = self2.is_dark_mode.load(Ordering::Relaxed);
+
+                let _old_color = ctl
+                    .hdc
+                    .SetTextColor(theme::text_color(is_dark_mode))
+                    .map_err(|e| eprintln!("SetTextColor on the edit box failed: {e}"));
+                let _old_bk_color = ctl
+                    .hdc
+                    .SetBkColor(theme::field_bg_color(is_dark_mode))
+                    .map_err(|e| eprintln!("SetBkColor on the edit box failed: {e}"));
+
+                Ok(match self2.field_hbrush.lock() {
+                    Ok(field_hbrush) => field_hbrush
+                        .as_ref()
+                        .map_or_else(|| HBRUSH::NULL, |hbrush| unsafe { hbrush.raw_copy() }),
+                    Err(e) => {
+                        eprintln!("Failed to lock field brush mutex: {e}");
+                        HBRUSH::NULL
+                    }
+                })
+            }
+        });
+
+        // Sort the process list by the clicked column
+        self.process_list.on().lvn_column_click({
+            let self2 = self.clone();
+            move |column_click| {
+                self2.sort_by_column(column_click.iSubItem as usize);
+                Ok(())
+            }
+        });
+
+        self.settings_btn.on().bn_clicked({
+            let self2 = self.clone();
             move || {
-                // TODO: Maybe replace with settings
-                show_help_message();
+                self2.open_settings();
                 Ok(())
             }
         });
@@ -1197,10 +3273,7 @@ impl MyWindow {
                 };
 
                 // Lock the window mutex
-                let windows = match windows.lock() {
-                    Ok(windows) => windows,
-                    Err(poisoned) => poisoned.into_inner(),
-                };
+                let windows = lock(&windows);
 
                 // Get the selected window
                 let Some(window) = windows.get(selected_item.index() as usize) else {
@@ -1208,54 +3281,167 @@ impl MyWindow {
                     return Ok(());
                 };
 
-                // Get the dimensions of the monitor the window is on
-                let Ok(monitor_info) = window
-                    .MonitorFromWindow(MONITOR::DEFAULTTONEAREST)
-                    .GetMonitorInfo()
-                    .map_err(|e| show_error_message(&format!("Failed to fullscreenize window - GetMonitorInfo failed with error: {e}")))
-                    else {
-                        return Ok(());
-                    };
-                let mut rect = RECT {
-                    left: 0,
-                    top: 0,
-                    right: monitor_info.rcMonitor.right - monitor_info.rcMonitor.left,
-                    bottom: monitor_info.rcMonitor.bottom - monitor_info.rcMonitor.top,
+                // Get the monitor chosen in the monitor picker
+                let choice = {
+                    let monitors = lock(&self2.monitors);
+                    self2
+                        .monitor_picker
+                        .items()
+                        .selected_index()
+                        .map(|index| monitors.choice_at(index as usize))
+                        .unwrap_or_default()
                 };
 
-                // Set the window style
-                window.set_style(WS::POPUP | WS::VISIBLE);
-
-                // Set the window size
-                match AdjustWindowRectExForDpi(rect, window.style(), false, window.style_ex(), window.GetDpiForWindow()) {
-                    Ok(rct) => rect = rct,
-                    Err(e) => {
-                        show_error_message(&format!("Failed to fullscreenize window - AdjustWindowRectExForDpi failed with error: {e}"));
-                        return Ok(());
-                    }
+                let scale = self2.selected_scale();
+
+                // Remember the choices for the application
+                if let Some(app) = window_ops::window_exe_name(&Win32System, window.ptr() as isize) {
+                    self2.remember_choices(
+                        &app,
+                        AppChoices {
+                            monitor: choice.clone(),
+                            scale: Some(scale),
+                        },
+                    );
                 }
 
-                // Set window to stay on top if checkbox is checked
-                let hwnd_insert_after = if self2.top_toggle.is_checked() {
-                    HwndPlace::Place(HWND_PLACE::TOPMOST)
-                } else {
-                    HwndPlace::None
+                self2
+                    .fullscreenize_window(window, &choice, scale)
+                    .map_err(|e| show_error_message(&format!("Failed to fullscreenize window - {e}")))
+                    .ok();
+
+                Ok(())
+            }
+        });
+
+        self.restore_btn.on().bn_clicked({
+            let self2 = self.clone();
+            let windows = windows.clone();
+            move || {
+                // Get the selected item
+                let Some(selected_item) = self2.process_list.items().iter_selected().next() else {
+                    eprintln!("Failed to restore window - Could not get selected item (no item selected?)");
+                    return Ok(());
                 };
 
-                // Set the window position
-                window
-                    .SetWindowPos(
-                        hwnd_insert_after,
-                        POINT::with(rect.left, rect.top),
-                        SIZE::with(rect.right - rect.left, rect.bottom - rect.top),
-                        SWP::FRAMECHANGED,
-                    )
-                    .map_err(|e| show_error_message(&format!("Failed to fullscreenize window - SetWindowPos failed with error: {e}")))
+                // Lock the window mutex
+                let windows = lock(&windows);
+
+                // Get the selected window
+                let Some(window) = windows.get(selected_item.index() as usize) else {
+                    show_error_message("Failed to restore window - Could not get the selected window from the list");
+                    return Ok(());
+                };
+
+                self2
+                    .restore_window(window)
+                    .map_err(|e| show_error_message(&format!("Failed to restore window - {e}")))
                     .ok();
 
                 Ok(())
             }
         });
+
+        // Show a context menu with the window actions when an item is right-clicked
+        self.process_list.on().nm_r_click({
+            let self2 = self.clone();
+            let windows = windows.clone();
+            move |click| {
+                // Create the submenu to show or hide the optional columns, checking the shown ones
+                let list_columns = lock(&self2.settings).list_columns.clone();
+                let columns_menu = HMENU::CreatePopupMenu()?;
+                columns_menu.append_item(
+                    &Column::OPTIONAL
+                        .iter()
+                        .zip(CMD_COLUMN_FIRST..)
+                        .map(|(column, cmd_id)| MenuItem::Entry {
+                            cmd_id,
+                            text: column.label(),
+                        })
+                        .collect::<Vec<_>>(),
+                )?;
+                for (&column, cmd_id) in Column::OPTIONAL.iter().zip(CMD_COLUMN_FIRST..) {
+                    if list_columns.is_shown(column) {
+                        columns_menu.CheckMenuItem(IdPos::Id(cmd_id), true)?;
+                    }
+                }
+
+                // Only the columns can be chosen when clicking outside of an item
+                let mut items = Vec::new();
+                if click.iItem >= 0 {
+                    items.extend([
+                        MenuItem::Entry {
+                            cmd_id: CMD_FULLSCREENIZE,
+                            text: "&Fullscreenize",
+                        },
+                        MenuItem::Entry {
+                            cmd_id: CMD_RESTORE,
+                            text: "Res&tore",
+                        },
+                        MenuItem::Separator,
+                        MenuItem::Entry {
+                            cmd_id: CMD_EXCLUDE_EXE,
+                            text: "Exclude this &application",
+                        },
+                        MenuItem::Entry {
+                            cmd_id: CMD_EXCLUDE_TITLE,
+                            text: "Exclude this &title",
+                        },
+                        MenuItem::Entry {
+                            cmd_id: CMD_EDIT_EXCLUSIONS,
+                            text: "&Edit exclusions...",
+                        },
+                        MenuItem::Separator,
+                        MenuItem::Entry {
+                            cmd_id: CMD_SAVE_PROFILE,
+                            text: "Save as &profile",
+                        },
+                        MenuItem::Entry {
+                            cmd_id: CMD_WATCHDOG_LOG,
+                            text: "&Watchdog log...",
+                        },
+                        MenuItem::Separator,
+                    ]);
+                }
+                items.push(MenuItem::Submenu {
+                    submenu_hmenu: &columns_menu,
+                    text: "&Columns",
+                });
+                let menu = HMENU::CreatePopupMenu()?;
+                menu.append_item(&items)?;
+
+                // Show the menu at the cursor and wait for the user's choice
+                let chosen = menu.TrackPopupMenu(
+                    TPM::RETURNCMD | TPM::RIGHTBUTTON,
+                    GetCursorPos()?,
+                    self2.wnd.hwnd(),
+                )?;
+
+                match chosen.map(|id| id as u16) {
+                    Some(CMD_FULLSCREENIZE) => self2.fullscreenize_btn.trigger_click(),
+                    Some(CMD_RESTORE) => self2.restore_btn.trigger_click(),
+                    Some(CMD_EXCLUDE_EXE) => {
+                        self2.exclude_window(&windows, click.iItem, ExclusionField::Exe);
+                    }
+                    Some(CMD_EXCLUDE_TITLE) => {
+                        self2.exclude_window(&windows, click.iItem, ExclusionField::Title);
+                    }
+                    Some(CMD_EDIT_EXCLUSIONS) => self2.edit_exclusions(),
+                    Some(CMD_SAVE_PROFILE) => self2.save_profile(&windows, click.iItem),
+                    Some(CMD_WATCHDOG_LOG) => self2.show_watchdog_log(),
+                    Some(id) if id >= CMD_COLUMN_FIRST => {
+                        if let Some(&column) =
+                            Column::OPTIONAL.get(usize::from(id - CMD_COLUMN_FIRST))
+                        {
+                            self2.toggle_column(column);
+                        }
+                    }
+                    _ => {}
+                }
+
+                Ok(0)
+            }
+        });
     }
 }
 
@@ -1277,26 +3463,3 @@ fn show_error_message(message: &str) {
         .map_err(|e| eprintln!("TaskDialog failed: {e}"))
         .ok();
 }
-
-/// Function to show a help message in a popup window
-fn show_help_message() {
-    // Show a popup window with the help message
-    // TODO: Create custom window so dark mode can be implemented
-    HWND::NULL
-        .TaskDialog(
-            Some("Fullscreenizer"),
-            None,
-            Some("Open the game you want to force in borderless-windowed-fullscreen mode, \
-                 set it to windowed mode to the resolution you want, hit the Refresh button \
-                 to refresh the windows list, select the game window from the list and press \
-                 the Fullscreenize button.  The window will be resized to the desktop area and \
-                 the border will be removed.  Note that using a different in-game resolution \
-                 from the desktop resolution may not work properly (or at all) depending on the game.\n\n\n\
-                 Made by Carter Persall\n\
-                 Based on the program by Kostas \"Bad Sector\" Michalopoulos"),
-            TDCBF::OK,
-            IconRes::None,
-        )
-        .map_err(|e| eprintln!("TaskDialog failed: {e}"))
-        .ok();
-}
@@ -0,0 +1,81 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use crate::settings::SETTINGS_FILE_NAME;
+
+/// Name of the directory Fullscreenizer stores its files in
+const APP_DIR_NAME: &str = "Fullscreenizer";
+
+/// Function to get the directory Fullscreenizer stores its files in
+/// # Returns
+/// * The directory of the executable in portable mode, otherwise `%APPDATA%\Fullscreenizer`
+///   (`~/.config/Fullscreenizer` on Linux), or `None` if the user's config directory could not be determined
+///
+/// Portable mode is enabled by placing a settings file next to the executable.
+/// The directory is not created by this function.
+pub fn config_dir() -> Option<PathBuf> {
+    portable_dir().or_else(|| user_config_dir().map(|dir| dir.join(APP_DIR_NAME)))
+}
+
+/// Function to get the user's config directory
+/// # Returns
+/// * `%APPDATA%`, or `None` if it is not set
+#[cfg(windows)]
+fn user_config_dir() -> Option<PathBuf> {
+    env_dir("APPDATA")
+}
+
+/// Function to get the user's config directory
+/// # Returns
+/// * `$XDG_CONFIG_HOME`, falling back to `~/.config`, or `None` if neither can be determined
+#[cfg(not(windows))]
+fn user_config_dir() -> Option<PathBuf> {
+    env_dir("XDG_CONFIG_HOME").or_else(|| env_dir("HOME").map(|home| home.join(".config")))
+}
+
+/// Function to read a directory from an environment variable
+/// # Returns
+/// * The directory, or `None` if the variable is not set or empty
+fn env_dir(name: &str) -> Option<PathBuf> {
+    env::var_os(name)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Function to get the directory of the executable if Fullscreenizer runs in portable mode
+/// # Returns
+/// * The directory of the executable, or `None` if there is no settings file next to it
+fn portable_dir() -> Option<PathBuf> {
+    let exe_path = env::current_exe().ok()?;
+    let dir = exe_path.parent()?;
+
+    dir.join(SETTINGS_FILE_NAME)
+        .is_file()
+        .then(|| dir.to_path_buf())
+}
+
+/// Function to replace the contents of a file without ever leaving it truncated
+/// # Arguments
+/// * `path` - Path of the file, whose directory is created if needed
+/// * `contents` - The new contents of the file
+/// # Returns
+/// * An error message if the file could not be written
+///
+/// The contents are written to a temporary file that then replaces the old file,
+/// so that a crash while writing never leaves a truncated file behind.
+pub fn write_atomic(path: &Path, contents: &str) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    }
+
+    // The temporary file is named after the process, so that instances writing at the same time do not share it
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(format!(".{}.tmp", process::id()));
+    let temp_path = PathBuf::from(temp_path);
+
+    fs::write(&temp_path, contents)
+        .map_err(|e| format!("Failed to write {}: {e}", temp_path.display()))?;
+    fs::rename(&temp_path, path).map_err(|e| format!("Failed to replace {}: {e}", path.display()))
+}
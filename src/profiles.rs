@@ -0,0 +1,516 @@
+use std::collections::HashSet;
+
+use regex::{Regex, RegexBuilder};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::geometry::Rect;
+use crate::monitors::MonitorChoice;
+use crate::scaling::ScaleMode;
+use crate::window_info;
+
+/// The position and size of a window, relative to the top-left corner of its monitor
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+/// A named set of options applied to the windows of an application
+///
+/// A window matches a profile if it satisfies every criterion the profile has,
+/// so a profile without any criterion never matches.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    /// The name of the profile, shown to the user
+    pub name: String,
+    /// The executable name of the application (case-insensitive, the .exe extension is optional)
+    pub exe: Option<String>,
+    /// The class name of the window (case-insensitive)
+    pub class: Option<String>,
+    /// A regular expression the title of the window must contain a match of (case-insensitive)
+    pub title: Option<String>,
+    /// The monitor the window is sent to
+    pub monitor: MonitorChoice,
+    /// Whether to apply the "stay on top" flag
+    pub topmost: bool,
+    /// Whether to remove the border of the window, or only to move and resize it
+    pub borderless: bool,
+    /// The rectangle the window should cover, or `None` to size the window on the monitor with `scale`
+    pub geometry: Option<ProfileGeometry>,
+    /// How the window is sized on the monitor when no geometry is given
+    pub scale: ScaleMode,
+    /// Whether the profile is applied automatically when a matching window appears
+    pub auto_apply: bool,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            exe: None,
+            class: None,
+            title: None,
+            monitor: MonitorChoice::Nearest,
+            topmost: false,
+            borderless: true,
+            geometry: None,
+            scale: ScaleMode::Stretch,
+            auto_apply: true,
+        }
+    }
+}
+
+impl Profile {
+    /// Function to compute the rectangle a window using this profile should occupy
+    /// # Arguments
+    /// * `monitor` - The full area of the target monitor, in virtual-desktop coordinates
+    /// * `client_size` - The width and height of the client area of the window before the profile is applied
+    /// # Returns
+    /// * The window rectangle, in virtual-desktop coordinates
+    pub fn target_rect(&self, monitor: Rect, client_size: (i32, i32)) -> Rect {
+        self.geometry.map_or_else(
+            || self.scale.target_rect(monitor, client_size),
+            |geometry| {
+                Rect::new(
+                    monitor.left + geometry.x,
+                    monitor.top + geometry.y,
+                    monitor.left + geometry.x + geometry.width,
+                    monitor.top + geometry.y + geometry.height,
+                )
+            },
+        )
+    }
+}
+
+/// A profile with its title pattern compiled
+#[derive(Clone, Debug)]
+struct CompiledProfile {
+    profile: Profile,
+    title: Option<Regex>,
+}
+
+impl CompiledProfile {
+    fn new(profile: Profile) -> Result<Self, String> {
+        let title = profile
+            .title
+            .as_deref()
+            .map(|title| {
+                RegexBuilder::new(title)
+                    .case_insensitive(true)
+                    .build()
+                    .map_err(|e| format!("Invalid title pattern '{title}': {e}"))
+            })
+            .transpose()?;
+
+        Ok(Self { profile, title })
+    }
+
+    fn matches(&self, exe_name: Option<&str>, class_name: &str, title: &str) -> bool {
+        let profile = &self.profile;
+
+        (profile.exe.is_some() || profile.class.is_some() || profile.title.is_some())
+            && profile.exe.as_deref().is_none_or(|exe| {
+                exe_name.is_some_and(|exe_name| window_info::exe_name_matches(exe_name, exe))
+            })
+            && profile
+                .class
+                .as_deref()
+                .is_none_or(|class| class_name.eq_ignore_ascii_case(class))
+            && self
+                .title
+                .as_ref()
+                .is_none_or(|regex| regex.is_match(title))
+    }
+}
+
+/// The profiles from the settings, in the order they are checked
+#[derive(Clone, Debug, Default)]
+pub struct Profiles {
+    profiles: Vec<CompiledProfile>,
+}
+
+impl Profiles {
+    /// Function to compile a list of profiles
+    /// # Arguments
+    /// * `profiles` - The profiles
+    /// # Returns
+    /// * The profiles, or an error message naming the first profile with an invalid title pattern
+    pub fn new(profiles: Vec<Profile>) -> Result<Self, String> {
+        let profiles = profiles
+            .into_iter()
+            .map(|profile| {
+                let name = profile.name.clone();
+                CompiledProfile::new(profile).map_err(|e| format!("Profile '{name}': {e}"))
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(Self { profiles })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Profile> {
+        self.profiles.iter().map(|compiled| &compiled.profile)
+    }
+
+    /// Function to add a profile, replacing the profile with the same name if there is one
+    /// # Arguments
+    /// * `profile` - The profile
+    /// # Returns
+    /// * An error message if the title pattern of the profile is invalid
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn insert(&mut self, profile: Profile) -> Result<(), String> {
+        let compiled = CompiledProfile::new(profile)?;
+
+        match self
+            .profiles
+            .iter_mut()
+            .find(|existing| existing.profile.name == compiled.profile.name)
+        {
+            Some(existing) => *existing = compiled,
+            None => self.profiles.push(compiled),
+        }
+
+        Ok(())
+    }
+
+    /// Function to find the profile to use for a window
+    /// # Arguments
+    /// * `exe_name` - The file name of the executable that owns the window, or `None` if it is unknown
+    /// * `class_name` - The class name of the window
+    /// * `title` - The title of the window
+    /// # Returns
+    /// * The first profile matching the window, or `None` if no profile matches
+    pub fn find(&self, exe_name: Option<&str>, class_name: &str, title: &str) -> Option<&Profile> {
+        self.profiles
+            .iter()
+            .find(|compiled| compiled.matches(exe_name, class_name, title))
+            .map(|compiled| &compiled.profile)
+    }
+}
+
+/// The profiles are stored in the settings file as an array of tables
+impl Serialize for Profiles {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for Profiles {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::new(Vec::<Profile>::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+/// The windows the profile watcher has already handled
+///
+/// A window is handled once a profile was applied to it, or if it already existed when watching started.
+/// Windows that do not match a profile yet are checked again, as games often change their title after starting.
+/// A handled window is never changed again, so that restoring it is not undone by the watcher.
+#[derive(Debug, Default)]
+pub struct SeenWindows {
+    handled: HashSet<isize>,
+    started: bool,
+}
+
+impl SeenWindows {
+    /// Function to get the windows that may need a profile applied
+    /// # Arguments
+    /// * `windows` - The raw handles of the windows currently listed
+    /// * `exists` - Function checking whether a handle still refers to a window, listed or not
+    /// # Returns
+    /// * The windows that were not handled yet, which is none on the first call
+    ///
+    /// Windows that were closed are forgotten, as their handles may be reused.
+    /// Windows that are only hidden or untitled for a moment are still remembered.
+    pub fn unhandled(&mut self, windows: &[isize], exists: impl Fn(isize) -> bool) -> Vec<isize> {
+        self.handled.retain(|&hwnd| exists(hwnd));
+
+        if !self.started {
+            self.started = true;
+            self.handled.extend(windows);
+            return Vec::new();
+        }
+
+        windows
+            .iter()
+            .copied()
+            .filter(|hwnd| !self.handled.contains(hwnd))
+            .collect()
+    }
+
+    /// Function to mark a window as handled, so that it is not changed again
+    pub fn mark_handled(&mut self, hwnd: isize) {
+        self.handled.insert(hwnd);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(name: &str) -> Profile {
+        Profile {
+            name: name.to_owned(),
+            ..Profile::default()
+        }
+    }
+
+    fn profiles(profiles: Vec<Profile>) -> Profiles {
+        Profiles::new(profiles).unwrap()
+    }
+
+    fn found<'a>(
+        profiles: &'a Profiles,
+        exe: Option<&str>,
+        class: &str,
+        title: &str,
+    ) -> Option<&'a str> {
+        profiles
+            .find(exe, class, title)
+            .map(|profile| profile.name.as_str())
+    }
+
+    #[test]
+    fn profiles_without_criteria_never_match() {
+        let profiles = profiles(vec![profile("Empty")]);
+        assert_eq!(found(&profiles, Some("game.exe"), "Class", "Title"), None);
+    }
+
+    #[test]
+    fn every_criterion_must_match() {
+        let profiles = profiles(vec![Profile {
+            exe: Some("Game".to_owned()),
+            class: Some("UnityWndClass".to_owned()),
+            title: Some(r"^super game \d+$".to_owned()),
+            ..profile("Super Game")
+        }]);
+
+        assert_eq!(
+            found(&profiles, Some("GAME.exe"), "unitywndclass", "Super Game 2"),
+            Some("Super Game")
+        );
+        assert_eq!(
+            found(
+                &profiles,
+                Some("game.exe"),
+                "UnityWndClass",
+                "Super Game Launcher"
+            ),
+            None
+        );
+        assert_eq!(
+            found(&profiles, Some("game.exe"), "OtherClass", "Super Game 2"),
+            None
+        );
+        assert_eq!(
+            found(
+                &profiles,
+                Some("other.exe"),
+                "UnityWndClass",
+                "Super Game 2"
+            ),
+            None
+        );
+        // A window whose executable is unknown never matches a profile with an executable
+        assert_eq!(
+            found(&profiles, None, "UnityWndClass", "Super Game 2"),
+            None
+        );
+    }
+
+    #[test]
+    fn titles_match_anywhere_ignoring_case() {
+        let profiles = profiles(vec![Profile {
+            title: Some("emulator".to_owned()),
+            ..profile("Emulators")
+        }]);
+        assert_eq!(
+            found(&profiles, None, "Class", "My EMULATOR 1.2"),
+            Some("Emulators")
+        );
+        assert_eq!(found(&profiles, None, "Class", "Emulation"), None);
+    }
+
+    #[test]
+    fn the_first_matching_profile_wins() {
+        let profiles = profiles(vec![
+            Profile {
+                exe: Some("game.exe".to_owned()),
+                title: Some("editor".to_owned()),
+                ..profile("Editor")
+            },
+            Profile {
+                exe: Some("game.exe".to_owned()),
+                ..profile("Game")
+            },
+        ]);
+        assert_eq!(
+            found(&profiles, Some("game.exe"), "C", "Level editor"),
+            Some("Editor")
+        );
+        assert_eq!(
+            found(&profiles, Some("game.exe"), "C", "Level 1"),
+            Some("Game")
+        );
+    }
+
+    #[test]
+    fn invalid_title_patterns_name_the_profile() {
+        let error = Profiles::new(vec![Profile {
+            title: Some("(unclosed".to_owned()),
+            ..profile("Broken")
+        }])
+        .unwrap_err();
+        assert!(
+            error.starts_with("Profile 'Broken': Invalid title pattern"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn insert_replaces_the_profile_with_the_same_name() {
+        let mut profiles = profiles(vec![
+            Profile {
+                exe: Some("a.exe".to_owned()),
+                ..profile("A")
+            },
+            Profile {
+                exe: Some("b.exe".to_owned()),
+                ..profile("B")
+            },
+        ]);
+        profiles
+            .insert(Profile {
+                exe: Some("c.exe".to_owned()),
+                ..profile("A")
+            })
+            .unwrap();
+        profiles
+            .insert(Profile {
+                exe: Some("d.exe".to_owned()),
+                ..profile("D")
+            })
+            .unwrap();
+
+        let names: Vec<&str> = profiles
+            .iter()
+            .map(|profile| profile.name.as_str())
+            .collect();
+        assert_eq!(names, ["A", "B", "D"]);
+        assert_eq!(found(&profiles, Some("a.exe"), "C", "T"), None);
+        assert_eq!(found(&profiles, Some("c.exe"), "C", "T"), Some("A"));
+        assert!(
+            profiles
+                .insert(Profile {
+                    title: Some("[".to_owned()),
+                    ..profile("B")
+                })
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn geometry_is_relative_to_the_monitor() {
+        let profile = Profile {
+            geometry: Some(ProfileGeometry {
+                x: 10,
+                y: 20,
+                width: 1280,
+                height: 720,
+            }),
+            ..profile("Windowed")
+        };
+        let monitor = Rect::new(-1920, -200, 0, 880);
+        assert_eq!(
+            profile.target_rect(monitor, (800, 600)),
+            Rect::new(-1910, -180, -630, 540)
+        );
+
+        let stretched = Profile {
+            geometry: None,
+            ..profile
+        };
+        assert_eq!(stretched.target_rect(monitor, (800, 600)), monitor);
+    }
+
+    #[test]
+    fn profiles_round_trip_through_toml() {
+        #[derive(Serialize, Deserialize)]
+        struct File {
+            profiles: Profiles,
+        }
+
+        let text = r#"
+            [[profiles]]
+            name = "Racing"
+            exe = "racing.exe"
+            monitor = { span = "All monitors" }
+            scale = "fit"
+
+            [[profiles]]
+            name = "Emulator"
+            title = "emulator"
+            auto_apply = false
+        "#;
+        let file: File = toml::from_str(text).unwrap();
+        let read: Vec<Profile> = file.profiles.iter().cloned().collect();
+        assert_eq!(
+            read,
+            [
+                Profile {
+                    exe: Some("racing.exe".to_owned()),
+                    monitor: MonitorChoice::Span("All monitors".to_owned()),
+                    scale: ScaleMode::Fit,
+                    ..profile("Racing")
+                },
+                Profile {
+                    title: Some("emulator".to_owned()),
+                    auto_apply: false,
+                    ..profile("Emulator")
+                },
+            ]
+        );
+
+        let written: File = toml::from_str(&toml::to_string(&file).unwrap()).unwrap();
+        assert!(written.profiles.iter().eq(file.profiles.iter()));
+
+        let invalid = "[[profiles]]\nname = \"Broken\"\ntitle = \"(\"\n";
+        assert!(toml::from_str::<File>(invalid).is_err());
+    }
+
+    #[test]
+    fn windows_open_when_watching_starts_are_never_handled() {
+        let mut seen = SeenWindows::default();
+        let exists = |_| true;
+        assert!(seen.unhandled(&[1, 2], exists).is_empty());
+        assert_eq!(seen.unhandled(&[1, 2, 3], exists), [3]);
+        // Windows without a matching profile yet are checked again
+        assert_eq!(seen.unhandled(&[1, 2, 3, 4], exists), [3, 4]);
+
+        seen.mark_handled(3);
+        assert_eq!(seen.unhandled(&[1, 2, 3, 4], exists), [4]);
+    }
+
+    #[test]
+    fn closed_windows_are_forgotten() {
+        let mut seen = SeenWindows::default();
+        assert!(seen.unhandled(&[1], |_| true).is_empty());
+
+        // The handle of a closed window may be reused by a new window
+        assert!(seen.unhandled(&[], |_| false).is_empty());
+        assert_eq!(seen.unhandled(&[1], |_| true), [1]);
+    }
+
+    #[test]
+    fn unlisted_windows_are_remembered() {
+        let mut seen = SeenWindows::default();
+        assert!(seen.unhandled(&[1], |_| true).is_empty());
+
+        // A window that is hidden for a moment still exists
+        assert!(seen.unhandled(&[], |_| true).is_empty());
+        assert!(seen.unhandled(&[1], |_| true).is_empty());
+    }
+}
@@ -0,0 +1,601 @@
+use core::fmt;
+use core::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::geometry::Rect;
+
+/// Named areas, and the expressions they stand for
+///
+/// Names are matched ignoring case, with hyphens and spaces treated alike (e.g. `Top-right quadrant`).
+pub const NAMED_AREAS: [(&str, &str); 13] = [
+    ("full", "100%x100%"),
+    ("whole monitor", "100%x100%"),
+    ("left half", "50%x100% left"),
+    ("right half", "50%x100% right"),
+    ("top half", "100%x50% top"),
+    ("bottom half", "100%x50% bottom"),
+    ("top left quadrant", "50%x50% top left"),
+    ("top right quadrant", "50%x50% top right"),
+    ("bottom left quadrant", "50%x50% bottom left"),
+    ("bottom right quadrant", "50%x50% bottom right"),
+    ("left third", "33.33%x100% left"),
+    ("center third", "33.34%x100% centered"),
+    ("right third", "33.33%x100% right"),
+];
+
+/// The presets the settings start with, by name and expression
+const DEFAULT_PRESETS: [(&str, &str); 8] = [
+    ("1280x720 centered", "1280x720 centered"),
+    ("1920x1080 centered", "1920x1080 centered"),
+    ("Left half", "left half"),
+    ("Right half", "right half"),
+    ("Top-left quadrant", "top-left quadrant"),
+    ("Top-right quadrant", "top-right quadrant"),
+    ("Bottom-left quadrant", "bottom-left quadrant"),
+    ("Bottom-right quadrant", "bottom-right quadrant"),
+];
+
+/// Largest length in pixels, either way, which keeps the resolved rectangles far from overflowing
+const MAX_PIXELS: i32 = 100_000;
+
+/// Largest share of a side, either way, in hundredths of a percent (1000%)
+const MAX_PERCENT: i32 = 100_000;
+
+/// A length along one side of an area
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Length {
+    /// A number of pixels
+    Pixels(i32),
+    /// A share of the side, in hundredths of a percent (e.g. `5000` for 50%)
+    Percent(i32),
+}
+
+impl Length {
+    /// Function to convert the length into pixels
+    /// # Arguments
+    /// * `side` - The length of the side of the area, in pixels
+    /// # Returns
+    /// * The length in pixels, where percentages are rounded to the nearest pixel
+    fn resolve(self, side: i32) -> i32 {
+        match self {
+            Self::Pixels(pixels) => pixels,
+            Self::Percent(hundredths) => {
+                let pixels = (i64::from(side) * i64::from(hundredths) + 5000).div_euclid(10000);
+                i32::try_from(pixels).unwrap_or(if pixels < 0 { i32::MIN } else { i32::MAX })
+            }
+        }
+    }
+
+    const fn is_positive(self) -> bool {
+        match self {
+            Self::Pixels(value) | Self::Percent(value) => value > 0,
+        }
+    }
+}
+
+impl FromStr for Length {
+    type Err = String;
+
+    /// Function to parse a length, as pixels (e.g. `1280` or `-10`) or a percentage with up to two decimals (e.g. `50%`)
+    ///
+    /// Lengths are limited to [`MAX_PIXELS`] and [`MAX_PERCENT`], as a rectangle that large is never meant.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            format!("invalid length '{s}', expected pixels (e.g. 1280) or a percentage (e.g. 50%)")
+        };
+        let out_of_range = || {
+            format!(
+                "length '{s}' is out of range, expected at most {MAX_PIXELS} pixels or {}%",
+                MAX_PERCENT / 100
+            )
+        };
+
+        let Some(percent) = s.strip_suffix('%') else {
+            let pixels: i64 = s.parse().ok().ok_or_else(invalid)?;
+            return i32::try_from(pixels)
+                .ok()
+                .filter(|pixels| (-MAX_PIXELS..=MAX_PIXELS).contains(pixels))
+                .map(Self::Pixels)
+                .ok_or_else(out_of_range);
+        };
+
+        let (negative, digits) = percent
+            .strip_prefix('-')
+            .map_or((false, percent), |digits| (true, digits));
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        if whole.is_empty()
+            || fraction.len() > 2
+            || !whole
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+
+        // Pad the decimals to hundredths (e.g. `12.5` is 1250 hundredths)
+        let hundredths = whole
+            .parse::<i32>()
+            .ok()
+            .and_then(|whole| whole.checked_mul(100))
+            .and_then(|whole| whole.checked_add(format!("{fraction:0<2}").parse().ok()?))
+            .ok_or_else(out_of_range)?;
+        if hundredths > MAX_PERCENT {
+            return Err(out_of_range());
+        }
+
+        Ok(Self::Percent(if negative {
+            -hundredths
+        } else {
+            hundredths
+        }))
+    }
+}
+
+/// Where a rectangle is placed along one side of an area
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Align {
+    /// At the left or top edge
+    Start,
+    /// In the middle
+    Center,
+    /// At the right or bottom edge
+    End,
+}
+
+impl Align {
+    /// Function to compute where a rectangle starts along one side of an area
+    /// # Arguments
+    /// * `start` - The coordinate of the left or top edge of the area
+    /// * `side` - The length of the side of the area
+    /// * `length` - The length of the rectangle along the side
+    const fn place(self, start: i32, side: i32, length: i32) -> i32 {
+        match self {
+            Self::Start => start,
+            Self::Center => start + (side - length) / 2,
+            Self::End => start + side - length,
+        }
+    }
+}
+
+/// Where a rectangle is placed in an area
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Position {
+    /// Against edges of the area, or in its middle
+    Aligned { horizontal: Align, vertical: Align },
+    /// At an offset from the top-left corner of the area
+    At { x: Length, y: Length },
+}
+
+/// Alignments that can follow the size of a rectangle, with the horizontal and vertical alignment they stand for
+const ALIGNMENTS: [(&str, Align, Align); 11] = [
+    ("", Align::Center, Align::Center),
+    ("centered", Align::Center, Align::Center),
+    ("center", Align::Center, Align::Center),
+    ("top left", Align::Start, Align::Start),
+    ("top", Align::Center, Align::Start),
+    ("top right", Align::End, Align::Start),
+    ("left", Align::Start, Align::Center),
+    ("right", Align::End, Align::Center),
+    ("bottom left", Align::Start, Align::End),
+    ("bottom", Align::Center, Align::End),
+    ("bottom right", Align::End, Align::End),
+];
+
+/// A rectangle relative to an area such as a monitor, as typed by the user
+///
+/// An expression is a size followed by a position, where lengths are pixels or percentages of the area:
+/// - `1280x720` or `1280x720 centered` centers the rectangle
+/// - `50%x100% left` or `640x480 bottom right` puts it against edges of the area
+/// - `1280x720 at 100,50` or `50%x50% at 50%,0` puts its top-left corner at an offset from the area's
+///
+/// An expression can also be one of the [`NAMED_AREAS`] (e.g. `left half` or `top-right quadrant`).
+/// In the settings file, an expression is stored as the text it was parsed from.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct RectExpr {
+    text: String,
+    width: Length,
+    height: Length,
+    position: Position,
+}
+
+impl RectExpr {
+    /// Function to compute the rectangle the expression stands for
+    /// # Arguments
+    /// * `area` - The area, usually a monitor, in virtual-desktop coordinates
+    /// # Returns
+    /// * The rectangle, in virtual-desktop coordinates, which may stick out of the area
+    pub fn resolve(&self, area: Rect) -> Rect {
+        let width = self.width.resolve(area.width());
+        let height = self.height.resolve(area.height());
+
+        let (left, top) = match self.position {
+            Position::Aligned {
+                horizontal,
+                vertical,
+            } => (
+                horizontal.place(area.left, area.width(), width),
+                vertical.place(area.top, area.height(), height),
+            ),
+            Position::At { x, y } => (
+                area.left + x.resolve(area.width()),
+                area.top + y.resolve(area.height()),
+            ),
+        };
+
+        Rect::new(left, top, left + width, top + height)
+    }
+}
+
+impl FromStr for RectExpr {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let text = s.trim();
+        let words = normalize_words(text);
+        let expression = NAMED_AREAS
+            .iter()
+            .find(|(name, _)| *name == words)
+            .map_or(text, |&(_, expression)| expression);
+
+        let (width, height, position) = parse_expression(&expression.to_lowercase())
+            .map_err(|e| format!("Invalid rectangle '{text}', {e}"))?;
+
+        Ok(Self {
+            text: text.to_owned(),
+            width,
+            height,
+            position,
+        })
+    }
+}
+
+impl TryFrom<String> for RectExpr {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+}
+
+impl From<RectExpr> for String {
+    fn from(expression: RectExpr) -> Self {
+        expression.text
+    }
+}
+
+impl fmt::Display for RectExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// Function to parse a rectangle expression other than a named area
+/// # Arguments
+/// * `expression` - The lowercase expression, such as `1280x720 centered`
+/// # Returns
+/// * The width, height and position of the rectangle, or an error message describing what was expected
+fn parse_expression(expression: &str) -> Result<(Length, Length, Position), String> {
+    let (size, position) = expression
+        .split_once(char::is_whitespace)
+        .unwrap_or((expression, ""));
+    let (width, height) = size.split_once('x').ok_or_else(|| {
+        "expected a size such as 1280x720 or 50%x100%, or a named area such as left half".to_owned()
+    })?;
+    let width: Length = width.parse()?;
+    let height: Length = height.parse()?;
+    if !width.is_positive() || !height.is_positive() {
+        return Err("the width and height must be positive".to_owned());
+    }
+
+    Ok((width, height, parse_position(position.trim())?))
+}
+
+/// Function to parse the position following the size in a rectangle expression
+/// # Arguments
+/// * `text` - The lowercase position, such as `top right` or `at 100,50`, or empty to center the rectangle
+/// # Returns
+/// * The position, or an error message describing what was expected
+fn parse_position(text: &str) -> Result<Position, String> {
+    if let Some(offset) = text
+        .strip_prefix("at")
+        .filter(|offset| offset.starts_with(char::is_whitespace))
+    {
+        let (x, y) = offset
+            .split_once(',')
+            .ok_or_else(|| "expected an offset such as 'at 100,50'".to_owned())?;
+        return Ok(Position::At {
+            x: x.trim().parse()?,
+            y: y.trim().parse()?,
+        });
+    }
+
+    let words = normalize_words(text);
+    ALIGNMENTS
+        .iter()
+        .find(|(name, _, _)| *name == words)
+        .map(|&(_, horizontal, vertical)| Position::Aligned {
+            horizontal,
+            vertical,
+        })
+        .ok_or_else(|| {
+            format!("unknown position '{text}', expected 'centered', an edge or corner such as 'top right', or 'at X,Y'")
+        })
+}
+
+/// Function to put words in a form where names can be compared
+/// # Returns
+/// * The lowercase words, with hyphens turned into spaces and single spaces between words
+fn normalize_words(text: &str) -> String {
+    text.to_lowercase()
+        .replace('-', " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A named rectangle expression, listed in the preset picker
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Preset {
+    /// The name of the preset, shown in the preset picker
+    pub name: String,
+    /// The rectangle the window is placed at, relative to its monitor
+    pub rect: RectExpr,
+}
+
+/// Function to get the presets the settings start with
+pub fn default_presets() -> Vec<Preset> {
+    DEFAULT_PRESETS
+        .iter()
+        .filter_map(|&(name, rect)| {
+            rect.parse().ok().map(|rect| Preset {
+                name: name.to_owned(),
+                rect,
+            })
+        })
+        .collect()
+}
+
+/// Function to find a preset by name
+/// # Arguments
+/// * `presets` - The presets from the settings
+/// * `name` - The name of the preset, compared case-insensitively
+/// # Returns
+/// * The preset, or `None` if no preset has that name
+pub fn find_preset<'a>(presets: &'a [Preset], name: &str) -> Option<&'a Preset> {
+    presets
+        .iter()
+        .find(|preset| preset.name.to_lowercase() == name.to_lowercase())
+}
+
+/// Function to get the rectangle expression given on the command line
+/// # Arguments
+/// * `presets` - The presets from the settings
+/// * `text` - The name of a preset, or a rectangle expression
+/// # Returns
+/// * The expression of the preset with that name, or the parsed expression, or an error message
+pub fn preset_or_expression(presets: &[Preset], text: &str) -> Result<RectExpr, String> {
+    find_preset(presets, text).map_or_else(|| text.parse(), |preset| Ok(preset.rect.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONITOR: Rect = Rect::new(0, 0, 1920, 1080);
+
+    fn resolve(expression: &str, area: Rect) -> Rect {
+        expression.parse::<RectExpr>().unwrap().resolve(area)
+    }
+
+    #[test]
+    fn lengths_are_parsed_as_pixels_or_percentages() {
+        assert_eq!("1280".parse(), Ok(Length::Pixels(1280)));
+        assert_eq!("-10".parse(), Ok(Length::Pixels(-10)));
+        assert_eq!("50%".parse(), Ok(Length::Percent(5000)));
+        assert_eq!("12.5%".parse(), Ok(Length::Percent(1250)));
+        assert_eq!("33.33%".parse(), Ok(Length::Percent(3333)));
+        assert_eq!("-5%".parse(), Ok(Length::Percent(-500)));
+    }
+
+    #[test]
+    fn malformed_lengths_are_rejected() {
+        for text in ["", "abc", "12px", "%", ".5%", "1.234%", "1e3%", "--5%"] {
+            assert!(text.parse::<Length>().is_err(), "'{text}' was accepted");
+        }
+    }
+
+    #[test]
+    fn lengths_out_of_range_are_rejected() {
+        assert_eq!("100000".parse(), Ok(Length::Pixels(100_000)));
+        assert_eq!("-100000".parse(), Ok(Length::Pixels(-100_000)));
+        assert_eq!("1000%".parse(), Ok(Length::Percent(100_000)));
+        for text in [
+            "100001",
+            "-100001",
+            "2147483647",
+            "-2147483648",
+            "99999999999",
+            "1000.01%",
+            "-1001%",
+            "99999999999%",
+        ] {
+            assert!(
+                text.parse::<Length>().unwrap_err().contains("out of range"),
+                "'{text}' was not rejected as out of range"
+            );
+        }
+    }
+
+    #[test]
+    fn huge_offsets_are_rejected_instead_of_overflowing() {
+        for text in [
+            "1280x720 at 2147483647,0",
+            "1280x720 at 0,-2147483648",
+            "2147483647x720",
+            "1280x720 at 99999999%,0",
+        ] {
+            assert!(text.parse::<RectExpr>().is_err(), "'{text}' was accepted");
+        }
+
+        let far = Rect::new(-100_000, -100_000, 100_000, 100_000);
+        assert_eq!(
+            resolve("1000%x1000% at 1000%,1000%", far),
+            Rect::new(1_900_000, 1_900_000, 3_900_000, 3_900_000)
+        );
+    }
+
+    #[test]
+    fn percentages_are_rounded_to_the_nearest_pixel() {
+        assert_eq!(Length::Percent(3333).resolve(1920), 640);
+        assert_eq!(Length::Percent(3334).resolve(1920), 640);
+        assert_eq!(Length::Percent(5000).resolve(1081), 541);
+        assert_eq!(Length::Percent(-500).resolve(1920), -96);
+        assert_eq!(Length::Pixels(1280).resolve(1920), 1280);
+    }
+
+    #[test]
+    fn sizes_are_centered_by_default() {
+        let centered = Rect::new(320, 180, 1600, 900);
+        assert_eq!(resolve("1280x720", MONITOR), centered);
+        assert_eq!(resolve("1280x720 centered", MONITOR), centered);
+        assert_eq!(resolve("1280X720 Center", MONITOR), centered);
+    }
+
+    #[test]
+    fn sizes_are_placed_against_edges_and_corners() {
+        for (position, expected) in [
+            ("top left", Rect::new(0, 0, 1280, 720)),
+            ("top", Rect::new(320, 0, 1600, 720)),
+            ("top right", Rect::new(640, 0, 1920, 720)),
+            ("left", Rect::new(0, 180, 1280, 900)),
+            ("right", Rect::new(640, 180, 1920, 900)),
+            ("bottom-left", Rect::new(0, 360, 1280, 1080)),
+            ("bottom", Rect::new(320, 360, 1600, 1080)),
+            ("Bottom  Right", Rect::new(640, 360, 1920, 1080)),
+        ] {
+            assert_eq!(
+                resolve(&format!("1280x720 {position}"), MONITOR),
+                expected,
+                "{position}"
+            );
+        }
+    }
+
+    #[test]
+    fn offsets_are_relative_to_the_area() {
+        let area = Rect::new(1920, -200, 3840, 880);
+        assert_eq!(
+            resolve("800x600 at 100,50", area),
+            Rect::new(2020, -150, 2820, 450)
+        );
+        assert_eq!(
+            resolve("800x600 at -10, -20", area),
+            Rect::new(1910, -220, 2710, 380)
+        );
+        assert_eq!(
+            resolve("50%x50% at 25%,25%", area),
+            Rect::new(2400, 70, 3360, 610)
+        );
+    }
+
+    #[test]
+    fn named_areas_are_resolved_on_the_monitor() {
+        let area = Rect::new(-1920, 0, 0, 1080);
+        assert_eq!(resolve("full", area), area);
+        assert_eq!(resolve("Whole monitor", area), area);
+        assert_eq!(resolve("left half", area), Rect::new(-1920, 0, -960, 1080));
+        assert_eq!(resolve("Right-Half", area), Rect::new(-960, 0, 0, 1080));
+        assert_eq!(resolve("bottom half", area), Rect::new(-1920, 540, 0, 1080));
+        assert_eq!(
+            resolve("top-right quadrant", area),
+            Rect::new(-960, 0, 0, 540)
+        );
+    }
+
+    #[test]
+    fn thirds_cover_the_monitor_without_gaps() {
+        let left = resolve("left third", MONITOR);
+        let center = resolve("center third", MONITOR);
+        let right = resolve("right third", MONITOR);
+
+        assert_eq!(left, Rect::new(0, 0, 640, 1080));
+        assert_eq!(center, Rect::new(640, 0, 1280, 1080));
+        assert_eq!(right, Rect::new(1280, 0, 1920, 1080));
+    }
+
+    #[test]
+    fn invalid_expressions_are_rejected() {
+        for text in [
+            "",
+            "1280",
+            "1280x",
+            "x720",
+            "0x720",
+            "1280x-720",
+            "0%x50%",
+            "1280x720 middle",
+            "1280x720 at 100",
+            "1280x720 at x,50",
+            "1280x720 atop",
+            "left quarter",
+        ] {
+            let error = text.parse::<RectExpr>().unwrap_err();
+            assert!(error.starts_with("Invalid rectangle"), "{text}: {error}");
+        }
+    }
+
+    #[test]
+    fn expressions_keep_their_text() {
+        let expression: RectExpr = "  Left Half ".parse().unwrap();
+        assert_eq!(expression.to_string(), "Left Half");
+        assert_eq!(String::from(expression.clone()), "Left Half");
+
+        let json = serde_json::to_string(&expression).unwrap();
+        assert_eq!(json, "\"Left Half\"");
+        assert_eq!(serde_json::from_str::<RectExpr>(&json).unwrap(), expression);
+        assert!(serde_json::from_str::<RectExpr>("\"1280x720 at 2147483647,0\"").is_err());
+    }
+
+    #[test]
+    fn default_presets_are_valid() {
+        let presets = default_presets();
+        assert_eq!(presets.len(), DEFAULT_PRESETS.len());
+        assert_eq!(
+            find_preset(&presets, "1280x720 centered")
+                .unwrap()
+                .rect
+                .resolve(MONITOR),
+            Rect::new(320, 180, 1600, 900)
+        );
+    }
+
+    #[test]
+    fn presets_are_found_by_name_before_parsing_expressions() {
+        let presets = vec![Preset {
+            name: "Stream".to_owned(),
+            rect: "1280x720 top left".parse().unwrap(),
+        }];
+
+        assert_eq!(
+            find_preset(&presets, "stream").map(|preset| &preset.name),
+            Some(&"Stream".to_owned())
+        );
+        assert!(find_preset(&presets, "Streams").is_none());
+        assert_eq!(
+            preset_or_expression(&presets, "STREAM")
+                .unwrap()
+                .resolve(MONITOR),
+            Rect::new(0, 0, 1280, 720)
+        );
+        assert_eq!(
+            preset_or_expression(&presets, "640x480 bottom right")
+                .unwrap()
+                .resolve(MONITOR),
+            Rect::new(1280, 600, 1920, 1080)
+        );
+        assert!(preset_or_expression(&presets, "Missing").is_err());
+    }
+}
@@ -0,0 +1,125 @@
+use core::fmt;
+use core::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::geometry::{Rect, fit_rect, fullscreen_rect, native_rect};
+use crate::rect_expr::RectExpr;
+
+/// How a fullscreenized window is sized on its monitor
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScaleMode {
+    /// Stretch the window over the whole monitor
+    #[default]
+    Stretch,
+    /// Scale the window to the largest size keeping its aspect ratio, centered on the monitor
+    Fit,
+    /// Keep the size of the window, centered on the monitor
+    Native,
+    /// Scale the window by the largest whole number that fits on the monitor, centered on the monitor
+    Integer,
+}
+
+impl ScaleMode {
+    /// The modes, in the order they are shown in the settings window
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub const ALL: [Self; 4] = [Self::Stretch, Self::Fit, Self::Native, Self::Integer];
+
+    /// Function to get the name of the mode shown in the scaling pickers
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Stretch => "Stretch",
+            Self::Fit => "Fit",
+            Self::Native => "Native size",
+            Self::Integer => "Integer scale",
+        }
+    }
+
+    /// Function to check whether the window may leave parts of the monitor uncovered
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub const fn leaves_bars(self) -> bool {
+        match self {
+            Self::Stretch => false,
+            Self::Fit | Self::Native | Self::Integer => true,
+        }
+    }
+
+    /// Function to compute the rectangle the client area of a window should cover
+    /// # Arguments
+    /// * `monitor` - The full area of the target monitor, in virtual-desktop coordinates
+    /// * `client_size` - The width and height of the client area of the window before it is fullscreenized
+    /// # Returns
+    /// * The rectangle, in virtual-desktop coordinates
+    pub fn target_rect(self, monitor: Rect, client_size: (i32, i32)) -> Rect {
+        match self {
+            Self::Stretch => fullscreen_rect(monitor),
+            Self::Fit => fit_rect(monitor, client_size.0, client_size.1),
+            Self::Native => native_rect(monitor, client_size.0, client_size.1, false),
+            Self::Integer => native_rect(monitor, client_size.0, client_size.1, true),
+        }
+    }
+}
+
+impl fmt::Display for ScaleMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Stretch => "stretch",
+            Self::Fit => "fit",
+            Self::Native => "native",
+            Self::Integer => "integer",
+        })
+    }
+}
+
+impl FromStr for ScaleMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "stretch" => Ok(Self::Stretch),
+            "fit" => Ok(Self::Fit),
+            "native" => Ok(Self::Native),
+            "integer" => Ok(Self::Integer),
+            _ => Err(format!(
+                "Unknown scaling mode '{s}', expected 'stretch', 'fit', 'native' or 'integer'"
+            )),
+        }
+    }
+}
+
+/// How a fullscreenized window is sized, either on its whole monitor or at a rectangle of it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Sizing {
+    /// Size the window on the monitor with a scaling mode
+    Scale(ScaleMode),
+    /// Place the window at a rectangle relative to the monitor, such as a preset
+    Rect(RectExpr),
+}
+
+impl Sizing {
+    /// Function to check whether the window may leave parts of the monitor uncovered that should be hidden
+    ///
+    /// A rectangle leaves the rest of the monitor visible on purpose, for example to show other windows next to it.
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub const fn leaves_bars(&self) -> bool {
+        match self {
+            Self::Scale(scale) => scale.leaves_bars(),
+            Self::Rect(_) => false,
+        }
+    }
+
+    /// Function to compute the rectangle the client area of a window should cover
+    /// # Arguments
+    /// * `monitor` - The full area of the target monitor, in virtual-desktop coordinates
+    /// * `client_size` - The width and height of the client area of the window before it is fullscreenized
+    /// # Returns
+    /// * The rectangle, in virtual-desktop coordinates
+    pub fn target_rect(&self, monitor: Rect, client_size: (i32, i32)) -> Rect {
+        match self {
+            Self::Scale(scale) => scale.target_rect(monitor, client_size),
+            Self::Rect(rect) => rect.resolve(monitor),
+        }
+    }
+}
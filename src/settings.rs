@@ -0,0 +1,571 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use toml::{Table, Value};
+
+use crate::exclusions::ExclusionList;
+use crate::game_detection::GameDetection;
+use crate::hotkeys::{self, Hotkey, MOD_ALT, MOD_CONTROL};
+use crate::list_columns::ListColumns;
+use crate::monitors::{MonitorChoice, MonitorSpan};
+use crate::paths;
+use crate::profiles::Profiles;
+use crate::rect_expr::{self, Preset};
+use crate::scaling::ScaleMode;
+use crate::tray_menu::RecentApps;
+
+/// Name of the settings file in the config directory
+pub const SETTINGS_FILE_NAME: &str = "settings.toml";
+
+/// Name of the file the exclusions were kept in before they moved into the settings file
+const LEGACY_EXCLUSIONS_FILE_NAME: &str = "exclusions.txt";
+
+/// Version of the settings file format
+///
+/// Adding a setting does not need a new version, as missing settings take their default value.
+/// Renaming, moving or changing the meaning of a setting does, along with a step in [`MIGRATIONS`].
+const SETTINGS_VERSION: u32 = 2;
+
+/// A step upgrading the settings file format, with the version it upgrades to the next one
+type Migration = (u32, fn(&mut Table));
+
+/// Steps upgrading the settings file format, in order
+///
+/// A version whose files are read unchanged by the next version has no step, which is every version so far:
+/// version 0 is a file without a version, which uses the same layout as version 1,
+/// and version 2 only added the choices remembered for each application, which version 1 refuses instead of dropping.
+const MIGRATIONS: [Migration; 0] = [];
+
+/// The color theme of the window
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    /// Follow the app theme chosen in the Windows settings
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    /// The themes, in the order they are shown in the settings window
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub const ALL: [Self; 3] = [Self::System, Self::Light, Self::Dark];
+
+    /// Function to get the name of the theme shown in the settings window
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::System => "Same as Windows",
+            Self::Light => "Light",
+            Self::Dark => "Dark",
+        }
+    }
+}
+
+/// The choices last made in the window for an application
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppChoices {
+    /// The monitor the application's windows were last sent to
+    pub monitor: MonitorChoice,
+    /// The scaling mode last chosen for the application, or `None` to use the default mode
+    pub scale: Option<ScaleMode>,
+}
+
+/// The user's settings, kept on disk between launches
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Path of the settings file, or `None` to only keep the settings in memory
+    #[serde(skip)]
+    path: Option<PathBuf>,
+
+    /// Version of the file format, which is always the current version once the settings are read
+    version: u32,
+    /// The color theme of the window
+    pub theme: Theme,
+    /// Whether to show the windows' icons in the window list
+    pub show_icons: bool,
+    /// The columns of the window list, their widths and the order the windows are sorted in
+    pub list_columns: ListColumns,
+    /// Whether the "stay on top" checkbox is checked
+    pub topmost: bool,
+    /// Whether to show an icon in the notification area, where the window is hidden when minimized
+    pub tray_icon: bool,
+    /// Whether closing the window hides it in the notification area instead of exiting
+    pub close_to_tray: bool,
+    /// Whether to put fullscreenized windows back into their fullscreen state when their application reverts it
+    pub watchdog: bool,
+    /// Whether to update the window list as soon as windows are opened, closed, shown, hidden or renamed
+    pub live_updates: bool,
+    /// How likely games are recognized and whether they are listed first
+    pub game_detection: GameDetection,
+    /// The monitor selected in the monitor picker for applications that were not sent to a monitor before
+    pub default_monitor: MonitorChoice,
+    /// The choices last made for each application, by executable name
+    apps: BTreeMap<String, AppChoices>,
+    /// The groups of monitors windows can be spanned across, listed after the monitors in the monitor picker
+    pub spans: Vec<MonitorSpan>,
+    /// How fullscreenized windows are sized on their monitor, for applications that were not fullscreenized before
+    pub scale_mode: ScaleMode,
+    /// Whether to cover the parts of the monitor a window that is not stretched leaves uncovered with a black backdrop
+    pub backdrop: bool,
+    /// Whether to fill the work area of the monitor, leaving the taskbar and docked app bars visible
+    pub work_area: bool,
+    /// The rectangles windows can be placed at instead of being scaled, listed in the preset picker
+    pub presets: Vec<Preset>,
+    /// The name of the preset selected in the preset picker, or `None` to scale windows
+    pub preset: Option<String>,
+    /// The exclusions deciding which windows are hidden from the window list
+    pub exclusions: ExclusionList,
+    /// The global hotkey fullscreenizing the foreground window, or `None` to disable it
+    #[serde(
+        serialize_with = "hotkeys::serialize_optional",
+        deserialize_with = "hotkeys::deserialize_optional"
+    )]
+    pub fullscreenize_hotkey: Option<Hotkey>,
+    /// The global hotkey restoring the foreground window, or `None` to disable it
+    #[serde(
+        serialize_with = "hotkeys::serialize_optional",
+        deserialize_with = "hotkeys::deserialize_optional"
+    )]
+    pub restore_hotkey: Option<Hotkey>,
+    /// The profiles applied to the windows of specific applications
+    pub profiles: Profiles,
+    /// The applications fullscreenized last, listed in the tray menu
+    pub recent_apps: RecentApps,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            path: None,
+            version: SETTINGS_VERSION,
+            theme: Theme::default(),
+            show_icons: true,
+            list_columns: ListColumns::default(),
+            topmost: false,
+            tray_icon: false,
+            close_to_tray: false,
+            watchdog: false,
+            live_updates: true,
+            game_detection: GameDetection::default(),
+            default_monitor: MonitorChoice::Nearest,
+            apps: BTreeMap::new(),
+            spans: vec![MonitorSpan::all_monitors()],
+            scale_mode: ScaleMode::default(),
+            backdrop: true,
+            work_area: false,
+            presets: rect_expr::default_presets(),
+            preset: None,
+            exclusions: ExclusionList::default(),
+            // Ctrl+Alt+F and Ctrl+Alt+R
+            fullscreenize_hotkey: Some(Hotkey::new(MOD_CONTROL | MOD_ALT, 0x46)),
+            restore_hotkey: Some(Hotkey::new(MOD_CONTROL | MOD_ALT, 0x52)),
+            profiles: Profiles::default(),
+            recent_apps: RecentApps::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// Function to load the settings from disk
+    /// # Arguments
+    /// * `path` - Path of the settings file, or `None` to only keep the settings in memory
+    /// # Returns
+    /// * The settings, which are the defaults if the file does not exist yet, or an error message
+    ///
+    /// If the file does not exist yet but exclusions were saved by an older version of Fullscreenizer,
+    /// they are imported and the new settings file is written.
+    pub fn load(path: Option<PathBuf>) -> Result<Self, String> {
+        let settings = match path.as_ref().map(fs::read_to_string) {
+            Some(Ok(text)) => deserialize(&text)?,
+            Some(Err(e)) if e.kind() != ErrorKind::NotFound => {
+                return Err(format!("Failed to read the settings: {e}"));
+            }
+            _ => {
+                let mut settings = Self {
+                    path,
+                    ..Self::default()
+                };
+
+                if let Some(exclusions) = settings.path.as_deref().and_then(load_legacy_exclusions)
+                {
+                    settings.exclusions = exclusions;
+                    settings
+                        .save()
+                        .map_err(|e| eprintln!("Failed to save the imported exclusions: {e}"))
+                        .ok();
+                }
+
+                return Ok(settings);
+            }
+        };
+
+        Ok(Self { path, ..settings })
+    }
+
+    /// Function to get the choices last made for an application
+    /// # Arguments
+    /// * `app` - The executable name of the application, compared case-insensitively
+    /// # Returns
+    /// * The choices, or `None` if the application was never fullscreenized from the window
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn app_choices(&self, app: &str) -> Option<&AppChoices> {
+        self.apps
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(app))
+            .map(|(_, choices)| choices)
+    }
+
+    /// Function to remember the choices made for an application
+    /// # Arguments
+    /// * `app` - The executable name of the application
+    /// * `choices` - The chosen monitor and scaling mode
+    /// # Returns
+    /// * `true` if the settings changed
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn remember_choices(&mut self, app: &str, choices: AppChoices) -> bool {
+        if self.app_choices(app) == Some(&choices) {
+            return false;
+        }
+
+        *self.app_choices_mut(app) = choices;
+        true
+    }
+
+    /// Function to get the choices of an application to change them, adding the application if needed
+    #[cfg_attr(not(windows), allow(dead_code))]
+    fn app_choices_mut(&mut self, app: &str) -> &mut AppChoices {
+        let name = self
+            .apps
+            .keys()
+            .find(|name| name.eq_ignore_ascii_case(app))
+            .cloned()
+            .unwrap_or_else(|| app.to_lowercase());
+
+        self.apps.entry(name).or_default()
+    }
+
+    /// Function to write the settings to disk
+    /// # Returns
+    /// * An error message if the settings could not be written
+    pub fn save(&self) -> Result<(), String> {
+        let Some(path) = self.path.as_ref() else {
+            return Ok(());
+        };
+
+        paths::write_atomic(path, &serialize(self)?)
+    }
+}
+
+/// Function to get the path of the settings file
+/// # Returns
+/// * The path of the settings file in the config directory, or `None` if the config directory could not be determined
+pub fn default_path() -> Option<PathBuf> {
+    paths::config_dir().map(|dir| dir.join(SETTINGS_FILE_NAME))
+}
+
+/// Function to convert the settings into the settings file format
+/// # Arguments
+/// * `settings` - The settings
+/// # Returns
+/// * The contents of the settings file, or an error message
+pub fn serialize(settings: &Settings) -> Result<String, String> {
+    toml::to_string_pretty(settings).map_err(|e| format!("Failed to serialize the settings: {e}"))
+}
+
+/// Function to read the settings from the settings file format
+/// # Arguments
+/// * `text` - The contents of the settings file
+/// # Returns
+/// * The settings, upgraded to the current version, or an error message if the settings are invalid
+///   or from a newer version
+///
+/// Missing settings take their default value, so an empty file is valid.
+pub fn deserialize(text: &str) -> Result<Settings, String> {
+    let mut table: Table =
+        toml::from_str(text).map_err(|e| format!("The settings are invalid: {e}"))?;
+
+    // Files written by hand may not have a version, they are read as the oldest format
+    let version = match table.get("version") {
+        None => 0,
+        Some(Value::Integer(version)) => u32::try_from(*version)
+            .map_err(|e| format!("The settings have an invalid version ({version}): {e}"))?,
+        Some(version) => return Err(format!("The settings have an invalid version ({version})")),
+    };
+    if version > SETTINGS_VERSION {
+        return Err(format!(
+            "The settings were written by a newer version of Fullscreenizer (version {version})"
+        ));
+    }
+
+    migrate(&mut table, version, &MIGRATIONS);
+
+    Value::Table(table)
+        .try_into()
+        .map_err(|e| format!("The settings are invalid: {e}"))
+}
+
+/// Function to upgrade settings read from an older version of the file format
+/// # Arguments
+/// * `table` - The settings, which are changed in place
+/// * `version` - The version the settings were written with
+/// * `steps` - The steps upgrading a version to the next, in order, which are [`MIGRATIONS`] outside of tests
+///
+/// Each step upgrades the settings by one version, so settings from any older version
+/// go through every later step in order.
+fn migrate(table: &mut Table, version: u32, steps: &[Migration]) {
+    for (_, step) in steps.iter().filter(|&&(from, _)| from >= version) {
+        step(table);
+    }
+
+    table.insert(
+        "version".to_owned(),
+        Value::Integer(SETTINGS_VERSION.into()),
+    );
+}
+
+/// Function to read the exclusions saved by older versions of Fullscreenizer
+/// # Arguments
+/// * `settings_path` - Path of the settings file, whose directory held the exclusions file
+/// # Returns
+/// * The exclusions, or `None` if there are none or they could not be read
+fn load_legacy_exclusions(settings_path: &Path) -> Option<ExclusionList> {
+    let path = settings_path.with_file_name(LEGACY_EXCLUSIONS_FILE_NAME);
+    let text = fs::read_to_string(&path).ok()?;
+
+    ExclusionList::parse(&text)
+        .map_err(|e| {
+            eprintln!(
+                "Failed to import the exclusions from {}: {e}",
+                path.display()
+            );
+        })
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    /// Function to get an empty directory only used by one test
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("fullscreenizer-{}-{name}", process::id()));
+        fs::remove_dir_all(&dir).ok();
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn files_without_a_version_are_upgraded() {
+        let settings = deserialize("theme = \"dark\"\ntopmost = true\n").unwrap();
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(settings.theme, Theme::Dark);
+        assert!(settings.topmost);
+
+        let text = serialize(&settings).unwrap();
+        assert!(text.starts_with(&format!("version = {SETTINGS_VERSION}\n")));
+    }
+
+    #[test]
+    fn missing_settings_take_their_default_value() {
+        let defaults = Settings::default();
+        let settings = deserialize("version = 1\nwatchdog = true\n").unwrap();
+        assert!(settings.watchdog);
+        assert_eq!(settings.show_icons, defaults.show_icons);
+        assert_eq!(settings.live_updates, defaults.live_updates);
+        assert_eq!(settings.spans, defaults.spans);
+        assert_eq!(settings.presets, defaults.presets);
+        assert_eq!(settings.fullscreenize_hotkey, defaults.fullscreenize_hotkey);
+        assert_eq!(settings.exclusions.to_text(), defaults.exclusions.to_text());
+
+        let empty = deserialize("").unwrap();
+        assert_eq!(serialize(&empty), serialize(&defaults));
+    }
+
+    #[test]
+    fn rejects_newer_and_invalid_versions() {
+        let newer = format!("version = {}\n", SETTINGS_VERSION + 1);
+        let error = deserialize(&newer).unwrap_err();
+        assert!(error.contains("newer version"), "{error}");
+
+        assert!(deserialize("version = -1\n").is_err());
+        assert!(deserialize("version = \"2\"\n").is_err());
+        assert!(deserialize("theme = \"blue\"\n").is_err());
+    }
+
+    #[test]
+    fn migrations_run_from_the_version_of_the_file() {
+        let steps: [Migration; 3] = [
+            (0, |table| {
+                table.insert("first".to_owned(), Value::Boolean(true));
+            }),
+            (1, |table| {
+                table.insert("second".to_owned(), Value::Boolean(true));
+            }),
+            (3, |table| {
+                table.insert("fourth".to_owned(), Value::Boolean(true));
+            }),
+        ];
+
+        let mut table = Table::new();
+        migrate(&mut table, 1, &steps);
+        assert!(!table.contains_key("first"));
+        assert!(table.contains_key("second"));
+        assert!(table.contains_key("fourth"));
+        assert_eq!(
+            table.get("version"),
+            Some(&Value::Integer(SETTINGS_VERSION.into()))
+        );
+    }
+
+    #[test]
+    fn version_1_files_are_read_unchanged_and_saved_as_the_current_version() {
+        let version_1 = "\
+version = 1
+theme = \"dark\"
+show_icons = false
+topmost = true
+watchdog = true
+default_monitor = \"DISPLAY2\"
+exclusions = [\"title exact Program Manager\", \"exe glob *launcher*.exe\"]
+
+[[profiles]]
+name = \"Game\"
+exe = \"game.exe\"
+";
+        let settings = deserialize(version_1).unwrap();
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(settings.theme, Theme::Dark);
+        assert!(!settings.show_icons);
+        assert!(settings.topmost);
+        assert!(settings.watchdog);
+        assert_eq!(
+            settings.default_monitor,
+            MonitorChoice::Device("DISPLAY2".to_owned())
+        );
+        assert_eq!(
+            settings.exclusions.to_text(),
+            "title exact Program Manager\nexe glob *launcher*.exe\n"
+        );
+        assert!(settings.app_choices("game.exe").is_none());
+
+        // Saving writes the same settings with the current version
+        let saved = serialize(&settings).unwrap();
+        assert!(saved.starts_with(&format!("version = {SETTINGS_VERSION}\n")));
+        let current =
+            version_1.replacen("version = 1", &format!("version = {SETTINGS_VERSION}"), 1);
+        assert_eq!(saved, serialize(&deserialize(&current).unwrap()).unwrap());
+        assert_eq!(serialize(&deserialize(&saved).unwrap()).unwrap(), saved);
+    }
+
+    #[test]
+    fn imports_the_legacy_exclusions_file() {
+        let dir = temp_dir("legacy-exclusions");
+        let path = dir.join(SETTINGS_FILE_NAME);
+        fs::write(
+            dir.join(LEGACY_EXCLUSIONS_FILE_NAME),
+            "# Hidden windows\nexe exact Discord.exe\ntitle glob *Launcher*\n",
+        )
+        .unwrap();
+
+        let settings = Settings::load(Some(path.clone())).unwrap();
+        assert!(settings.exclusions.is_excluded(
+            "Discord",
+            "Chrome_WidgetWin_1",
+            Some("discord.exe")
+        ));
+        assert!(
+            settings
+                .exclusions
+                .is_excluded("Game Launcher", "Launcher", None)
+        );
+
+        // The imported exclusions are written to the new settings file, which is used from then on
+        let saved = fs::read_to_string(&path).unwrap();
+        assert!(saved.contains("exe exact Discord.exe"));
+        fs::remove_file(dir.join(LEGACY_EXCLUSIONS_FILE_NAME)).unwrap();
+        let reloaded = Settings::load(Some(path)).unwrap();
+        assert_eq!(reloaded.exclusions.to_text(), settings.exclusions.to_text());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn missing_files_give_the_defaults_without_writing() {
+        let dir = temp_dir("missing-settings");
+        let path = dir.join(SETTINGS_FILE_NAME);
+
+        let settings = Settings::load(Some(path.clone())).unwrap();
+        assert_eq!(serialize(&settings), serialize(&Settings::default()));
+        assert!(!path.exists());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    fn choices(monitor: MonitorChoice, scale: Option<ScaleMode>) -> AppChoices {
+        AppChoices { monitor, scale }
+    }
+
+    #[test]
+    fn choices_are_remembered_per_application() {
+        let mut settings = Settings::default();
+        let display2 = MonitorChoice::Device("\\\\.\\DISPLAY2".to_owned());
+        let game = choices(display2.clone(), Some(ScaleMode::Fit));
+
+        assert!(settings.remember_choices("Game.exe", game.clone()));
+        assert!(!settings.remember_choices("game.EXE", game.clone()));
+        assert!(settings.remember_choices("other.exe", AppChoices::default()));
+
+        assert_eq!(settings.app_choices("GAME.exe"), Some(&game));
+        assert_eq!(
+            settings.app_choices("other.exe"),
+            Some(&AppChoices::default())
+        );
+        assert_eq!(settings.app_choices("unknown.exe"), None);
+
+        // Choosing another scaling mode replaces the remembered one, under the name first used
+        let integer = choices(display2, Some(ScaleMode::Integer));
+        assert!(settings.remember_choices("GAME.EXE", integer.clone()));
+        assert_eq!(settings.app_choices("game.exe"), Some(&integer));
+        assert_eq!(settings.apps.len(), 2);
+    }
+
+    #[test]
+    fn choices_are_kept_in_the_settings_file() {
+        let mut settings = Settings::default();
+        let span = MonitorChoice::Span("All monitors".to_owned());
+        let racing = choices(span, Some(ScaleMode::Native));
+        settings.remember_choices("racing.exe", racing.clone());
+        settings.remember_choices("game.exe", choices(MonitorChoice::Nearest, None));
+
+        let text = serialize(&settings).unwrap();
+        assert!(text.contains("scale = \"native\""), "{text}");
+        let read = deserialize(&text).unwrap();
+        assert_eq!(read.app_choices("racing.exe"), Some(&racing));
+        // The nearest monitor and the default scaling mode are kept as an empty entry, not forgotten
+        assert_eq!(read.app_choices("game.exe"), Some(&AppChoices::default()));
+    }
+
+    #[test]
+    fn choices_without_a_scaling_mode_use_the_default() {
+        let settings = deserialize(
+            "version = 2\nscale_mode = \"integer\"\n[apps.\"game.exe\"]\nmonitor = 'DISPLAY2'\n",
+        )
+        .unwrap();
+        let choices = settings.app_choices("game.exe").unwrap();
+        assert_eq!(
+            choices.monitor,
+            MonitorChoice::Device("DISPLAY2".to_owned())
+        );
+        assert_eq!(choices.scale, None);
+        assert_eq!(settings.scale_mode, ScaleMode::Integer);
+    }
+}
@@ -0,0 +1,729 @@
+extern crate alloc;
+
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use winsafe::co::{BST, CBS, SS, TDCBF};
+use winsafe::gui::{
+    Button, ButtonOpts, CheckBox, CheckBoxOpts, ComboBox, ComboBoxOpts, Edit, EditOpts, Label,
+    LabelOpts, WindowModal, WindowModalOpts, dpi, dpi_x,
+};
+use winsafe::prelude::{
+    GuiEventsButton as _, GuiEventsLabel as _, GuiEventsWindow as _, GuiParent, GuiWindow as _,
+};
+use winsafe::{HWND, IconRes};
+
+use crate::exclusions_dialog::ExclusionsDialog;
+use crate::hotkeys::{self, Hotkey};
+use crate::locks::lock;
+use crate::monitors::MonitorList;
+use crate::scaling::ScaleMode;
+use crate::settings::{Settings, Theme};
+use crate::theme;
+
+/// A modal window to change the settings
+///
+/// The settings are edited on a copy, which is only handed back if the user presses OK.
+#[derive(Clone)]
+pub struct SettingsDialog {
+    wnd: WindowModal,
+    theme_picker: ComboBox,
+    monitor_picker: ComboBox,
+    scale_picker: ComboBox,
+    icons_toggle: CheckBox,
+    icons_label: Label,
+    topmost_toggle: CheckBox,
+    topmost_label: Label,
+    tray_toggle: CheckBox,
+    tray_label: Label,
+    close_to_tray_toggle: CheckBox,
+    close_to_tray_label: Label,
+    watchdog_toggle: CheckBox,
+    watchdog_label: Label,
+    live_updates_toggle: CheckBox,
+    live_updates_label: Label,
+    games_first_toggle: CheckBox,
+    games_first_label: Label,
+    backdrop_toggle: CheckBox,
+    backdrop_label: Label,
+    work_area_toggle: CheckBox,
+    work_area_label: Label,
+    fullscreenize_hotkey_edit: Edit,
+    restore_hotkey_edit: Edit,
+    exclusions_btn: Button,
+    help_btn: Button,
+    ok_btn: Button,
+    cancel_btn: Button,
+
+    dark_mode: bool,
+    // The monitors shown in the monitor picker
+    monitors: Arc<MonitorList>,
+    // The settings being edited
+    settings: Arc<Mutex<Settings>>,
+    // Whether the user pressed OK
+    accepted: Arc<AtomicBool>,
+}
+
+impl SettingsDialog {
+    /// Function to create the dialog
+    /// # Arguments
+    /// * `settings` - The current settings
+    /// * `monitors` - The monitors that can be chosen as the default monitor
+    /// * `dark_mode` - Whether to use the dark theme
+    pub fn new(settings: &Settings, monitors: MonitorList, dark_mode: bool) -> Self {
+        let wnd = WindowModal::new(WindowModalOpts {
+            title: "Settings",
+            size: dpi(380, 498),
+            ..Default::default()
+        });
+        theme::paint_modal(&wnd, dark_mode);
+
+        Label::new(
+            &wnd,
+            LabelOpts {
+                text: "Theme:",
+                position: dpi(12, 16),
+                size: dpi(124, 20),
+                control_style: SS::LEFTNOWORDWRAP,
+                ..Default::default()
+            },
+        );
+
+        let theme_labels = Theme::ALL.map(Theme::label);
+        let theme_picker = ComboBox::new(
+            &wnd,
+            ComboBoxOpts {
+                position: dpi(140, 12),
+                width: dpi_x(228),
+                control_style: CBS::DROPDOWNLIST,
+                items: &theme_labels,
+                selected_item: Theme::ALL
+                    .iter()
+                    .position(|&theme| theme == settings.theme)
+                    .map(|index| index as u32),
+                ..Default::default()
+            },
+        );
+
+        Label::new(
+            &wnd,
+            LabelOpts {
+                text: "Default monitor:",
+                position: dpi(12, 48),
+                size: dpi(124, 20),
+                control_style: SS::LEFTNOWORDWRAP,
+                ..Default::default()
+            },
+        );
+
+        let monitor_labels = monitors.labels();
+        let monitor_labels: Vec<&str> = monitor_labels.iter().map(String::as_str).collect();
+        let monitor_picker = ComboBox::new(
+            &wnd,
+            ComboBoxOpts {
+                position: dpi(140, 44),
+                width: dpi_x(228),
+                control_style: CBS::DROPDOWNLIST,
+                items: &monitor_labels,
+                // A default monitor that is not connected is kept unless another monitor is chosen
+                selected_item: monitors
+                    .index_of(&settings.default_monitor)
+                    .map(|index| index as u32),
+                ..Default::default()
+            },
+        );
+
+        Label::new(
+            &wnd,
+            LabelOpts {
+                text: "Default scaling:",
+                position: dpi(12, 80),
+                size: dpi(124, 20),
+                control_style: SS::LEFTNOWORDWRAP,
+                ..Default::default()
+            },
+        );
+
+        let scale_labels = ScaleMode::ALL.map(ScaleMode::label);
+        let scale_picker = ComboBox::new(
+            &wnd,
+            ComboBoxOpts {
+                position: dpi(140, 76),
+                width: dpi_x(228),
+                control_style: CBS::DROPDOWNLIST,
+                items: &scale_labels,
+                selected_item: ScaleMode::ALL
+                    .iter()
+                    .position(|&scale| scale == settings.scale_mode)
+                    .map(|index| index as u32),
+                ..Default::default()
+            },
+        );
+
+        // Checkboxes with a separate label, as the text color of a checkbox cannot be changed in dark mode
+        let icons_toggle = CheckBox::new(
+            &wnd,
+            CheckBoxOpts {
+                position: dpi(12, 114),
+                size: dpi(20, 20),
+                check_state: if settings.show_icons {
+                    BST::CHECKED
+                } else {
+                    BST::UNCHECKED
+                },
+                ..Default::default()
+            },
+        );
+        let icons_label = Label::new(
+            &wnd,
+            LabelOpts {
+                text: "Show window icons in the list",
+                position: dpi(36, 114),
+                size: dpi(332, 20),
+                control_style: SS::LEFTNOWORDWRAP | SS::NOTIFY,
+                ..Default::default()
+            },
+        );
+
+        let topmost_toggle = CheckBox::new(
+            &wnd,
+            CheckBoxOpts {
+                position: dpi(12, 140),
+                size: dpi(20, 20),
+                check_state: if settings.topmost {
+                    BST::CHECKED
+                } else {
+                    BST::UNCHECKED
+                },
+                ..Default::default()
+            },
+        );
+        let topmost_label = Label::new(
+            &wnd,
+            LabelOpts {
+                text: "Apply \"stay on top\" flag by default",
+                position: dpi(36, 140),
+                size: dpi(332, 20),
+                control_style: SS::LEFTNOWORDWRAP | SS::NOTIFY,
+                ..Default::default()
+            },
+        );
+
+        let tray_toggle = CheckBox::new(
+            &wnd,
+            CheckBoxOpts {
+                position: dpi(12, 166),
+                size: dpi(20, 20),
+                check_state: if settings.tray_icon {
+                    BST::CHECKED
+                } else {
+                    BST::UNCHECKED
+                },
+                ..Default::default()
+            },
+        );
+        let tray_label = Label::new(
+            &wnd,
+            LabelOpts {
+                text: "Show a tray icon and minimize to the tray",
+                position: dpi(36, 166),
+                size: dpi(332, 20),
+                control_style: SS::LEFTNOWORDWRAP | SS::NOTIFY,
+                ..Default::default()
+            },
+        );
+
+        let close_to_tray_toggle = CheckBox::new(
+            &wnd,
+            CheckBoxOpts {
+                position: dpi(12, 192),
+                size: dpi(20, 20),
+                check_state: if settings.close_to_tray {
+                    BST::CHECKED
+                } else {
+                    BST::UNCHECKED
+                },
+                ..Default::default()
+            },
+        );
+        let close_to_tray_label = Label::new(
+            &wnd,
+            LabelOpts {
+                text: "Close to the tray instead of exiting",
+                position: dpi(36, 192),
+                size: dpi(332, 20),
+                control_style: SS::LEFTNOWORDWRAP | SS::NOTIFY,
+                ..Default::default()
+            },
+        );
+
+        let watchdog_toggle = CheckBox::new(
+            &wnd,
+            CheckBoxOpts {
+                position: dpi(12, 218),
+                size: dpi(20, 20),
+                check_state: if settings.watchdog {
+                    BST::CHECKED
+                } else {
+                    BST::UNCHECKED
+                },
+                ..Default::default()
+            },
+        );
+        let watchdog_label = Label::new(
+            &wnd,
+            LabelOpts {
+                text: "Fix windows whose game reverts the fullscreen",
+                position: dpi(36, 218),
+                size: dpi(332, 20),
+                control_style: SS::LEFTNOWORDWRAP | SS::NOTIFY,
+                ..Default::default()
+            },
+        );
+
+        let live_updates_toggle = CheckBox::new(
+            &wnd,
+            CheckBoxOpts {
+                position: dpi(12, 244),
+                size: dpi(20, 20),
+                check_state: if settings.live_updates {
+                    BST::CHECKED
+                } else {
+                    BST::UNCHECKED
+                },
+                ..Default::default()
+            },
+        );
+        let live_updates_label = Label::new(
+            &wnd,
+            LabelOpts {
+                text: "Update the window list as windows open and close",
+                position: dpi(36, 244),
+                size: dpi(332, 20),
+                control_style: SS::LEFTNOWORDWRAP | SS::NOTIFY,
+                ..Default::default()
+            },
+        );
+
+        let games_first_toggle = CheckBox::new(
+            &wnd,
+            CheckBoxOpts {
+                position: dpi(12, 270),
+                size: dpi(20, 20),
+                check_state: if settings.game_detection.games_first {
+                    BST::CHECKED
+                } else {
+                    BST::UNCHECKED
+                },
+                ..Default::default()
+            },
+        );
+        let games_first_label = Label::new(
+            &wnd,
+            LabelOpts {
+                text: "List likely games first",
+                position: dpi(36, 270),
+                size: dpi(332, 20),
+                control_style: SS::LEFTNOWORDWRAP | SS::NOTIFY,
+                ..Default::default()
+            },
+        );
+
+        let backdrop_toggle = CheckBox::new(
+            &wnd,
+            CheckBoxOpts {
+                position: dpi(12, 296),
+                size: dpi(20, 20),
+                check_state: if settings.backdrop {
+                    BST::CHECKED
+                } else {
+                    BST::UNCHECKED
+                },
+                ..Default::default()
+            },
+        );
+        let backdrop_label = Label::new(
+            &wnd,
+            LabelOpts {
+                text: "Cover the rest of the monitor in black",
+                position: dpi(36, 296),
+                size: dpi(332, 20),
+                control_style: SS::LEFTNOWORDWRAP | SS::NOTIFY,
+                ..Default::default()
+            },
+        );
+
+        let work_area_toggle = CheckBox::new(
+            &wnd,
+            CheckBoxOpts {
+                position: dpi(12, 322),
+                size: dpi(20, 20),
+                check_state: if settings.work_area {
+                    BST::CHECKED
+                } else {
+                    BST::UNCHECKED
+                },
+                ..Default::default()
+            },
+        );
+        let work_area_label = Label::new(
+            &wnd,
+            LabelOpts {
+                text: "Fill the work area, keeping the taskbar visible",
+                position: dpi(36, 322),
+                size: dpi(332, 20),
+                control_style: SS::LEFTNOWORDWRAP | SS::NOTIFY,
+                ..Default::default()
+            },
+        );
+
+        Label::new(
+            &wnd,
+            LabelOpts {
+                text: "Fullscreenize hotkey:",
+                position: dpi(12, 358),
+                size: dpi(124, 20),
+                control_style: SS::LEFTNOWORDWRAP,
+                ..Default::default()
+            },
+        );
+
+        let fullscreenize_hotkey_edit = Edit::new(
+            &wnd,
+            EditOpts {
+                text: &hotkey_text(settings.fullscreenize_hotkey),
+                position: dpi(140, 354),
+                width: dpi_x(228),
+                ..Default::default()
+            },
+        );
+
+        Label::new(
+            &wnd,
+            LabelOpts {
+                text: "Restore hotkey:",
+                position: dpi(12, 390),
+                size: dpi(124, 20),
+                control_style: SS::LEFTNOWORDWRAP,
+                ..Default::default()
+            },
+        );
+
+        let restore_hotkey_edit = Edit::new(
+            &wnd,
+            EditOpts {
+                text: &hotkey_text(settings.restore_hotkey),
+                position: dpi(140, 386),
+                width: dpi_x(228),
+                ..Default::default()
+            },
+        );
+
+        Label::new(
+            &wnd,
+            LabelOpts {
+                text: "Hidden windows:",
+                position: dpi(12, 426),
+                size: dpi(124, 20),
+                control_style: SS::LEFTNOWORDWRAP,
+                ..Default::default()
+            },
+        );
+
+        let exclusions_btn = Button::new(
+            &wnd,
+            ButtonOpts {
+                text: "&Exclusions...",
+                position: dpi(140, 421),
+                ..Default::default()
+            },
+        );
+
+        let help_btn = Button::new(
+            &wnd,
+            ButtonOpts {
+                text: "&Help",
+                position: dpi(12, 462),
+                ..Default::default()
+            },
+        );
+
+        let ok_btn = Button::new(
+            &wnd,
+            ButtonOpts {
+                text: "&OK",
+                position: dpi(186, 462),
+                ..Default::default()
+            },
+        );
+
+        let cancel_btn = Button::new(
+            &wnd,
+            ButtonOpts {
+                text: "&Cancel",
+                position: dpi(280, 462),
+                ..Default::default()
+            },
+        );
+
+        let new_self = Self {
+            wnd,
+            theme_picker,
+            monitor_picker,
+            scale_picker,
+            icons_toggle,
+            icons_label,
+            topmost_toggle,
+            topmost_label,
+            tray_toggle,
+            tray_label,
+            close_to_tray_toggle,
+            close_to_tray_label,
+            watchdog_toggle,
+            watchdog_label,
+            live_updates_toggle,
+            live_updates_label,
+            games_first_toggle,
+            games_first_label,
+            backdrop_toggle,
+            backdrop_label,
+            work_area_toggle,
+            work_area_label,
+            fullscreenize_hotkey_edit,
+            restore_hotkey_edit,
+            exclusions_btn,
+            help_btn,
+            ok_btn,
+            cancel_btn,
+            dark_mode,
+            monitors: Arc::new(monitors),
+            settings: Arc::new(Mutex::new(settings.clone())),
+            accepted: Arc::new(AtomicBool::new(false)),
+        };
+
+        new_self.events();
+        new_self
+    }
+
+    /// Function to show the dialog and wait for it to be closed
+    /// # Arguments
+    /// * `parent` - The window that owns the dialog
+    /// # Returns
+    /// * The changed settings, or `None` if the dialog was cancelled
+    pub fn show(&self, parent: &impl GuiParent) -> Option<Settings> {
+        self.wnd
+            .show_modal(parent)
+            .map_err(|e| eprintln!("Failed to show the settings dialog: {e}"))
+            .ok()?;
+
+        self.accepted
+            .load(Ordering::Relaxed)
+            .then(|| lock(&self.settings).clone())
+    }
+
+    fn events(&self) {
+        self.wnd.on().wm_create({
+            let self2 = self.clone();
+            move |_| {
+                theme::apply_theme(
+                    self2.wnd.hwnd(),
+                    &[
+                        self2.icons_toggle.hwnd(),
+                        self2.topmost_toggle.hwnd(),
+                        self2.tray_toggle.hwnd(),
+                        self2.close_to_tray_toggle.hwnd(),
+                        self2.watchdog_toggle.hwnd(),
+                        self2.live_updates_toggle.hwnd(),
+                        self2.games_first_toggle.hwnd(),
+                        self2.backdrop_toggle.hwnd(),
+                        self2.work_area_toggle.hwnd(),
+                        self2.fullscreenize_hotkey_edit.hwnd(),
+                        self2.restore_hotkey_edit.hwnd(),
+                        self2.exclusions_btn.hwnd(),
+                        self2.help_btn.hwnd(),
+                        self2.ok_btn.hwnd(),
+                        self2.cancel_btn.hwnd(),
+                    ],
+                    &[
+                        self2.theme_picker.hwnd(),
+                        self2.monitor_picker.hwnd(),
+                        self2.scale_picker.hwnd(),
+                    ],
+                    self2.dark_mode,
+                );
+
+                Ok(0)
+            }
+        });
+
+        // Toggle the checkboxes when their labels are clicked
+        for (label, toggle) in [
+            (&self.icons_label, &self.icons_toggle),
+            (&self.topmost_label, &self.topmost_toggle),
+            (&self.tray_label, &self.tray_toggle),
+            (&self.close_to_tray_label, &self.close_to_tray_toggle),
+            (&self.watchdog_label, &self.watchdog_toggle),
+            (&self.live_updates_label, &self.live_updates_toggle),
+            (&self.games_first_label, &self.games_first_toggle),
+            (&self.backdrop_label, &self.backdrop_toggle),
+            (&self.work_area_label, &self.work_area_toggle),
+        ] {
+            label.on().stn_clicked({
+                let toggle = toggle.clone();
+                move || {
+                    toggle.trigger_click();
+                    Ok(())
+                }
+            });
+        }
+
+        self.exclusions_btn.on().bn_clicked({
+            let self2 = self.clone();
+            move || {
+                let dialog =
+                    ExclusionsDialog::new(&lock(&self2.settings).exclusions, self2.dark_mode);
+
+                if let Some(exclusions) = dialog.show(&self2.wnd) {
+                    lock(&self2.settings).exclusions = exclusions;
+                }
+
+                Ok(())
+            }
+        });
+
+        self.help_btn.on().bn_clicked({
+            let self2 = self.clone();
+            move || {
+                show_help_message(self2.wnd.hwnd());
+                Ok(())
+            }
+        });
+
+        self.ok_btn.on().bn_clicked({
+            let self2 = self.clone();
+            move || {
+                // Keep the dialog open if a hotkey is invalid, so that the mistake can be fixed
+                let read_hotkeys = || -> Result<_, String> {
+                    Ok((
+                        read_hotkey(&self2.fullscreenize_hotkey_edit)?,
+                        read_hotkey(&self2.restore_hotkey_edit)?,
+                    ))
+                };
+                let (fullscreenize_hotkey, restore_hotkey) = match read_hotkeys() {
+                    Ok(hotkeys) => hotkeys,
+                    Err(e) => {
+                        self2
+                            .wnd
+                            .hwnd()
+                            .TaskDialog(
+                                Some("Invalid hotkey"),
+                                None,
+                                Some(&e),
+                                TDCBF::OK,
+                                IconRes::Error,
+                            )
+                            .map_err(|e| eprintln!("TaskDialog failed: {e}"))
+                            .ok();
+                        return Ok(());
+                    }
+                };
+
+                {
+                    let mut settings = lock(&self2.settings);
+
+                    if let Some(theme) = self2
+                        .theme_picker
+                        .items()
+                        .selected_index()
+                        .and_then(|index| Theme::ALL.get(index as usize))
+                    {
+                        settings.theme = *theme;
+                    }
+                    if let Some(index) = self2.monitor_picker.items().selected_index() {
+                        settings.default_monitor = self2.monitors.choice_at(index as usize);
+                    }
+                    if let Some(scale) = self2
+                        .scale_picker
+                        .items()
+                        .selected_index()
+                        .and_then(|index| ScaleMode::ALL.get(index as usize))
+                    {
+                        settings.scale_mode = *scale;
+                    }
+                    settings.show_icons = self2.icons_toggle.is_checked();
+                    settings.topmost = self2.topmost_toggle.is_checked();
+                    settings.tray_icon = self2.tray_toggle.is_checked();
+                    settings.close_to_tray = self2.close_to_tray_toggle.is_checked();
+                    settings.watchdog = self2.watchdog_toggle.is_checked();
+                    settings.live_updates = self2.live_updates_toggle.is_checked();
+                    settings.game_detection.games_first = self2.games_first_toggle.is_checked();
+                    settings.backdrop = self2.backdrop_toggle.is_checked();
+                    settings.work_area = self2.work_area_toggle.is_checked();
+                    settings.fullscreenize_hotkey = fullscreenize_hotkey;
+                    settings.restore_hotkey = restore_hotkey;
+                }
+
+                self2.accepted.store(true, Ordering::Relaxed);
+                self2.wnd.close();
+                Ok(())
+            }
+        });
+
+        self.cancel_btn.on().bn_clicked({
+            let self2 = self.clone();
+            move || {
+                self2.wnd.close();
+                Ok(())
+            }
+        });
+    }
+}
+
+/// Function to get the text of a hotkey shown in a hotkey field
+/// # Arguments
+/// * `hotkey` - The hotkey, or `None` if it is disabled
+/// # Returns
+/// * The hotkey as text, or an empty string if it is disabled
+fn hotkey_text(hotkey: Option<Hotkey>) -> String {
+    hotkey.map(|hotkey| hotkey.to_string()).unwrap_or_default()
+}
+
+/// Function to read the hotkey typed in a hotkey field
+/// # Arguments
+/// * `edit` - The hotkey field
+/// # Returns
+/// * The hotkey, `None` if the field is empty, or an error message
+fn read_hotkey(edit: &Edit) -> Result<Option<Hotkey>, String> {
+    let text = edit
+        .hwnd()
+        .GetWindowText()
+        .map_err(|e| format!("Failed to read the hotkey: {e}"))?;
+
+    hotkeys::parse_optional(&text)
+}
+
+/// Function to show a help message in a popup window
+/// # Arguments
+/// * `parent` - The window that owns the popup
+fn show_help_message(parent: &HWND) {
+    // Show a popup window with the help message
+    // TODO: Create custom window so dark mode can be implemented
+    parent
+        .TaskDialog(
+            Some("Fullscreenizer"),
+            None,
+            Some("Open the game you want to force in borderless-windowed-fullscreen mode, \
+                 set it to windowed mode to the resolution you want, hit the Refresh button \
+                 to refresh the windows list, select the game window from the list and press \
+                 the Fullscreenize button.  The window will be resized to the desktop area and \
+                 the border will be removed.  Note that using a different in-game resolution \
+                 from the desktop resolution may not work properly (or at all) depending on the game.\n\n\n\
+                 Made by Carter Persall\n\
+                 Based on the program by Kostas \"Bad Sector\" Michalopoulos"),
+            TDCBF::OK,
+            IconRes::None,
+        )
+        .map_err(|e| eprintln!("TaskDialog failed: {e}"))
+        .ok();
+}
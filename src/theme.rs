@@ -0,0 +1,141 @@
+extern crate alloc;
+
+use alloc::sync::Arc;
+use core::iter;
+
+use winsafe::guard::DeleteObjectGuard;
+use winsafe::gui::WindowModal;
+use winsafe::prelude::{GuiEventsWindow as _, GuiWindow as _, Handle as _};
+use winsafe::{self as w, COLORREF, DwmAttr, HBRUSH, HDC, HWND};
+
+/// Function to get the background color of windows and labels
+pub fn window_bg_color(dark_mode: bool) -> COLORREF {
+    if dark_mode {
+        COLORREF::from_rgb(0x1E, 0x1E, 0x1E) // Very dark gray
+    } else {
+        COLORREF::from_rgb(0xF0, 0xF0, 0xF0) // Light gray
+    }
+}
+
+/// Function to get the background color of list views and edit boxes
+pub fn field_bg_color(dark_mode: bool) -> COLORREF {
+    if dark_mode {
+        COLORREF::from_rgb(0x3C, 0x3C, 0x3C) // Dark gray
+    } else {
+        COLORREF::from_rgb(0xFF, 0xFF, 0xFF) // White
+    }
+}
+
+/// Function to get the color of text drawn on the window and field backgrounds
+pub fn text_color(dark_mode: bool) -> COLORREF {
+    if dark_mode {
+        COLORREF::from_rgb(0xF0, 0xF0, 0xF0) // Light gray
+    } else {
+        COLORREF::from_rgb(0x00, 0x00, 0x00) // Black
+    }
+}
+
+/// Function to apply the theme to the title bar of a window and to its controls
+/// # Arguments
+/// * `wnd` - The window
+/// * `controls` - The buttons, checkboxes, list views and edit boxes of the window
+/// * `combo_boxes` - The combo boxes of the window
+/// * `dark_mode` - Whether to use the dark theme
+pub fn apply_theme(wnd: &HWND, controls: &[&HWND], combo_boxes: &[&HWND], dark_mode: bool) {
+    let theme = if dark_mode {
+        "DarkMode_Explorer"
+    } else {
+        "Explorer"
+    };
+
+    // Set the theme of the window title bar
+    wnd.DwmSetWindowAttribute(DwmAttr::UseImmersiveDarkMode(dark_mode))
+        .map_err(|e| eprintln!("DwmSetWindowAttribute failed: {e}"))
+        .ok();
+
+    for hwnd in iter::once(wnd).chain(controls.iter().copied()) {
+        hwnd.SetWindowTheme(theme, None)
+            .map_err(|e| eprintln!("SetWindowTheme failed: {e}"))
+            .ok();
+    }
+
+    // Combo boxes only support dark mode through the CFD (Common File Dialog) theme
+    for hwnd in combo_boxes {
+        hwnd.SetWindowTheme(if dark_mode { "DarkMode_CFD" } else { "CFD" }, None)
+            .map_err(|e| eprintln!("SetWindowTheme on combo box failed: {e}"))
+            .ok();
+    }
+}
+
+/// Function to paint the background of a modal window, its labels and its edit boxes with the theme colors
+/// # Arguments
+/// * `wnd` - The window, which must not be created yet
+/// * `dark_mode` - Whether to use the dark theme
+///
+/// The brushes are created once and freed when the window's event handlers are dropped.
+pub fn paint_modal(wnd: &WindowModal, dark_mode: bool) {
+    let window_hbrush = Arc::new(create_brush(window_bg_color(dark_mode)));
+    let field_hbrush = Arc::new(create_brush(field_bg_color(dark_mode)));
+
+    wnd.on().wm_ctl_color_static({
+        let window_hbrush = window_hbrush.clone();
+        move |ctl| {
+            set_text_colors(&ctl.hdc, window_bg_color(dark_mode), dark_mode);
+            Ok(brush_handle(&window_hbrush))
+        }
+    });
+
+    wnd.on().wm_ctl_color_edit(move |ctl| {
+        set_text_colors(&ctl.hdc, field_bg_color(dark_mode), dark_mode);
+        Ok(brush_handle(&field_hbrush))
+    });
+
+    wnd.on().wm_erase_bkgnd({
+        let wnd = wnd.clone();
+        move |erase_bkgnd| -> w::AnyResult<i32> {
+            if let Some(hbrush) = window_hbrush.as_ref() {
+                match wnd.hwnd().GetClientRect() {
+                    Ok(rect) => {
+                        erase_bkgnd
+                            .hdc
+                            .FillRect(rect, hbrush)
+                            .map_err(|e| eprintln!("FillRect failed: {e}"))
+                            .ok();
+
+                        return Ok(1);
+                    }
+                    Err(e) => eprintln!("GetClientRect failed: {e}"),
+                }
+            }
+
+            // If the brush could not be created, paint the background with the default system color
+            unsafe { wnd.hwnd().DefWindowProc(erase_bkgnd) };
+
+            Ok(0)
+        }
+    });
+}
+
+/// Function to create a solid brush, logging any error
+fn create_brush(color: COLORREF) -> Option<DeleteObjectGuard<HBRUSH>> {
+    HBRUSH::CreateSolidBrush(color)
+        .map_err(|e| eprintln!("CreateSolidBrush failed: {e}"))
+        .ok()
+}
+
+/// Function to get the raw handle of a brush, or a null handle to use the default brush
+fn brush_handle(hbrush: &Option<DeleteObjectGuard<HBRUSH>>) -> HBRUSH {
+    hbrush
+        .as_ref()
+        .map_or_else(|| HBRUSH::NULL, |hbrush| unsafe { hbrush.raw_copy() })
+}
+
+/// Function to set the colors text is drawn with in a control
+fn set_text_colors(hdc: &HDC, bg_color: COLORREF, dark_mode: bool) {
+    let _old_color = hdc
+        .SetTextColor(text_color(dark_mode))
+        .map_err(|e| eprintln!("SetTextColor failed: {e}"));
+    let _old_bk_color = hdc
+        .SetBkColor(bg_color)
+        .map_err(|e| eprintln!("SetBkColor failed: {e}"));
+}
@@ -0,0 +1,68 @@
+use winsafe::co::{NIF, NIIF, NIM, WM};
+use winsafe::prelude::Handle as _;
+use winsafe::{HINSTANCE, HWND, IdIdiStr, NOTIFYICONDATA, Shell_NotifyIcon};
+
+/// ID of Fullscreenizer's icon in the notification area
+const TRAY_ICON_ID: u32 = 1;
+
+/// Function to get the message the tray icon sends to the window when it is clicked
+///
+/// `WM_APP` itself is used for the post-creation tasks of the main window.
+pub fn callback_message() -> WM {
+    unsafe { WM::from_raw(WM::APP.raw() + 1) }
+}
+
+/// Function to add Fullscreenizer's icon to the notification area
+/// # Arguments
+/// * `hwnd` - The window receiving the clicks on the icon
+/// # Returns
+/// * An error message if the icon could not be added
+pub fn add_icon(hwnd: &HWND) -> Result<(), String> {
+    // Reuse the icon of the main window
+    let hicon = HINSTANCE::GetModuleHandle(None)
+        .and_then(|hinstance| hinstance.LoadIcon(IdIdiStr::Id(101)))
+        .map_err(|e| format!("LoadIcon failed with error: {e}"))?;
+
+    let mut data = notify_icon_data(hwnd);
+    data.uFlags = NIF::ICON | NIF::MESSAGE | NIF::TIP;
+    data.uCallbackMessage = callback_message();
+    data.hIcon = hicon;
+    data.set_szTip("Fullscreenizer");
+
+    Shell_NotifyIcon(NIM::ADD, &data)
+        .map_err(|e| format!("Shell_NotifyIcon failed with error: {e}"))
+}
+
+/// Function to remove Fullscreenizer's icon from the notification area
+/// # Arguments
+/// * `hwnd` - The window the icon was added with
+pub fn remove_icon(hwnd: &HWND) {
+    Shell_NotifyIcon(NIM::DELETE, &notify_icon_data(hwnd))
+        .map_err(|e| eprintln!("Failed to remove the tray icon - Shell_NotifyIcon failed: {e}"))
+        .ok();
+}
+
+/// Function to show a notification next to Fullscreenizer's icon in the notification area
+/// # Arguments
+/// * `hwnd` - The window the icon was added with
+/// * `text` - The text of the notification
+/// * `is_error` - Whether the notification reports an error
+pub fn show_balloon(hwnd: &HWND, text: &str, is_error: bool) {
+    let mut data = notify_icon_data(hwnd);
+    data.uFlags = NIF::INFO;
+    data.set_szInfoTitle("Fullscreenizer");
+    data.set_szInfo(text);
+    data.dwInfoFlags = if is_error { NIIF::ERROR } else { NIIF::INFO };
+
+    Shell_NotifyIcon(NIM::MODIFY, &data)
+        .map_err(|e| eprintln!("Failed to show a notification - Shell_NotifyIcon failed: {e}"))
+        .ok();
+}
+
+/// Function to create the structure identifying Fullscreenizer's icon
+fn notify_icon_data(hwnd: &HWND) -> NOTIFYICONDATA {
+    let mut data = NOTIFYICONDATA::default();
+    data.hWnd = unsafe { hwnd.raw_copy() };
+    data.uID = TRAY_ICON_ID;
+    data
+}
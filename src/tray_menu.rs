@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+
+use crate::window_info;
+
+/// Maximum number of recently fullscreenized applications kept
+const MAX_RECENT_APPS: usize = 8;
+
+/// Maximum number of windows listed in the tray menu
+pub const MAX_MENU_WINDOWS: usize = 30;
+
+/// Maximum number of characters of a window title shown in the tray menu
+const MAX_LABEL_CHARS: usize = 60;
+
+/// Command ID of the first window of the tray menu
+const WINDOW_BASE_ID: u16 = 100;
+/// Command ID of the first recent application of the tray menu
+const RECENT_APP_BASE_ID: u16 = 200;
+
+/// An item of the tray icon's context menu
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrayCommand {
+    /// Show the Fullscreenizer window
+    Show,
+    /// Close Fullscreenizer
+    Exit,
+    /// Fullscreenize the window at this index of the menu's window list
+    Window(usize),
+    /// Fullscreenize a window of the recent application at this index
+    RecentApp(usize),
+}
+
+impl TrayCommand {
+    /// Function to get the command ID of the menu item
+    pub const fn id(self) -> u16 {
+        match self {
+            Self::Show => 1,
+            Self::Exit => 2,
+            Self::Window(index) => WINDOW_BASE_ID + index as u16,
+            Self::RecentApp(index) => RECENT_APP_BASE_ID + index as u16,
+        }
+    }
+
+    /// Function to get the menu item of a command ID
+    /// # Arguments
+    /// * `id` - The command ID returned by the menu
+    /// # Returns
+    /// * The menu item, or `None` if the ID does not belong to the tray menu
+    pub fn from_id(id: u16) -> Option<Self> {
+        let window_ids = WINDOW_BASE_ID..WINDOW_BASE_ID + MAX_MENU_WINDOWS as u16;
+        let recent_app_ids = RECENT_APP_BASE_ID..RECENT_APP_BASE_ID + MAX_RECENT_APPS as u16;
+
+        match id {
+            1 => Some(Self::Show),
+            2 => Some(Self::Exit),
+            id if window_ids.contains(&id) => Some(Self::Window(usize::from(id - WINDOW_BASE_ID))),
+            id if recent_app_ids.contains(&id) => {
+                Some(Self::RecentApp(usize::from(id - RECENT_APP_BASE_ID)))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Function to get the text of a menu item showing a window title or an application name
+/// # Arguments
+/// * `text` - The text
+/// # Returns
+/// * The text shortened to fit in the menu, with `&` escaped so that it is not read as a mnemonic
+pub fn menu_label(text: &str) -> String {
+    let label = if text.chars().count() > MAX_LABEL_CHARS {
+        let shortened: String = text.chars().take(MAX_LABEL_CHARS - 3).collect();
+        shortened + "..."
+    } else {
+        text.to_owned()
+    };
+
+    label.replace('&', "&&")
+}
+
+/// Function to get the text of the menu item of a recent application
+/// # Arguments
+/// * `exe_name` - The executable name of the application
+/// # Returns
+/// * The executable name without its `.exe` extension, ready to be shown in the menu
+pub fn app_label(exe_name: &str) -> String {
+    menu_label(window_info::strip_exe_extension(exe_name))
+}
+
+/// The executable names of the applications fullscreenized last, most recent first
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RecentApps {
+    apps: Vec<String>,
+}
+
+impl RecentApps {
+    /// Function to move an application to the top of the list
+    /// # Arguments
+    /// * `exe_name` - The executable name of the application
+    /// # Returns
+    /// * `true` if the list changed
+    ///
+    /// The oldest application is forgotten once the list is full.
+    pub fn add(&mut self, exe_name: &str) -> bool {
+        if self
+            .apps
+            .first()
+            .is_some_and(|first| first.eq_ignore_ascii_case(exe_name))
+        {
+            return false;
+        }
+
+        self.apps.retain(|app| !app.eq_ignore_ascii_case(exe_name));
+        self.apps.insert(0, exe_name.to_owned());
+        self.apps.truncate(MAX_RECENT_APPS);
+        true
+    }
+
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.apps.get(index).map(String::as_str)
+    }
+
+    /// Function to iterate over the applications, most recent first
+    ///
+    /// Applications added by hand to the settings beyond the size of the list are skipped.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.apps.iter().take(MAX_RECENT_APPS).map(String::as_str)
+    }
+}
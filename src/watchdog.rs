@@ -0,0 +1,427 @@
+use core::cmp::Reverse;
+use core::fmt;
+use core::time::Duration;
+use std::collections::{HashMap, VecDeque};
+use std::time::Instant;
+
+use crate::geometry::Rect;
+use crate::window_system::TOPMOST_EX_STYLE;
+
+/// `WS_` style bits making up the border of a window
+const FRAME_STYLES: u32 = 0x00C0_0000 // CAPTION
+    | 0x0080_0000 // BORDER
+    | 0x0040_0000 // DLGFRAME
+    | 0x0004_0000; // THICKFRAME
+
+/// `WS_MINIMIZE` style bit
+const MINIMIZE_STYLE: u32 = 0x2000_0000;
+
+/// Maximum number of times a window is fixed within [`RATE_LIMIT_PERIOD`]
+const RATE_LIMIT_COUNT: usize = 5;
+
+/// Period the interventions on a window are counted over
+const RATE_LIMIT_PERIOD: Duration = Duration::from_secs(30);
+
+/// Maximum number of entries kept in the intervention log
+const MAX_LOG_ENTRIES: usize = 50;
+
+/// The state a fullscreenized window should stay in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DesiredState {
+    /// The window rectangle, in virtual-desktop coordinates
+    pub rect: Rect,
+    /// The `WS_` style of the window
+    pub style: u32,
+    /// Whether the window should have the "stay on top" flag
+    pub topmost: bool,
+}
+
+/// The current state of a window, as read from the system
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ActualState {
+    pub rect: Rect,
+    pub style: u32,
+    pub ex_style: u32,
+}
+
+/// A way a window drifted from its desired state
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Drift {
+    /// The window was moved or resized
+    Moved,
+    /// The window got its border back
+    Border,
+    /// The window lost its "stay on top" flag
+    Topmost,
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Moved => "moved or resized",
+            Self::Border => "border restored",
+            Self::Topmost => "\"stay on top\" flag removed",
+        })
+    }
+}
+
+/// Function to compare the state of a window with the state it should be in
+/// # Arguments
+/// * `desired` - The state the window should be in
+/// * `actual` - The current state of the window
+/// # Returns
+/// * The ways the window drifted, which is empty if it is still in the desired state
+///
+/// Minimized windows are never considered to have drifted, as minimizing is usually done by the user.
+pub fn detect_drift(desired: &DesiredState, actual: &ActualState) -> Vec<Drift> {
+    let mut drift = Vec::new();
+    if actual.style & MINIMIZE_STYLE != 0 {
+        return drift;
+    }
+
+    if actual.rect != desired.rect {
+        drift.push(Drift::Moved);
+    }
+    if actual.style & FRAME_STYLES != desired.style & FRAME_STYLES {
+        drift.push(Drift::Border);
+    }
+    if desired.topmost && actual.ex_style & TOPMOST_EX_STYLE == 0 {
+        drift.push(Drift::Topmost);
+    }
+
+    drift
+}
+
+/// What to do after a window changed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Decision {
+    /// The window is not watched, or is still in its desired state
+    Ignore,
+    /// The window drifted and should be put back into this state
+    Reapply(DesiredState),
+    /// The window drifted, but was fixed too often recently and is left alone for now
+    RateLimited,
+}
+
+/// An entry of the intervention log
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct LogEntry {
+    title: String,
+    drift: Vec<Drift>,
+    rate_limited: bool,
+}
+
+/// A fullscreenized window watched by the watchdog
+#[derive(Debug)]
+struct WatchedWindow {
+    title: String,
+    desired: DesiredState,
+    /// The times the window was fixed within the rate limit period
+    recent: VecDeque<Instant>,
+    /// The number of times the window was fixed since it was fullscreenized
+    interventions: u32,
+}
+
+/// Keeps fullscreenized windows in their desired state when their application reverts it
+///
+/// The watchdog only decides what to do, the windows are read and changed by the caller.
+#[derive(Debug, Default)]
+pub struct Watchdog {
+    windows: HashMap<isize, WatchedWindow>,
+    log: VecDeque<LogEntry>,
+}
+
+impl Watchdog {
+    /// Function to start watching a window
+    /// # Arguments
+    /// * `hwnd` - The raw handle of the window
+    /// * `title` - The title of the window, shown in the intervention log
+    /// * `desired` - The state the window should stay in
+    pub fn watch(&mut self, hwnd: isize, title: &str, desired: DesiredState) {
+        self.windows.insert(
+            hwnd,
+            WatchedWindow {
+                title: title.to_owned(),
+                desired,
+                recent: VecDeque::new(),
+                interventions: 0,
+            },
+        );
+    }
+
+    /// Function to stop watching a window, usually after it was restored or closed
+    pub fn unwatch(&mut self, hwnd: isize) {
+        self.windows.remove(&hwnd);
+    }
+
+    /// Function to get the raw handles of the watched windows
+    pub fn watched(&self) -> Vec<isize> {
+        self.windows.keys().copied().collect()
+    }
+
+    /// Function to decide what to do after a window changed
+    /// # Arguments
+    /// * `hwnd` - The raw handle of the window
+    /// * `actual` - The current state of the window
+    /// * `now` - The current time
+    /// # Returns
+    /// * Whether the window should be put back into its desired state
+    ///
+    /// Every drift is logged, and a window is fixed at most [`RATE_LIMIT_COUNT`] times
+    /// within [`RATE_LIMIT_PERIOD`], so that the watchdog does not fight an application forever.
+    pub fn check(&mut self, hwnd: isize, actual: &ActualState, now: Instant) -> Decision {
+        let Some(window) = self.windows.get_mut(&hwnd) else {
+            return Decision::Ignore;
+        };
+
+        let drift = detect_drift(&window.desired, actual);
+        if drift.is_empty() {
+            return Decision::Ignore;
+        }
+
+        while window
+            .recent
+            .front()
+            .is_some_and(|&time| now.duration_since(time) >= RATE_LIMIT_PERIOD)
+        {
+            window.recent.pop_front();
+        }
+        let rate_limited = window.recent.len() >= RATE_LIMIT_COUNT;
+        if !rate_limited {
+            window.recent.push_back(now);
+            window.interventions += 1;
+        }
+
+        let entry = LogEntry {
+            title: window.title.clone(),
+            drift,
+            rate_limited,
+        };
+        let decision = if rate_limited {
+            Decision::RateLimited
+        } else {
+            Decision::Reapply(window.desired)
+        };
+
+        // Repeated rate-limited drifts are only logged once
+        if !(rate_limited && self.log.back() == Some(&entry)) {
+            self.log.push_back(entry);
+            if self.log.len() > MAX_LOG_ENTRIES {
+                self.log.pop_front();
+            }
+        }
+
+        decision
+    }
+
+    /// Function to describe how often the watchdog had to intervene
+    /// # Returns
+    /// * The number of fixes per watched window, followed by the most recent interventions
+    pub fn report(&self) -> String {
+        if self.log.is_empty() {
+            return "The watchdog has not had to intervene yet.".to_owned();
+        }
+
+        let mut windows: Vec<&WatchedWindow> = self.windows.values().collect();
+        windows.sort_by_key(|window| Reverse(window.interventions));
+
+        let mut report = String::from("Fixes per window:\n");
+        for window in windows {
+            report += &format!("{}: {}\n", window.title, window.interventions);
+        }
+
+        report += "\nRecent interventions:\n";
+        for entry in self.log.iter().rev() {
+            let drift: Vec<String> = entry.drift.iter().map(Drift::to_string).collect();
+            report += &format!(
+                "{}: {}{}\n",
+                entry.title,
+                drift.join(", "),
+                if entry.rate_limited {
+                    " (left alone, fixed too often)"
+                } else {
+                    ""
+                }
+            );
+        }
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HWND: isize = 0x1234;
+    const FULLSCREEN: Rect = Rect::new(0, 0, 1920, 1080);
+    /// `WS_VISIBLE | WS_POPUP`, the style of a borderless window
+    const BORDERLESS: u32 = 0x9000_0000;
+    /// `WS_VISIBLE | WS_OVERLAPPEDWINDOW`, the style of a normal window
+    const BORDERED: u32 = 0x10CF_0000;
+
+    fn desired(topmost: bool) -> DesiredState {
+        DesiredState {
+            rect: FULLSCREEN,
+            style: BORDERLESS,
+            topmost,
+        }
+    }
+
+    fn actual(rect: Rect, style: u32, ex_style: u32) -> ActualState {
+        ActualState {
+            rect,
+            style,
+            ex_style,
+        }
+    }
+
+    fn moved() -> ActualState {
+        actual(Rect::new(100, 100, 900, 700), BORDERLESS, 0)
+    }
+
+    #[test]
+    fn no_drift_in_the_desired_state() {
+        let drift = detect_drift(
+            &desired(true),
+            &actual(FULLSCREEN, BORDERLESS, TOPMOST_EX_STYLE),
+        );
+        assert!(drift.is_empty());
+    }
+
+    #[test]
+    fn detects_every_kind_of_drift() {
+        let drift = detect_drift(
+            &desired(true),
+            &actual(Rect::new(100, 100, 900, 700), BORDERED, 0),
+        );
+        assert_eq!(drift, vec![Drift::Moved, Drift::Border, Drift::Topmost]);
+    }
+
+    #[test]
+    fn ignores_style_bits_other_than_the_border() {
+        // WS_MAXIMIZEBOX is not part of the border
+        let drift = detect_drift(
+            &desired(false),
+            &actual(FULLSCREEN, BORDERLESS | 0x0001_0000, 0),
+        );
+        assert!(drift.is_empty());
+    }
+
+    #[test]
+    fn topmost_is_only_checked_when_desired() {
+        assert!(detect_drift(&desired(false), &actual(FULLSCREEN, BORDERLESS, 0)).is_empty());
+        assert_eq!(
+            detect_drift(&desired(true), &actual(FULLSCREEN, BORDERLESS, 0)),
+            vec![Drift::Topmost]
+        );
+    }
+
+    #[test]
+    fn minimized_windows_never_drift() {
+        let minimized = actual(
+            Rect::new(-32000, -32000, -31840, -31972),
+            BORDERED | MINIMIZE_STYLE,
+            0,
+        );
+        assert!(detect_drift(&desired(true), &minimized).is_empty());
+    }
+
+    #[test]
+    fn unwatched_and_undrifted_windows_are_ignored() {
+        let mut watchdog = Watchdog::default();
+        let now = Instant::now();
+        assert_eq!(watchdog.check(HWND, &moved(), now), Decision::Ignore);
+
+        watchdog.watch(HWND, "Game", desired(false));
+        assert_eq!(
+            watchdog.check(HWND, &actual(FULLSCREEN, BORDERLESS, 0), now),
+            Decision::Ignore
+        );
+
+        watchdog.unwatch(HWND);
+        assert_eq!(watchdog.check(HWND, &moved(), now), Decision::Ignore);
+        assert!(watchdog.watched().is_empty());
+    }
+
+    #[test]
+    fn rate_limits_repeated_fixes() {
+        let mut watchdog = Watchdog::default();
+        watchdog.watch(HWND, "Game", desired(false));
+        let start = Instant::now();
+
+        for i in 0..RATE_LIMIT_COUNT {
+            let now = start + Duration::from_secs(u64::try_from(i).unwrap());
+            assert_eq!(
+                watchdog.check(HWND, &moved(), now),
+                Decision::Reapply(desired(false))
+            );
+        }
+        let now = start + Duration::from_secs(10);
+        assert_eq!(watchdog.check(HWND, &moved(), now), Decision::RateLimited);
+        assert_eq!(watchdog.check(HWND, &moved(), now), Decision::RateLimited);
+    }
+
+    #[test]
+    fn fixes_again_once_the_period_passed() {
+        let mut watchdog = Watchdog::default();
+        watchdog.watch(HWND, "Game", desired(false));
+        let start = Instant::now();
+
+        for _ in 0..RATE_LIMIT_COUNT {
+            watchdog.check(HWND, &moved(), start);
+        }
+        assert_eq!(
+            watchdog.check(
+                HWND,
+                &moved(),
+                start + RATE_LIMIT_PERIOD - Duration::from_secs(1)
+            ),
+            Decision::RateLimited
+        );
+        assert_eq!(
+            watchdog.check(HWND, &moved(), start + RATE_LIMIT_PERIOD),
+            Decision::Reapply(desired(false))
+        );
+    }
+
+    #[test]
+    fn rate_limits_each_window_separately() {
+        let mut watchdog = Watchdog::default();
+        watchdog.watch(HWND, "Game", desired(false));
+        watchdog.watch(HWND + 1, "Other game", desired(false));
+        let now = Instant::now();
+
+        for _ in 0..RATE_LIMIT_COUNT {
+            watchdog.check(HWND, &moved(), now);
+        }
+        assert_eq!(watchdog.check(HWND, &moved(), now), Decision::RateLimited);
+        assert_eq!(
+            watchdog.check(HWND + 1, &moved(), now),
+            Decision::Reapply(desired(false))
+        );
+    }
+
+    #[test]
+    fn repeated_rate_limited_drifts_are_logged_once() {
+        let mut watchdog = Watchdog::default();
+        assert_eq!(
+            watchdog.report(),
+            "The watchdog has not had to intervene yet."
+        );
+
+        watchdog.watch(HWND, "Game", desired(false));
+        let now = Instant::now();
+        for _ in 0..RATE_LIMIT_COUNT + 3 {
+            watchdog.check(HWND, &moved(), now);
+        }
+
+        let report = watchdog.report();
+        assert!(report.starts_with("Fixes per window:\nGame: 5\n"));
+        assert_eq!(report.matches("(left alone, fixed too often)").count(), 1);
+        assert_eq!(
+            report.matches("Game: moved or resized\n").count(),
+            RATE_LIMIT_COUNT
+        );
+    }
+}
@@ -0,0 +1,258 @@
+use crate::journal::Journal;
+use crate::monitors::MonitorList;
+use crate::profiles::{Profile, SeenWindows};
+use crate::settings::Settings;
+use crate::watchdog::DesiredState;
+use crate::window_ops;
+use crate::window_system::WindowSystem;
+
+/// Interval between two checks for new windows, in milliseconds
+pub const POLL_INTERVAL_MS: u32 = 1000;
+
+/// A profile that was applied to a window by the watcher
+#[derive(Debug)]
+pub struct AppliedProfile {
+    /// The raw handle of the window
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub hwnd: isize,
+    /// The title of the window
+    pub title: String,
+    /// The name of the profile
+    pub profile: String,
+    /// The executable name of the window's application, if it could be queried
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub exe_name: Option<String>,
+    /// The state the window was put in, or an error message if the profile could not be applied
+    pub result: Result<DesiredState, String>,
+}
+
+/// Watches for new windows and applies the matching profiles to them
+///
+/// Windows that were already open when watching started are left alone.
+#[derive(Debug, Default)]
+pub struct ProfileWatcher {
+    seen: SeenWindows,
+}
+
+impl ProfileWatcher {
+    /// Function to apply the profiles to the windows that appeared since the last check
+    /// # Arguments
+    /// * `system` - The system managing the windows
+    /// * `settings` - The settings holding the profiles, the exclusions and the spans
+    /// * `journal` - The journal the original state of the windows is recorded in
+    /// # Returns
+    /// * The profiles that were applied, which is empty if no new window matched a profile
+    pub fn poll(
+        &mut self,
+        system: &impl WindowSystem,
+        settings: &Settings,
+        journal: &mut Journal,
+    ) -> Vec<AppliedProfile> {
+        let windows = window_ops::enumerate_windows(system, &settings.exclusions, false);
+        let hwnds: Vec<isize> = windows.iter().map(|window| window.hwnd).collect();
+        let unhandled = self.seen.unhandled(&hwnds, |hwnd| system.is_window(hwnd));
+        if unhandled.is_empty() {
+            return Vec::new();
+        }
+
+        let mut applied = Vec::new();
+        let mut monitors = None;
+        for window in windows
+            .iter()
+            .filter(|window| unhandled.contains(&window.hwnd))
+        {
+            // Windows fullscreenized by hand are not changed again
+            if journal.windows().get(window.hwnd).is_some() {
+                self.seen.mark_handled(window.hwnd);
+                continue;
+            }
+
+            // Windows without a matching profile are checked again, as their title may still change
+            let Some(profile) = settings
+                .profiles
+                .find(window.exe_name(), &window.class_name, &window.title)
+                .filter(|profile| profile.auto_apply)
+            else {
+                continue;
+            };
+
+            self.seen.mark_handled(window.hwnd);
+            let monitors = monitors.get_or_insert_with(|| {
+                MonitorList::new(system.monitors()).with_spans(&settings.spans)
+            });
+
+            applied.push(AppliedProfile {
+                hwnd: window.hwnd,
+                title: window.title.clone(),
+                profile: profile.name.clone(),
+                exe_name: window.exe_name().map(str::to_owned),
+                result: apply_profile(
+                    system,
+                    window.hwnd,
+                    profile,
+                    monitors,
+                    settings.work_area,
+                    journal,
+                ),
+            });
+        }
+
+        applied
+    }
+}
+
+/// Function to apply a profile to a window
+/// # Arguments
+/// * `system` - The system managing the windows
+/// * `hwnd` - The raw handle of the window
+/// * `profile` - The profile
+/// * `monitors` - The connected monitors
+/// * `work_area` - Whether to place the window relative to the work area of its monitor instead of the whole monitor
+/// * `journal` - The journal the original state of the window is recorded in
+/// # Returns
+/// * The state the window was put in, or an error message if the window could not be modified
+pub fn apply_profile(
+    system: &impl WindowSystem,
+    hwnd: isize,
+    profile: &Profile,
+    monitors: &MonitorList,
+    work_area: bool,
+    journal: &mut Journal,
+) -> Result<DesiredState, String> {
+    // Get the dimensions of the profile's monitor or span
+    let area = monitors
+        .resolve_area(&profile.monitor, system.rect(hwnd)?, work_area)
+        .ok_or_else(|| "Could not find a monitor to place the window on".to_owned())?;
+    if !area.rectangular {
+        eprintln!(
+            "Warning: the monitors spanned by profile '{}' do not form a rectangle",
+            profile.name
+        );
+    }
+    let target = profile.target_rect(area.rect, system.client_size(hwnd)?);
+
+    // Record the original state of the window so that it can be restored later
+    let state = window_ops::capture_state(system, hwnd)
+        .map_err(|e| format!("Could not save the window's state: {e}"))?;
+    journal
+        .record(state)
+        .map_err(|e| eprintln!("Failed to save the window's state - {e}"))
+        .ok();
+
+    if profile.borderless {
+        window_ops::fullscreenize(system, hwnd, target, profile.topmost)?;
+    } else {
+        window_ops::move_window(system, hwnd, target, profile.topmost)?;
+    }
+
+    window_ops::desired_state(system, hwnd, profile.topmost)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_system::{FakeSystem, FakeWindow};
+    use crate::geometry::Rect;
+    use crate::monitors::Monitor;
+    use crate::profiles::Profiles;
+
+    const MONITOR: Rect = Rect::new(0, 0, 1920, 1080);
+
+    fn system() -> FakeSystem {
+        FakeSystem::new(vec![Monitor {
+            device_name: "\\\\.\\DISPLAY1".to_owned(),
+            rect: MONITOR,
+            work_area: Rect::new(0, 0, 1920, 1040),
+            is_primary: true,
+        }])
+    }
+
+    fn settings() -> Settings {
+        let mut settings = Settings::default();
+        settings.profiles = Profiles::new(vec![Profile {
+            name: "Game".to_owned(),
+            exe: Some("game.exe".to_owned()),
+            ..Profile::default()
+        }])
+        .unwrap();
+        settings
+    }
+
+    fn applied_names(applied: &[AppliedProfile]) -> Vec<(isize, &str)> {
+        applied
+            .iter()
+            .map(|applied| (applied.hwnd, applied.profile.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn profiles_are_applied_to_new_windows_only() {
+        let system = system();
+        system.add_window(FakeWindow::new(1, "Game", r"C:\Games\game.exe"));
+        let settings = settings();
+        let mut journal = Journal::default();
+        let mut watcher = ProfileWatcher::default();
+
+        assert!(watcher.poll(&system, &settings, &mut journal).is_empty());
+
+        system.add_window(FakeWindow::new(2, "Game", r"C:\Games\game.exe"));
+        system.add_window(FakeWindow::new(3, "Editor", r"C:\Tools\editor.exe"));
+        let applied = watcher.poll(&system, &settings, &mut journal);
+        assert_eq!(applied_names(&applied), [(2, "Game")]);
+        assert!(applied.first().unwrap().result.is_ok());
+        assert_eq!(system.window(2).unwrap().rect, MONITOR);
+        assert!(journal.windows().get(2).is_some());
+        assert_eq!(
+            system.window(1).unwrap().rect,
+            Rect::new(100, 100, 900, 700)
+        );
+
+        assert!(watcher.poll(&system, &settings, &mut journal).is_empty());
+    }
+
+    #[test]
+    fn restored_windows_are_not_changed_again_after_being_unlisted() {
+        let system = system();
+        let settings = settings();
+        let mut journal = Journal::default();
+        let mut watcher = ProfileWatcher::default();
+        assert!(watcher.poll(&system, &settings, &mut journal).is_empty());
+
+        system.add_window(FakeWindow::new(1, "Game", r"C:\Games\game.exe"));
+        assert_eq!(watcher.poll(&system, &settings, &mut journal).len(), 1);
+
+        // The user restores the window, which the game then hides for a moment
+        let state = journal.take(1).unwrap();
+        window_ops::restore(&system, 1, &state).unwrap();
+        system.set_visible(1, false);
+        assert!(watcher.poll(&system, &settings, &mut journal).is_empty());
+
+        system.set_visible(1, true);
+        assert!(watcher.poll(&system, &settings, &mut journal).is_empty());
+        assert_eq!(
+            system.window(1).unwrap().rect,
+            Rect::new(100, 100, 900, 700)
+        );
+    }
+
+    #[test]
+    fn reused_handles_of_closed_windows_are_handled_again() {
+        let system = system();
+        let settings = settings();
+        let mut journal = Journal::default();
+        let mut watcher = ProfileWatcher::default();
+        assert!(watcher.poll(&system, &settings, &mut journal).is_empty());
+
+        system.add_window(FakeWindow::new(1, "Game", r"C:\Games\game.exe"));
+        assert_eq!(watcher.poll(&system, &settings, &mut journal).len(), 1);
+        journal.take(1);
+
+        system.close_window(1);
+        assert!(watcher.poll(&system, &settings, &mut journal).is_empty());
+        system.add_window(FakeWindow::new(1, "Game", r"C:\Games\game.exe"));
+        assert_eq!(
+            applied_names(&watcher.poll(&system, &settings, &mut journal)),
+            [(1, "Game")]
+        );
+    }
+}
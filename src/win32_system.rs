@@ -0,0 +1,252 @@
+use winsafe::co::{
+    GCLP, GW, HWND_PLACE, ICON_SZ, MONITOR, MONITORINFOF, PROCESS, PROCESS_NAME, SW, SWP, WM, WPF,
+    WS, WS_EX,
+};
+use winsafe::msg::WndMsg;
+use winsafe::prelude::Handle as _;
+use winsafe::{
+    AdjustWindowRectExForDpi, EnumWindows, HDC, HICON, HPROCESS, HWND, HwndPlace, POINT, RECT,
+    SIZE, WINDOWPLACEMENT,
+};
+
+use crate::geometry::Rect;
+use crate::monitors::Monitor;
+use crate::window_state::SavedPlacement;
+use crate::window_system::{WindowSystem, ZOrder};
+
+/// The windows of the Windows desktop, managed through the Win32 API
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Win32System;
+
+/// Function to get the window handle of a raw handle
+fn handle(hwnd: isize) -> HWND {
+    unsafe { HWND::from_ptr(hwnd as *mut _) }
+}
+
+impl WindowSystem for Win32System {
+    type Icon = HICON;
+
+    fn windows(&self) -> Vec<isize> {
+        let mut windows = Vec::new();
+
+        EnumWindows(|hwnd: HWND| -> bool {
+            windows.push(hwnd.ptr() as isize);
+
+            // Return true to continue enumerating
+            true
+        })
+        .map_err(|e| eprintln!("EnumWindows failed: {e}"))
+        .ok();
+
+        windows
+    }
+
+    fn is_window(&self, hwnd: isize) -> bool {
+        handle(hwnd).IsWindow()
+    }
+
+    fn is_visible(&self, hwnd: isize) -> bool {
+        handle(hwnd).IsWindowVisible()
+    }
+
+    fn title(&self, hwnd: isize) -> Result<String, String> {
+        handle(hwnd)
+            .GetWindowText()
+            .map_err(|e| format!("GetWindowText failed with error: {e}"))
+    }
+
+    fn class_name(&self, hwnd: isize) -> Result<String, String> {
+        handle(hwnd)
+            .GetClassName()
+            .map_err(|e| format!("GetClassName failed with error: {e}"))
+    }
+
+    fn process_id(&self, hwnd: isize) -> u32 {
+        handle(hwnd).GetWindowThreadProcessId().1
+    }
+
+    fn exe_path(&self, hwnd: isize) -> Option<String> {
+        let process_id = self.process_id(hwnd);
+
+        HPROCESS::OpenProcess(PROCESS::QUERY_LIMITED_INFORMATION, false, process_id)
+            .and_then(|process| process.QueryFullProcessImageName(PROCESS_NAME::WIN32))
+            .map_err(|e| eprintln!("Failed to get the executable of process {process_id}: {e}"))
+            .ok()
+    }
+
+    fn rect(&self, hwnd: isize) -> Result<Rect, String> {
+        handle(hwnd)
+            .GetWindowRect()
+            .map(Rect::from)
+            .map_err(|e| format!("GetWindowRect failed with error: {e}"))
+    }
+
+    fn client_size(&self, hwnd: isize) -> Result<(i32, i32), String> {
+        handle(hwnd)
+            .GetClientRect()
+            .map(|rect| (rect.right - rect.left, rect.bottom - rect.top))
+            .map_err(|e| format!("GetClientRect failed with error: {e}"))
+    }
+
+    fn monitor_name(&self, hwnd: isize) -> Option<String> {
+        handle(hwnd)
+            .MonitorFromWindow(MONITOR::DEFAULTTONEAREST)
+            .GetMonitorInfo()
+            .map(|info| info.szDevice())
+            .ok()
+    }
+
+    fn style(&self, hwnd: isize) -> u32 {
+        handle(hwnd).style().raw()
+    }
+
+    fn ex_style(&self, hwnd: isize) -> u32 {
+        handle(hwnd).style_ex().raw()
+    }
+
+    fn set_style(&self, hwnd: isize, style: u32) {
+        handle(hwnd).set_style(unsafe { WS::from_raw(style) });
+    }
+
+    fn set_ex_style(&self, hwnd: isize, ex_style: u32) {
+        handle(hwnd).set_style_ex(unsafe { WS_EX::from_raw(ex_style) });
+    }
+
+    fn window_rect_for_client(&self, hwnd: isize, client: Rect) -> Result<Rect, String> {
+        let hwnd = handle(hwnd);
+
+        AdjustWindowRectExForDpi(
+            RECT::from(client),
+            hwnd.style(),
+            false,
+            hwnd.style_ex(),
+            hwnd.GetDpiForWindow(),
+        )
+        .map(Rect::from)
+        .map_err(|e| format!("AdjustWindowRectExForDpi failed with error: {e}"))
+    }
+
+    fn set_position(
+        &self,
+        hwnd: isize,
+        rect: Option<Rect>,
+        z_order: ZOrder,
+        activate: bool,
+    ) -> Result<(), String> {
+        let hwnd_insert_after = match z_order {
+            ZOrder::Top => HwndPlace::None,
+            ZOrder::Topmost => HwndPlace::Place(HWND_PLACE::TOPMOST),
+            ZOrder::NoTopmost => HwndPlace::Place(HWND_PLACE::NOTOPMOST),
+            ZOrder::After(prev) => HwndPlace::Hwnd(handle(prev)),
+        };
+
+        // The frame change applies the styles changed before moving the window
+        let mut flags = SWP::FRAMECHANGED;
+        if !activate {
+            flags |= SWP::NOACTIVATE;
+        }
+        let (position, size) = match rect {
+            Some(rect) => (
+                POINT::with(rect.left, rect.top),
+                SIZE::with(rect.width(), rect.height()),
+            ),
+            None => {
+                flags |= SWP::NOMOVE | SWP::NOSIZE;
+                (POINT::default(), SIZE::default())
+            }
+        };
+
+        handle(hwnd)
+            .SetWindowPos(hwnd_insert_after, position, size, flags)
+            .map_err(|e| format!("SetWindowPos failed with error: {e}"))
+    }
+
+    fn placement(&self, hwnd: isize) -> Result<SavedPlacement, String> {
+        let mut placement = WINDOWPLACEMENT::default();
+        handle(hwnd)
+            .GetWindowPlacement(&mut placement)
+            .map_err(|e| format!("GetWindowPlacement failed with error: {e}"))?;
+
+        Ok(SavedPlacement {
+            flags: placement.flags.raw(),
+            show_cmd: placement.showCmd.raw(),
+            min_position: (placement.ptMinPosition.x, placement.ptMinPosition.y),
+            max_position: (placement.ptMaxPosition.x, placement.ptMaxPosition.y),
+            normal_rect: Rect::from(placement.rcNormalPosition),
+        })
+    }
+
+    fn set_placement(&self, hwnd: isize, saved: &SavedPlacement) -> Result<(), String> {
+        let mut placement = WINDOWPLACEMENT::default();
+        placement.flags = unsafe { WPF::from_raw(saved.flags) };
+        placement.showCmd = unsafe { SW::from_raw(saved.show_cmd) };
+        placement.ptMinPosition = POINT::with(saved.min_position.0, saved.min_position.1);
+        placement.ptMaxPosition = POINT::with(saved.max_position.0, saved.max_position.1);
+        placement.rcNormalPosition = RECT::from(saved.normal_rect);
+
+        handle(hwnd)
+            .SetWindowPlacement(&placement)
+            .map_err(|e| format!("SetWindowPlacement failed with error: {e}"))
+    }
+
+    fn previous_window(&self, hwnd: isize) -> Option<isize> {
+        handle(hwnd)
+            .GetWindow(GW::HWNDPREV)
+            .map(|prev| prev.ptr() as isize)
+    }
+
+    fn monitors(&self) -> Vec<Monitor> {
+        let mut monitors = Vec::new();
+
+        HDC::NULL
+            .EnumDisplayMonitors(None, |hmonitor, _, _| -> bool {
+                match hmonitor.GetMonitorInfo() {
+                    Ok(info) => monitors.push(Monitor {
+                        device_name: info.szDevice(),
+                        rect: Rect::from(info.rcMonitor),
+                        work_area: Rect::from(info.rcWork),
+                        is_primary: info.dwFlags.has(MONITORINFOF::PRIMARY),
+                    }),
+                    Err(e) => eprintln!("GetMonitorInfo failed: {e}"),
+                }
+
+                // Return true to continue enumerating
+                true
+            })
+            .map_err(|e| eprintln!("EnumDisplayMonitors failed: {e}"))
+            .ok();
+
+        monitors
+    }
+
+    fn icon(&self, hwnd: isize) -> Option<HICON> {
+        let hwnd = handle(hwnd);
+
+        // Try multiple methods to get the window icon
+        // - Try each method in order until one returns a valid icon
+        // - Each method is a closure that takes a &HWND and returns an HICON
+        unsafe {
+            [
+                /* Method 1: WM_GETICON Message */
+                |hwnd: &HWND| {
+                    HICON::from_ptr(hwnd.SendMessage(WndMsg::new(
+                        WM::GETICON,
+                        ICON_SZ::SMALL.raw() as usize,
+                        0,
+                    )) as *mut _)
+                },
+                /* Method 2: Use GetClassLongPtr to get the small icon */
+                // See https://learn.microsoft.com/en-us/windows/win32/winmsg/wm-geticon#remarks
+                |hwnd: &HWND| HICON::from_ptr(hwnd.GetClassLongPtr(GCLP::HICONSM) as *mut _),
+                /* Method 3: Use GetClassLongPtr to get the large icon */
+                |hwnd: &HWND| HICON::from_ptr(hwnd.GetClassLongPtr(GCLP::HICON) as *mut _),
+            ]
+        }
+        .into_iter()
+        .find_map(|func| match func(&hwnd) {
+            // If the icon is valid, return it, otherwise continue to the next method
+            icon if icon != HICON::NULL && icon != HICON::INVALID => Some(icon),
+            _ => None,
+        })
+    }
+}
@@ -0,0 +1,241 @@
+use core::sync::atomic::{AtomicIsize, Ordering};
+use std::sync::RwLock;
+
+use winsafe::co::{EVENT, GA, OBJID, WINEVENT, WM};
+use winsafe::msg::WndMsg;
+use winsafe::prelude::Handle as _;
+use winsafe::{HWINEVENTHOOK, HWND, SetWinEventHook};
+
+use crate::locks::{read, write};
+
+/// Raw handle of the window notified of window changes, or 0 if no hooks are installed
+static NOTIFY_HWND: AtomicIsize = AtomicIsize::new(0);
+
+/// Ids of the processes owning the windows whose changes are forwarded
+static TRACKED_PROCESSES: RwLock<Vec<u32>> = RwLock::new(Vec::new());
+
+/// Raw handle of the window notified of window list changes, or 0 if no hooks are installed
+static LIST_NOTIFY_HWND: AtomicIsize = AtomicIsize::new(0);
+
+/// The events signaling that a window may have reverted its fullscreen state
+///
+/// There is no event for style changes, so the state change and minimization events stand in for them.
+/// Other style changes are caught by the periodic check of the watched windows.
+const EVENTS: [EVENT; 4] = [
+    EVENT::OBJECT_LOCATIONCHANGE,
+    EVENT::OBJECT_STATECHANGE,
+    EVENT::SYSTEM_FOREGROUND,
+    EVENT::SYSTEM_MINIMIZEEND,
+];
+
+/// The events signaling that a window may have been added to or removed from the window list, or renamed
+const LIST_EVENTS: [EVENT; 5] = [
+    EVENT::OBJECT_CREATE,
+    EVENT::OBJECT_DESTROY,
+    EVENT::OBJECT_SHOW,
+    EVENT::OBJECT_HIDE,
+    EVENT::OBJECT_NAMECHANGE,
+];
+
+/// Function to get the message posted to the notified window when a top-level window changed
+///
+/// The `wParam` of the message is the raw handle of the window that changed.
+pub fn changed_message() -> WM {
+    unsafe { WM::from_raw(WM::APP.raw() + 2) }
+}
+
+/// Function to get the message posted to the notified window when the window list may have changed
+///
+/// The `wParam` of the message is the raw handle of the window that was opened, closed, shown, hidden or renamed.
+pub fn list_changed_message() -> WM {
+    unsafe { WM::from_raw(WM::APP.raw() + 3) }
+}
+
+/// Hooks notifying a window when a tracked window is moved, resized, restyled or brought to the foreground
+///
+/// Only the windows of the processes passed to [`WindowChangeHooks::track`] are reported.
+/// The hooks are removed when this is dropped.
+pub struct WindowChangeHooks {
+    hooks: Vec<HWINEVENTHOOK>,
+}
+
+impl WindowChangeHooks {
+    /// Function to install the hooks
+    /// # Arguments
+    /// * `notify` - The window the change messages are posted to
+    /// # Returns
+    /// * The hooks, or an error message if they could not be installed
+    ///
+    /// The hooks run out of context, so they are called on this thread while it processes messages.
+    pub fn install(notify: &HWND) -> Result<Self, String> {
+        NOTIFY_HWND.store(notify.ptr() as isize, Ordering::Relaxed);
+
+        let mut hooks = Self { hooks: Vec::new() };
+        set_hooks(&mut hooks.hooks, &EVENTS, win_event_proc)?;
+
+        Ok(hooks)
+    }
+
+    /// Function to choose the windows whose changes are reported
+    /// # Arguments
+    /// * `process_ids` - The ids of the processes owning the tracked windows
+    ///
+    /// Every window of these processes is reported, the notified window still has to check which window changed.
+    pub fn track(&self, mut process_ids: Vec<u32>) {
+        process_ids.sort_unstable();
+        process_ids.dedup();
+        *write(&TRACKED_PROCESSES) = process_ids;
+    }
+}
+
+impl Drop for WindowChangeHooks {
+    fn drop(&mut self) {
+        unhook(&mut self.hooks);
+        NOTIFY_HWND.store(0, Ordering::Relaxed);
+        write(&TRACKED_PROCESSES).clear();
+    }
+}
+
+/// Hooks notifying a window when any top-level window is opened, closed, shown, hidden or renamed
+///
+/// The hooks are removed when this is dropped.
+pub struct WindowListHooks {
+    hooks: Vec<HWINEVENTHOOK>,
+}
+
+impl WindowListHooks {
+    /// Function to install the hooks
+    /// # Arguments
+    /// * `notify` - The window the list change messages are posted to
+    /// # Returns
+    /// * The hooks, or an error message if they could not be installed
+    ///
+    /// The hooks run out of context, so they are called on this thread while it processes messages.
+    pub fn install(notify: &HWND) -> Result<Self, String> {
+        LIST_NOTIFY_HWND.store(notify.ptr() as isize, Ordering::Relaxed);
+
+        let mut hooks = Self { hooks: Vec::new() };
+        set_hooks(&mut hooks.hooks, &LIST_EVENTS, list_event_proc)?;
+
+        Ok(hooks)
+    }
+}
+
+impl Drop for WindowListHooks {
+    fn drop(&mut self) {
+        unhook(&mut self.hooks);
+        LIST_NOTIFY_HWND.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Function to hook events, one hook per event
+/// # Arguments
+/// * `hooks` - The installed hooks, which the new hooks are added to so that they are removed if a later one fails
+/// * `events` - The events to hook
+/// * `proc` - The function called for the events
+/// # Returns
+/// * An error message if a hook could not be installed
+fn set_hooks(
+    hooks: &mut Vec<HWINEVENTHOOK>,
+    events: &[EVENT],
+    proc: extern "system" fn(HWINEVENTHOOK, EVENT, HWND, OBJID, i32, u32, u32),
+) -> Result<(), String> {
+    for &event in events {
+        let hook = SetWinEventHook(
+            event,
+            event,
+            None,
+            proc,
+            0,
+            0,
+            WINEVENT::OUTOFCONTEXT | WINEVENT::SKIPOWNPROCESS,
+        )
+        .map_err(|e| format!("SetWinEventHook failed with error: {e}"))?;
+        hooks.push(hook);
+    }
+
+    Ok(())
+}
+
+/// Function to remove hooks
+fn unhook(hooks: &mut Vec<HWINEVENTHOOK>) {
+    for hook in hooks.drain(..) {
+        hook.UnhookWinEvent()
+            .map_err(|e| eprintln!("UnhookWinEvent failed: {e}"))
+            .ok();
+    }
+}
+
+/// Function to post a message about a window to the notified window
+/// # Arguments
+/// * `notify` - The raw handle of the notified window, or 0 if no hooks are installed
+/// * `message` - The message
+/// * `hwnd` - The window the message is about
+fn post(notify: isize, message: WM, hwnd: &HWND) {
+    if notify == 0 {
+        return;
+    }
+
+    let notify = unsafe { HWND::from_ptr(notify as *mut _) };
+    unsafe {
+        notify
+            .PostMessage(WndMsg::new(message, hwnd.ptr() as usize, 0))
+            .map_err(|e| eprintln!("Failed to forward a window change - PostMessage failed: {e}"))
+            .ok();
+    }
+}
+
+/// Function called by the system for every hooked window change event
+///
+/// Only events about whole windows of tracked processes are forwarded,
+/// as the hooks also report changes of carets, cursors, controls and every other window.
+extern "system" fn win_event_proc(
+    _hook: HWINEVENTHOOK,
+    _event: EVENT,
+    hwnd: HWND,
+    id_object: OBJID,
+    _id_child: i32,
+    _event_thread: u32,
+    _event_time: u32,
+) {
+    if id_object != OBJID::WINDOW || hwnd == HWND::NULL {
+        return;
+    }
+    let (_, process_id) = hwnd.GetWindowThreadProcessId();
+    if read(&TRACKED_PROCESSES).binary_search(&process_id).is_err() {
+        return;
+    }
+
+    post(
+        NOTIFY_HWND.load(Ordering::Relaxed),
+        changed_message(),
+        &hwnd,
+    );
+}
+
+/// Function called by the system for every hooked window list event
+///
+/// Only events about top-level windows are forwarded, as child windows are created and renamed all the time.
+/// A destroyed window cannot be checked anymore, so those events are always forwarded.
+extern "system" fn list_event_proc(
+    _hook: HWINEVENTHOOK,
+    event: EVENT,
+    hwnd: HWND,
+    id_object: OBJID,
+    _id_child: i32,
+    _event_thread: u32,
+    _event_time: u32,
+) {
+    if id_object != OBJID::WINDOW || hwnd == HWND::NULL {
+        return;
+    }
+    if event != EVENT::OBJECT_DESTROY && hwnd.GetAncestor(GA::ROOT).as_ref() != Some(&hwnd) {
+        return;
+    }
+
+    post(
+        LIST_NOTIFY_HWND.load(Ordering::Relaxed),
+        list_changed_message(),
+        &hwnd,
+    );
+}
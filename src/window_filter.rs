@@ -0,0 +1,223 @@
+use core::cmp::Reverse;
+
+use crate::window_info::WindowInfo;
+
+/// Score of a text equal to the query
+const EXACT_SCORE: u32 = 1000;
+/// Score of a text starting with the query
+const PREFIX_SCORE: u32 = 900;
+/// Score of a text containing the query at the start of a word
+const WORD_SCORE: u32 = 800;
+/// Score of a text containing the query anywhere else
+const SUBSTRING_SCORE: u32 = 700;
+/// Score of a text containing the characters of the query in order, with nothing in between
+///
+/// Each character between the matched characters lowers the score, down to [`MIN_FUZZY_SCORE`].
+const FUZZY_SCORE: u32 = 400;
+/// Lowest score of a text containing the characters of the query in order
+const MIN_FUZZY_SCORE: u32 = 100;
+
+/// Function to score how well a text matches a filter query
+/// # Arguments
+/// * `query` - The query typed by the user
+/// * `text` - The text, such as a window title
+/// # Returns
+/// * A higher score for better matches, or `None` if the text does not match
+///
+/// Matches ignore case. A text matches if it contains the query, or if it contains the characters of the query
+/// in order (e.g. `ffx` matches `Final Fantasy X`), which scores lower the more characters are in between.
+pub fn match_score(query: &str, text: &str) -> Option<u32> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+
+    if query.is_empty() {
+        return Some(0);
+    }
+    if text == query {
+        return Some(EXACT_SCORE);
+    }
+    if text.starts_with(&query) {
+        return Some(PREFIX_SCORE);
+    }
+
+    // Prefer a match at the start of a word over a match inside a word
+    let mut contains = false;
+    for start in 0..text.len() {
+        if text
+            .get(start..)
+            .is_some_and(|rest| rest.starts_with(&query))
+        {
+            let word_start = start
+                .checked_sub(1)
+                .and_then(|prev| text.get(prev))
+                .is_none_or(|prev| !prev.is_alphanumeric());
+            if word_start {
+                return Some(WORD_SCORE);
+            }
+            contains = true;
+        }
+    }
+    if contains {
+        return Some(SUBSTRING_SCORE);
+    }
+
+    fuzzy_score(&query, &text)
+}
+
+/// Function to score a text containing the characters of a query in order
+/// # Arguments
+/// * `query` - The lowercase characters of the query
+/// * `text` - The lowercase characters of the text
+/// # Returns
+/// * The score, or `None` if the text does not contain the characters of the query in order
+fn fuzzy_score(query: &[char], text: &[char]) -> Option<u32> {
+    let mut chars = text.iter().enumerate();
+    let mut first = None;
+    let mut last = 0;
+    for wanted in query {
+        let (index, _) = chars.find(|(_, c)| *c == wanted)?;
+        first.get_or_insert(index);
+        last = index;
+    }
+
+    // The characters skipped between the first and the last matched characters
+    let skipped = (last + 1 - first.unwrap_or(0)).saturating_sub(query.len());
+
+    Some(
+        FUZZY_SCORE
+            .saturating_sub(u32::try_from(skipped).unwrap_or(u32::MAX))
+            .max(MIN_FUZZY_SCORE),
+    )
+}
+
+/// Function to score how well a window matches a filter query
+/// # Arguments
+/// * `query` - The query typed by the user
+/// * `window` - The window, whose title, executable name and class name are matched
+/// # Returns
+/// * The score of the best matching property, or `None` if no property matches
+pub fn window_score(query: &str, window: &WindowInfo) -> Option<u32> {
+    [
+        Some(window.title.as_str()),
+        window.exe_name(),
+        Some(window.class_name.as_str()),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|text| !text.is_empty())
+    .filter_map(|text| match_score(query, text))
+    .max()
+}
+
+/// Function to keep the windows matching a filter query, from the best to the worst match
+/// # Arguments
+/// * `query` - The query typed by the user, where leading and trailing spaces are ignored
+/// * `windows` - The windows, in the order they are listed without a filter
+/// # Returns
+/// * The matching windows, where windows matching equally well keep their order,
+///   or all windows if the query is empty
+pub fn filter(query: &str, windows: Vec<WindowInfo>) -> Vec<WindowInfo> {
+    let query = query.trim();
+    if query.is_empty() {
+        return windows;
+    }
+
+    let mut matches: Vec<(u32, WindowInfo)> = windows
+        .into_iter()
+        .filter_map(|window| window_score(query, &window).map(|score| (score, window)))
+        .collect();
+    matches.sort_by_key(|&(score, _)| Reverse(score));
+
+    matches.into_iter().map(|(_, window)| window).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(hwnd: isize, title: &str, exe_path: &str, class_name: &str) -> WindowInfo {
+        WindowInfo {
+            hwnd,
+            title: title.to_owned(),
+            class_name: class_name.to_owned(),
+            exe_path: Some(exe_path.to_owned()),
+            ..WindowInfo::default()
+        }
+    }
+
+    fn handles(windows: &[WindowInfo]) -> Vec<isize> {
+        windows.iter().map(|window| window.hwnd).collect()
+    }
+
+    #[test]
+    fn scores_better_matches_higher() {
+        assert_eq!(match_score("elden ring", "ELDEN RING"), Some(EXACT_SCORE));
+        assert_eq!(match_score("elden", "Elden Ring"), Some(PREFIX_SCORE));
+        assert_eq!(match_score("ring", "Elden Ring"), Some(WORD_SCORE));
+        assert_eq!(match_score("den", "Elden Ring"), Some(SUBSTRING_SCORE));
+        assert_eq!(
+            match_score("ffx", "Final Fantasy X"),
+            Some(FUZZY_SCORE - 12)
+        );
+        assert_eq!(match_score("xyz", "Elden Ring"), None);
+    }
+
+    #[test]
+    fn a_word_match_is_found_after_a_match_inside_a_word() {
+        assert_eq!(match_score("art", "Smart Art"), Some(WORD_SCORE));
+        assert_eq!(match_score("art", "Smart-art"), Some(WORD_SCORE));
+    }
+
+    #[test]
+    fn fuzzy_matches_score_lower_with_more_characters_in_between() {
+        let close = match_score("abc", "a-b-c").unwrap();
+        let far = match_score("abc", "a---b---c").unwrap();
+        assert_eq!(close, FUZZY_SCORE - 2);
+        assert!(far < close);
+
+        let spread = format!("a{}b", " ".repeat(1000));
+        assert_eq!(match_score("ab", &spread), Some(MIN_FUZZY_SCORE));
+        assert_eq!(match_score("ba", "a-b"), None);
+    }
+
+    #[test]
+    fn an_empty_query_matches_everything() {
+        assert_eq!(match_score("", "Anything"), Some(0));
+        assert_eq!(match_score("", ""), Some(0));
+    }
+
+    #[test]
+    fn windows_are_scored_by_their_best_property() {
+        let game = window(1, "Untitled", r"C:\Games\eldenring.exe", "ELDEN RING™");
+        assert_eq!(window_score("eldenring.exe", &game), Some(EXACT_SCORE));
+        assert_eq!(window_score("elden", &game), Some(PREFIX_SCORE));
+        assert_eq!(window_score("zzz", &game), None);
+    }
+
+    #[test]
+    fn filter_ranks_windows_and_keeps_the_order_of_equal_matches() {
+        let windows = vec![
+            window(1, "Notes - Editor", r"C:\Tools\editor.exe", "EditorWindow"),
+            window(2, "Steam", r"C:\Steam\steam.exe", "SDL_app"),
+            window(3, "Starfield", r"C:\Games\Starfield.exe", "Starfield"),
+            window(
+                4,
+                "Settings",
+                r"C:\Windows\settings.exe",
+                "ApplicationFrameWindow",
+            ),
+            window(
+                5,
+                "Stardew Valley",
+                r"C:\Games\Stardew Valley.exe",
+                "SDL_app",
+            ),
+        ];
+
+        assert_eq!(handles(&filter("  sta ", windows.clone())), vec![3, 5, 2]);
+        assert_eq!(handles(&filter("steam", windows.clone())), vec![2]);
+        assert_eq!(handles(&filter("sdl", windows.clone())), vec![2, 5]);
+        assert!(filter("nothing matches", windows.clone()).is_empty());
+        assert_eq!(handles(&filter(" ", windows)), vec![1, 2, 3, 4, 5]);
+    }
+}
@@ -0,0 +1,275 @@
+use std::ffi::OsStr;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::geometry::Rect;
+
+/// Names of the `WS` style bits included in window records
+const STYLE_NAMES: [(u32, &str); 10] = [
+    (0x8000_0000, "POPUP"),
+    (0x4000_0000, "CHILD"),
+    (0x2000_0000, "MINIMIZE"),
+    (0x1000_0000, "VISIBLE"),
+    (0x0800_0000, "DISABLED"),
+    (0x0100_0000, "MAXIMIZE"),
+    (0x00C0_0000, "CAPTION"),
+    (0x0080_0000, "BORDER"),
+    (0x0008_0000, "SYSMENU"),
+    (0x0004_0000, "THICKFRAME"),
+];
+
+/// Names of the `WS_EX` extended style bits included in window records
+const EX_STYLE_NAMES: [(u32, &str); 7] = [
+    (0x0000_0008, "TOPMOST"),
+    (0x0000_0020, "TRANSPARENT"),
+    (0x0000_0080, "TOOLWINDOW"),
+    (0x0000_0100, "WINDOWEDGE"),
+    (0x0000_0200, "CLIENTEDGE"),
+    (0x0004_0000, "APPWINDOW"),
+    (0x0008_0000, "LAYERED"),
+];
+
+/// A top-level window, as shown in the window list
+///
+/// This is also the record printed by `fullscreenizer list --json`, so renaming fields is a breaking change.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct WindowInfo {
+    /// The raw handle of the window
+    pub hwnd: isize,
+    /// The title of the window
+    pub title: String,
+    /// The class name of the window
+    pub class_name: String,
+    /// The ID of the process that owns the window
+    pub process_id: u32,
+    /// The full path of the executable of the process that owns the window,
+    /// or `None` if it could not be queried
+    pub exe_path: Option<String>,
+    /// The rectangle of the window, in virtual-desktop coordinates
+    pub rect: Rect,
+    /// The device name of the monitor the window is on, or `None` if it could not be queried
+    pub monitor: Option<String>,
+    /// The raw `WS` style bits
+    pub style: u32,
+    /// The raw `WS_EX` extended style bits
+    pub ex_style: u32,
+    /// Whether the window is visible
+    pub visible: bool,
+}
+
+impl WindowInfo {
+    /// Function to get the file name of the executable of the process that owns the window
+    /// # Returns
+    /// * The file name (e.g. `game.exe`), or `None` if the executable could not be queried
+    pub fn exe_name(&self) -> Option<&str> {
+        self.exe_path.as_deref().map(file_name)
+    }
+}
+
+/// A window record, as printed by `fullscreenizer list --json`
+#[derive(Serialize)]
+struct WindowRecord<'a> {
+    #[serde(flatten)]
+    info: &'a WindowInfo,
+    exe_name: Option<&'a str>,
+    style_flags: Vec<&'static str>,
+    ex_style_flags: Vec<&'static str>,
+}
+
+impl<'a> From<&'a WindowInfo> for WindowRecord<'a> {
+    fn from(info: &'a WindowInfo) -> Self {
+        Self {
+            info,
+            exe_name: info.exe_name(),
+            style_flags: flag_names(info.style, &STYLE_NAMES),
+            ex_style_flags: flag_names(info.ex_style, &EX_STYLE_NAMES),
+        }
+    }
+}
+
+/// Function to get the names of the flags set in a style
+/// # Arguments
+/// * `style` - The raw style bits
+/// * `names` - The known flags and their names
+/// # Returns
+/// * The names of the known flags that are fully set in the style
+fn flag_names(style: u32, names: &[(u32, &'static str)]) -> Vec<&'static str> {
+    names
+        .iter()
+        .filter(|&&(flag, _)| style & flag == flag)
+        .map(|&(_, name)| name)
+        .collect()
+}
+
+/// Function to get the file name part of a Windows path
+/// # Arguments
+/// * `path` - The path (e.g. `C:\Games\game.exe`)
+/// # Returns
+/// * The file name (e.g. `game.exe`)
+pub fn file_name(path: &str) -> &str {
+    path.rsplit(['\\', '/']).next().unwrap_or(path)
+}
+
+/// Function to check whether an executable name matches a name given by the user
+/// # Arguments
+/// * `exe_name` - The file name of the executable (e.g. `game.exe`)
+/// * `name` - The name given by the user, where the `.exe` extension is optional
+/// # Returns
+/// * `true` if the names are equal, ignoring case
+pub fn exe_name_matches(exe_name: &str, name: &str) -> bool {
+    exe_name.eq_ignore_ascii_case(name) || strip_exe_extension(exe_name).eq_ignore_ascii_case(name)
+}
+
+/// Function to remove the `.exe` extension from an executable name, if present
+pub fn strip_exe_extension(exe_name: &str) -> &str {
+    let path = Path::new(exe_name);
+    if path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("exe"))
+    {
+        path.file_stem().and_then(OsStr::to_str).unwrap_or(exe_name)
+    } else {
+        exe_name
+    }
+}
+
+/// Function to serialize windows as a JSON array
+/// # Arguments
+/// * `windows` - The windows
+/// # Returns
+/// * The pretty-printed JSON array, or an error message
+pub fn to_json(windows: &[WindowInfo]) -> Result<String, String> {
+    let records: Vec<WindowRecord> = windows.iter().map(WindowRecord::from).collect();
+    serde_json::to_string_pretty(&records).map_err(|e| format!("Failed to serialize windows: {e}"))
+}
+
+/// Function to serialize windows as newline-delimited JSON
+/// # Arguments
+/// * `windows` - The windows
+/// # Returns
+/// * One compact JSON object per window, each followed by a newline, or an error message
+pub fn to_ndjson(windows: &[WindowInfo]) -> Result<String, String> {
+    windows.iter().try_fold(String::new(), |mut out, window| {
+        out.push_str(
+            &serde_json::to_string(&WindowRecord::from(window))
+                .map_err(|e| format!("Failed to serialize windows: {e}"))?,
+        );
+        out.push('\n');
+        Ok(out)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, json};
+
+    use super::*;
+
+    /// Windows as they could be enumerated on a desktop with two monitors
+    fn fixture() -> Vec<WindowInfo> {
+        vec![
+            WindowInfo {
+                hwnd: 0x0002_0A3C,
+                title: "Super Game".to_owned(),
+                class_name: "UnityWndClass".to_owned(),
+                process_id: 4242,
+                exe_path: Some(r"C:\Games\Super Game\SuperGame.exe".to_owned()),
+                rect: Rect::new(-1600, 100, -800, 700),
+                monitor: Some(r"\\.\DISPLAY2".to_owned()),
+                style: 0x14CF_0000,
+                ex_style: 0x0000_0108,
+                visible: true,
+            },
+            WindowInfo {
+                hwnd: 0x0003_0B10,
+                title: String::new(),
+                class_name: "Shell_TrayWnd".to_owned(),
+                process_id: 512,
+                exe_path: None,
+                rect: Rect::new(0, 1040, 1920, 1080),
+                monitor: None,
+                style: 0x8400_0000,
+                ex_style: 0x0000_0088,
+                visible: false,
+            },
+        ]
+    }
+
+    #[test]
+    fn records_add_the_exe_name_and_style_flags() {
+        let windows = fixture();
+        let records: Value = serde_json::from_str(&to_json(&windows).unwrap()).unwrap();
+
+        assert_eq!(
+            records,
+            json!([
+                {
+                    "hwnd": 0x0002_0A3C,
+                    "title": "Super Game",
+                    "class_name": "UnityWndClass",
+                    "process_id": 4242,
+                    "exe_path": r"C:\Games\Super Game\SuperGame.exe",
+                    "rect": { "left": -1600, "top": 100, "right": -800, "bottom": 700 },
+                    "monitor": r"\\.\DISPLAY2",
+                    "style": 0x14CF_0000,
+                    "ex_style": 0x0000_0108,
+                    "visible": true,
+                    "exe_name": "SuperGame.exe",
+                    "style_flags": ["VISIBLE", "CAPTION", "BORDER", "SYSMENU", "THICKFRAME"],
+                    "ex_style_flags": ["TOPMOST", "WINDOWEDGE"],
+                },
+                {
+                    "hwnd": 0x0003_0B10,
+                    "title": "",
+                    "class_name": "Shell_TrayWnd",
+                    "process_id": 512,
+                    "exe_path": null,
+                    "rect": { "left": 0, "top": 1040, "right": 1920, "bottom": 1080 },
+                    "monitor": null,
+                    "style": 0x8400_0000_u32,
+                    "ex_style": 0x0000_0088,
+                    "visible": false,
+                    "exe_name": null,
+                    "style_flags": ["POPUP"],
+                    "ex_style_flags": ["TOPMOST", "TOOLWINDOW"],
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn ndjson_has_one_compact_record_per_line() {
+        let windows = fixture();
+        let text = to_ndjson(&windows).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert!(text.ends_with('\n'));
+        assert_eq!(lines.len(), windows.len());
+        let array: Value = serde_json::from_str(&to_json(&windows).unwrap()).unwrap();
+        for (line, record) in lines.iter().zip(array.as_array().unwrap()) {
+            assert!(!line.contains("\n  "));
+            assert_eq!(&serde_json::from_str::<Value>(line).unwrap(), record);
+        }
+        assert_eq!(to_ndjson(&[]), Ok(String::new()));
+    }
+
+    #[test]
+    fn flags_are_only_listed_when_fully_set() {
+        // CAPTION is BORDER plus DLGFRAME, so BORDER alone is not a caption
+        assert_eq!(flag_names(0x0080_0000, &STYLE_NAMES), ["BORDER"]);
+        assert!(flag_names(0, &STYLE_NAMES).is_empty());
+    }
+
+    #[test]
+    fn exe_names_are_matched_with_an_optional_extension() {
+        assert_eq!(file_name(r"C:\Games\game.exe"), "game.exe");
+        assert_eq!(file_name("/opt/games/game.exe"), "game.exe");
+        assert_eq!(file_name("game.exe"), "game.exe");
+        assert_eq!(strip_exe_extension("Game.EXE"), "Game");
+        assert_eq!(strip_exe_extension("launcher.bin"), "launcher.bin");
+        assert!(exe_name_matches("SuperGame.exe", "supergame"));
+        assert!(exe_name_matches("SuperGame.exe", "SUPERGAME.EXE"));
+        assert!(!exe_name_matches("SuperGame.exe", "super"));
+    }
+}
@@ -0,0 +1,500 @@
+use crate::exclusions::ExclusionList;
+use crate::geometry::Rect;
+use crate::scaling::Sizing;
+use crate::watchdog::{ActualState, DesiredState};
+use crate::window_info::{WindowInfo, file_name};
+use crate::window_state::SavedWindowState;
+use crate::window_system::{BORDERLESS_STYLE, TOPMOST_EX_STYLE, WindowSystem, ZOrder};
+
+/// Function to enumerate the top-level windows
+/// # Arguments
+/// * `system` - The system managing the windows
+/// * `exclusions` - The exclusions deciding which windows are not listed
+/// * `include_all` - Whether to also include invisible, untitled and excluded windows
+/// # Returns
+/// * The windows, in z-order
+pub fn enumerate_windows(
+    system: &impl WindowSystem,
+    exclusions: &ExclusionList,
+    include_all: bool,
+) -> Vec<WindowInfo> {
+    system
+        .windows()
+        .into_iter()
+        // Skip invisible windows
+        .filter(|&hwnd| include_all || system.is_visible(hwnd))
+        .map(|hwnd| window_info(system, hwnd))
+        .filter(|window| {
+            include_all
+                || exclusions.is_listed(&window.title, &window.class_name, window.exe_name())
+        })
+        .collect()
+}
+
+/// Function to get the windows shown in the window list, along with their titles
+/// # Arguments
+/// * `system` - The system managing the windows
+/// * `exclusions` - The exclusions deciding which windows are not listed
+/// # Returns
+/// * The raw handles and titles of the visible, titled and not excluded windows, in z-order
+///
+/// Unlike [`enumerate_windows`], only the properties needed to decide whether a window is listed are queried.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn listed_windows(
+    system: &impl WindowSystem,
+    exclusions: &ExclusionList,
+) -> Vec<(isize, String)> {
+    system
+        .windows()
+        .into_iter()
+        // Skip invisible windows
+        .filter(|&hwnd| system.is_visible(hwnd))
+        // Skip the windows whose title cannot be read
+        .filter_map(|hwnd| system.title(hwnd).ok().map(|title| (hwnd, title)))
+        .filter(|(hwnd, title)| is_listed(system, *hwnd, title, exclusions))
+        .collect()
+}
+
+/// Function to check whether a window should be listed
+/// # Arguments
+/// * `system` - The system managing the windows
+/// * `hwnd` - The raw handle of the window
+/// * `title` - The title of the window
+/// * `exclusions` - The exclusions deciding which windows are not listed
+/// # Returns
+/// * `true` if the window has a title and is not excluded
+///
+/// The executable of the window is only queried if an exclusion needs it.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn is_listed(
+    system: &impl WindowSystem,
+    hwnd: isize,
+    title: &str,
+    exclusions: &ExclusionList,
+) -> bool {
+    if title.is_empty() {
+        return false;
+    }
+
+    let class_name = system.class_name(hwnd).unwrap_or_default();
+    let exe_name = if exclusions.uses_exe() {
+        window_exe_name(system, hwnd)
+    } else {
+        None
+    };
+
+    !exclusions.is_excluded(title, &class_name, exe_name.as_deref())
+}
+
+/// Function to read the information about a window shown in the window list
+/// # Arguments
+/// * `system` - The system managing the windows
+/// * `hwnd` - The raw handle of the window
+/// # Returns
+/// * The information about the window, with empty or `None` fields for anything that could not be queried
+pub fn window_info(system: &impl WindowSystem, hwnd: isize) -> WindowInfo {
+    WindowInfo {
+        hwnd,
+        title: system.title(hwnd).unwrap_or_default(),
+        class_name: system.class_name(hwnd).unwrap_or_default(),
+        process_id: system.process_id(hwnd),
+        exe_path: system.exe_path(hwnd),
+        rect: system.rect(hwnd).unwrap_or_default(),
+        monitor: system.monitor_name(hwnd),
+        style: system.style(hwnd),
+        ex_style: system.ex_style(hwnd),
+        visible: system.is_visible(hwnd),
+    }
+}
+
+/// Function to get the executable name of the process that owns a window
+/// # Arguments
+/// * `system` - The system managing the windows
+/// * `hwnd` - The raw handle of the window
+/// # Returns
+/// * The file name of the executable (e.g. `game.exe`), or `None` if it could not be queried
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn window_exe_name(system: &impl WindowSystem, hwnd: isize) -> Option<String> {
+    system
+        .exe_path(hwnd)
+        .map(|path| file_name(&path).to_owned())
+}
+
+/// Function to read the state of a window that is needed to restore it later
+/// # Arguments
+/// * `system` - The system managing the windows
+/// * `hwnd` - The raw handle of the window
+/// # Returns
+/// * The current state of the window, or an error message
+pub fn capture_state(system: &impl WindowSystem, hwnd: isize) -> Result<SavedWindowState, String> {
+    let placement = system.placement(hwnd)?;
+
+    Ok(SavedWindowState {
+        hwnd,
+        process_id: system.process_id(hwnd),
+        class_name: system.class_name(hwnd)?,
+        title: system.title(hwnd).unwrap_or_default(),
+        style: system.style(hwnd),
+        ex_style: system.ex_style(hwnd),
+        placement,
+        // If there is no window above this one, it is at the top of the z-order
+        insert_after: system.previous_window(hwnd).unwrap_or(0),
+    })
+}
+
+/// Function to get the window a saved state was read from
+/// # Arguments
+/// * `system` - The system managing the windows
+/// * `state` - The saved state of the window
+/// # Returns
+/// * The raw handle of the window, or `None` if it was closed
+///
+/// Window handles are reused by the system, so the handle is only trusted
+/// if it still belongs to the same process and window class.
+pub fn find_window(system: &impl WindowSystem, state: &SavedWindowState) -> Option<isize> {
+    (system.is_window(state.hwnd)
+        && system.process_id(state.hwnd) == state.process_id
+        && system
+            .class_name(state.hwnd)
+            .is_ok_and(|class_name| class_name == state.class_name))
+    .then_some(state.hwnd)
+}
+
+/// Function to get where to put a window in the z-order
+/// # Arguments
+/// * `topmost` - Whether to apply the "stay on top" flag to the window
+const fn z_order(topmost: bool) -> ZOrder {
+    if topmost {
+        ZOrder::Topmost
+    } else {
+        ZOrder::Top
+    }
+}
+
+/// Function to compute the rectangle the client area of a window should cover once fullscreenized
+/// # Arguments
+/// * `system` - The system managing the windows
+/// * `hwnd` - The raw handle of the window, before it is fullscreenized
+/// * `monitor` - The full area of the target monitor, in virtual-desktop coordinates
+/// * `sizing` - How the window is sized on the monitor
+/// # Returns
+/// * The rectangle, in virtual-desktop coordinates, or an error message
+pub fn target_rect(
+    system: &impl WindowSystem,
+    hwnd: isize,
+    monitor: Rect,
+    sizing: &Sizing,
+) -> Result<Rect, String> {
+    Ok(sizing.target_rect(monitor, system.client_size(hwnd)?))
+}
+
+/// Function to remove the border of a window and move it over a rectangle
+/// # Arguments
+/// * `system` - The system managing the windows
+/// * `hwnd` - The raw handle of the window
+/// * `target` - The rectangle the window should cover, in virtual-desktop coordinates
+/// * `topmost` - Whether to apply the "stay on top" flag to the window
+/// # Returns
+/// * An error message if the window could not be modified
+pub fn fullscreenize(
+    system: &impl WindowSystem,
+    hwnd: isize,
+    target: Rect,
+    topmost: bool,
+) -> Result<(), String> {
+    // Set the window style
+    system.set_style(hwnd, BORDERLESS_STYLE);
+
+    // Get the window rectangle needed for the client area to cover the target
+    let rect = system.window_rect_for_client(hwnd, target)?;
+
+    // Set the window position
+    system.set_position(hwnd, Some(rect), z_order(topmost), true)
+}
+
+/// Function to move a window over a rectangle without changing its border
+/// # Arguments
+/// * `system` - The system managing the windows
+/// * `hwnd` - The raw handle of the window
+/// * `target` - The rectangle the window, including its border, should cover, in virtual-desktop coordinates
+/// * `topmost` - Whether to apply the "stay on top" flag to the window
+/// # Returns
+/// * An error message if the window could not be moved
+pub fn move_window(
+    system: &impl WindowSystem,
+    hwnd: isize,
+    target: Rect,
+    topmost: bool,
+) -> Result<(), String> {
+    system.set_position(hwnd, Some(target), z_order(topmost), false)
+}
+
+/// Function to read the current state of a window, as compared by the watchdog
+/// # Arguments
+/// * `system` - The system managing the windows
+/// * `hwnd` - The raw handle of the window
+/// # Returns
+/// * The current state of the window, or an error message
+pub fn actual_state(system: &impl WindowSystem, hwnd: isize) -> Result<ActualState, String> {
+    Ok(ActualState {
+        rect: system.rect(hwnd)?,
+        style: system.style(hwnd),
+        ex_style: system.ex_style(hwnd),
+    })
+}
+
+/// Function to read the state a window was just put in, so that the watchdog can keep it there
+/// # Arguments
+/// * `system` - The system managing the windows
+/// * `hwnd` - The raw handle of the window, right after it was fullscreenized
+/// * `topmost` - Whether the "stay on top" flag was applied
+/// # Returns
+/// * The state the window should stay in, or an error message
+pub fn desired_state(
+    system: &impl WindowSystem,
+    hwnd: isize,
+    topmost: bool,
+) -> Result<DesiredState, String> {
+    let actual = actual_state(system, hwnd)?;
+
+    Ok(DesiredState {
+        rect: actual.rect,
+        style: actual.style,
+        topmost,
+    })
+}
+
+/// Function to put a window back into the state it was fullscreenized into
+/// # Arguments
+/// * `system` - The system managing the windows
+/// * `hwnd` - The raw handle of the window
+/// * `desired` - The state the window should be in
+/// # Returns
+/// * An error message if the window could not be modified
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn reapply(
+    system: &impl WindowSystem,
+    hwnd: isize,
+    desired: &DesiredState,
+) -> Result<(), String> {
+    system.set_style(hwnd, desired.style);
+
+    // The window is not activated, so that fixing a window in the background does not steal the focus
+    system.set_position(hwnd, Some(desired.rect), z_order(desired.topmost), false)
+}
+
+/// Function to put a window back into the state it was in before it was fullscreenized
+/// # Arguments
+/// * `system` - The system managing the windows
+/// * `hwnd` - The raw handle of the window
+/// * `state` - The original state of the window
+/// # Returns
+/// * An error message if the window could not be restored
+pub fn restore(
+    system: &impl WindowSystem,
+    hwnd: isize,
+    state: &SavedWindowState,
+) -> Result<(), String> {
+    // Restore the original styles
+    system.set_style(hwnd, state.style);
+    system.set_ex_style(hwnd, state.ex_style);
+
+    // Restore the original size, position and show state
+    system.set_placement(hwnd, &state.placement)?;
+
+    // Restore the original z-order
+    // The placement does not include the frame, so the frame change is applied here too
+    if state.ex_style & TOPMOST_EX_STYLE != 0 {
+        return system.set_position(hwnd, None, ZOrder::Topmost, false);
+    }
+
+    // Remove the "stay on top" flag that may have been applied when fullscreenizing
+    system.set_position(hwnd, None, ZOrder::NoTopmost, false)?;
+
+    // Put the window back below the window that was above it, if that window still exists
+    // Placing the window after a topmost window would make it topmost, so those are skipped
+    let prev = state.insert_after;
+    if prev != 0 && system.is_window(prev) && system.ex_style(prev) & TOPMOST_EX_STYLE == 0 {
+        system.set_position(hwnd, None, ZOrder::After(prev), false)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_system::{BORDER_THICKNESS, FakeSystem, FakeWindow};
+    use crate::monitors::Monitor;
+
+    const MONITOR: Rect = Rect::new(0, 0, 1920, 1080);
+
+    /// Function to create a system with one monitor and these windows, the first one at the top
+    fn system(windows: Vec<FakeWindow>) -> FakeSystem {
+        let system = FakeSystem::new(vec![Monitor {
+            device_name: "\\\\.\\DISPLAY1".to_owned(),
+            rect: MONITOR,
+            work_area: Rect::new(0, 0, 1920, 1040),
+            is_primary: true,
+        }]);
+        for window in windows {
+            system.add_window(window);
+        }
+        system
+    }
+
+    fn desktop() -> FakeSystem {
+        let hidden = FakeWindow {
+            visible: false,
+            ..FakeWindow::new(2, "Hidden", r"C:\Games\hidden.exe")
+        };
+        system(vec![
+            FakeWindow::new(1, "Game", r"C:\Games\game.exe"),
+            hidden,
+            FakeWindow::new(3, "Program Manager", r"C:\Windows\explorer.exe"),
+            FakeWindow::new(4, "", r"C:\Games\game.exe"),
+            FakeWindow::new(5, "Launcher", r"C:\Games\launcher.exe"),
+        ])
+    }
+
+    fn handles(windows: &[WindowInfo]) -> Vec<isize> {
+        windows.iter().map(|window| window.hwnd).collect()
+    }
+
+    #[test]
+    fn enumerate_windows_skips_hidden_untitled_and_excluded_windows() {
+        let system = desktop();
+        let windows = enumerate_windows(&system, &ExclusionList::default(), false);
+        assert_eq!(handles(&windows), vec![1, 5]);
+
+        let game = windows.first().unwrap();
+        assert_eq!(game.title, "Game");
+        assert_eq!(game.exe_name(), Some("game.exe"));
+        assert_eq!(game.monitor.as_deref(), Some("\\\\.\\DISPLAY1"));
+    }
+
+    #[test]
+    fn enumerate_windows_includes_everything_on_request() {
+        let system = desktop();
+        let windows = enumerate_windows(&system, &ExclusionList::default(), true);
+        assert_eq!(handles(&windows), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn listed_windows_matches_enumerate_windows() {
+        let system = desktop();
+        let exclusions = ExclusionList::parse("exe exact launcher.exe").unwrap();
+
+        assert_eq!(
+            listed_windows(&system, &exclusions),
+            vec![(1, "Game".to_owned()), (3, "Program Manager".to_owned())]
+        );
+        assert_eq!(
+            handles(&enumerate_windows(&system, &exclusions, false)),
+            vec![1, 3]
+        );
+    }
+
+    #[test]
+    fn capture_state_records_the_window_above() {
+        let system = desktop();
+
+        let state = capture_state(&system, 3).unwrap();
+        assert_eq!(state.hwnd, 3);
+        assert_eq!(state.process_id, 3);
+        assert_eq!(state.class_name, "FakeWindowClass");
+        assert_eq!(state.title, "Program Manager");
+        assert_eq!(state.style, system.style(3));
+        assert_eq!(state.placement, system.placement(3).unwrap());
+        assert_eq!(state.insert_after, 2);
+
+        assert_eq!(capture_state(&system, 1).unwrap().insert_after, 0);
+        assert!(capture_state(&system, 9).is_err());
+    }
+
+    #[test]
+    fn fullscreenize_removes_the_border_and_covers_the_target() {
+        let system = desktop();
+        fullscreenize(&system, 5, MONITOR, true).unwrap();
+
+        let window = system.window(5).unwrap();
+        assert_eq!(window.style, BORDERLESS_STYLE);
+        assert_eq!(window.rect, MONITOR);
+        assert_eq!(system.client_size(5), Ok((1920, 1080)));
+        assert_ne!(window.ex_style & TOPMOST_EX_STYLE, 0);
+        assert!(window.activated);
+        assert_eq!(system.windows().first(), Some(&5));
+    }
+
+    #[test]
+    fn fullscreenize_fails_for_closed_windows() {
+        let system = desktop();
+        system.close_window(5);
+        assert!(fullscreenize(&system, 5, MONITOR, false).is_err());
+    }
+
+    #[test]
+    fn restore_undoes_fullscreenize() {
+        let system = desktop();
+        let original = system.window(3).unwrap();
+        let state = capture_state(&system, 3).unwrap();
+
+        fullscreenize(&system, 3, MONITOR, true).unwrap();
+        restore(&system, 3, &state).unwrap();
+
+        let restored = system.window(3).unwrap();
+        assert_eq!(restored.style, original.style);
+        assert_eq!(restored.ex_style, original.ex_style);
+        assert_eq!(restored.rect, original.rect);
+        assert_eq!(
+            system.client_size(3),
+            Ok((
+                original.rect.width() - 2 * BORDER_THICKNESS,
+                original.rect.height() - 2 * BORDER_THICKNESS
+            ))
+        );
+        assert!(!restored.activated);
+        assert_eq!(system.windows(), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn restore_keeps_topmost_windows_on_top() {
+        let system = desktop();
+        system.set_ex_style(3, TOPMOST_EX_STYLE);
+        let state = capture_state(&system, 3).unwrap();
+
+        fullscreenize(&system, 3, MONITOR, false).unwrap();
+        restore(&system, 3, &state).unwrap();
+
+        assert_ne!(system.ex_style(3) & TOPMOST_EX_STYLE, 0);
+    }
+
+    #[test]
+    fn restore_leaves_the_window_at_the_top_if_the_window_above_was_closed() {
+        let system = desktop();
+        let state = capture_state(&system, 3).unwrap();
+
+        fullscreenize(&system, 3, MONITOR, true).unwrap();
+        system.close_window(2);
+        restore(&system, 3, &state).unwrap();
+
+        assert_eq!(system.ex_style(3) & TOPMOST_EX_STYLE, 0);
+        assert_eq!(system.windows(), vec![3, 1, 4, 5]);
+    }
+
+    #[test]
+    fn find_window_rejects_reused_handles() {
+        let system = desktop();
+        let state = capture_state(&system, 5).unwrap();
+        assert_eq!(find_window(&system, &state), Some(5));
+
+        system.close_window(5);
+        assert_eq!(find_window(&system, &state), None);
+
+        system.add_window(FakeWindow {
+            process_id: 42,
+            ..FakeWindow::new(5, "Launcher", r"C:\Games\launcher.exe")
+        });
+        assert_eq!(find_window(&system, &state), None);
+    }
+}
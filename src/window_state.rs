@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+
+use crate::geometry::Rect;
+
+/// The state of a window before it was fullscreenized
+///
+/// Everything is stored as raw values so that the state can outlive the window handle it was read from.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedWindowState {
+    /// The raw handle of the window
+    pub hwnd: isize,
+    /// The ID of the process that owns the window
+    ///
+    /// Window handles are reused by the system, so this is used to check that a handle still refers to the same window.
+    pub process_id: u32,
+    /// The class name of the window
+    pub class_name: String,
+    /// The title of the window
+    pub title: String,
+    /// The raw `WS` style bits
+    pub style: u32,
+    /// The raw `WS_EX` extended style bits
+    pub ex_style: u32,
+    /// The window's placement, as returned by `GetWindowPlacement`
+    pub placement: SavedPlacement,
+    /// The raw handle of the window directly above this one in the z-order, or 0 if it was the topmost window
+    pub insert_after: isize,
+}
+
+/// The fields of a `WINDOWPLACEMENT` needed to restore a window
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedPlacement {
+    /// The raw `WPF` flags
+    pub flags: u32,
+    /// The raw `SW` show command
+    pub show_cmd: i32,
+    /// The position of the window when minimized
+    pub min_position: (i32, i32),
+    /// The position of the window when maximized
+    pub max_position: (i32, i32),
+    /// The rectangle of the window when restored, in workspace coordinates
+    pub normal_rect: Rect,
+}
+
+/// The windows modified by Fullscreenizer, along with their original states
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ModifiedWindows {
+    states: Vec<SavedWindowState>,
+}
+
+impl ModifiedWindows {
+    /// Function to record the original state of a window before it is modified
+    /// # Arguments
+    /// * `state` - The original state of the window
+    /// # Returns
+    /// * `true` if the state was recorded, `false` if the window was already modified
+    ///
+    /// Modifying an already modified window keeps the first recorded state,
+    /// so that restoring it always goes back to how the window was before Fullscreenizer touched it.
+    pub fn record(&mut self, state: SavedWindowState) -> bool {
+        if self.get(state.hwnd).is_some() {
+            return false;
+        }
+
+        self.states.push(state);
+        true
+    }
+
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub const fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &SavedWindowState> {
+        self.states.iter()
+    }
+
+    /// Function to get the original state of a modified window
+    /// # Arguments
+    /// * `hwnd` - The raw handle of the window
+    pub fn get(&self, hwnd: isize) -> Option<&SavedWindowState> {
+        self.states.iter().find(|state| state.hwnd == hwnd)
+    }
+
+    /// Function to stop tracking a window, usually after it was restored
+    /// # Arguments
+    /// * `hwnd` - The raw handle of the window
+    /// # Returns
+    /// * The original state of the window, or `None` if the window was not modified
+    pub fn take(&mut self, hwnd: isize) -> Option<SavedWindowState> {
+        let index = self.states.iter().position(|state| state.hwnd == hwnd)?;
+        Some(self.states.remove(index))
+    }
+}
@@ -0,0 +1,109 @@
+use crate::geometry::Rect;
+use crate::monitors::Monitor;
+use crate::window_state::SavedPlacement;
+
+/// `WS_POPUP | WS_VISIBLE`, the style of a fullscreenized window
+pub const BORDERLESS_STYLE: u32 = 0x8000_0000 | 0x1000_0000;
+
+/// `WS_EX_TOPMOST` extended style bit
+pub const TOPMOST_EX_STYLE: u32 = 0x0000_0008;
+
+/// Where a window is put in the z-order when it is moved
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ZOrder {
+    /// At the top, keeping the "stay on top" flag as it is
+    Top,
+    /// At the top, with the "stay on top" flag
+    Topmost,
+    /// Below the windows that stay on top, without the "stay on top" flag
+    NoTopmost,
+    /// Directly below the window with this raw handle
+    After(isize),
+}
+
+/// The operations Fullscreenizer needs from the system managing the windows
+///
+/// Windows are identified by their raw handle, so that the code using this does not depend on the system.
+/// Functions reading a property return an empty value if the window was closed in the meantime.
+pub trait WindowSystem {
+    /// A window icon, as shown in the window list
+    type Icon;
+
+    /// Function to get the top-level windows, in z-order
+    fn windows(&self) -> Vec<isize>;
+
+    /// Function to check whether a handle still refers to a window
+    fn is_window(&self, hwnd: isize) -> bool;
+
+    /// Function to check whether a window is visible
+    fn is_visible(&self, hwnd: isize) -> bool;
+
+    /// Function to get the title of a window
+    fn title(&self, hwnd: isize) -> Result<String, String>;
+
+    /// Function to get the class name of a window
+    fn class_name(&self, hwnd: isize) -> Result<String, String>;
+
+    /// Function to get the ID of the process that owns a window
+    fn process_id(&self, hwnd: isize) -> u32;
+
+    /// Function to get the full path of the executable of the process that owns a window
+    fn exe_path(&self, hwnd: isize) -> Option<String>;
+
+    /// Function to get the rectangle of a window, in virtual-desktop coordinates
+    fn rect(&self, hwnd: isize) -> Result<Rect, String>;
+
+    /// Function to get the width and height of the client area of a window, which excludes its border
+    fn client_size(&self, hwnd: isize) -> Result<(i32, i32), String>;
+
+    /// Function to get the device name of the monitor a window is on
+    fn monitor_name(&self, hwnd: isize) -> Option<String>;
+
+    /// Function to get the raw `WS` style bits of a window
+    fn style(&self, hwnd: isize) -> u32;
+
+    /// Function to get the raw `WS_EX` extended style bits of a window
+    fn ex_style(&self, hwnd: isize) -> u32;
+
+    /// Function to change the raw `WS` style bits of a window
+    fn set_style(&self, hwnd: isize, style: u32);
+
+    /// Function to change the raw `WS_EX` extended style bits of a window
+    fn set_ex_style(&self, hwnd: isize, ex_style: u32);
+
+    /// Function to get the window rectangle needed for the client area of a window to cover a rectangle
+    /// # Arguments
+    /// * `hwnd` - The window, whose current styles decide the size of its border
+    /// * `client` - The rectangle the client area should cover
+    fn window_rect_for_client(&self, hwnd: isize, client: Rect) -> Result<Rect, String>;
+
+    /// Function to move a window and apply its changed styles
+    /// # Arguments
+    /// * `hwnd` - The window
+    /// * `rect` - The new rectangle of the window, or `None` to keep its size and position
+    /// * `z_order` - Where to put the window in the z-order
+    /// * `activate` - Whether to activate the window
+    fn set_position(
+        &self,
+        hwnd: isize,
+        rect: Option<Rect>,
+        z_order: ZOrder,
+        activate: bool,
+    ) -> Result<(), String>;
+
+    /// Function to get the placement of a window, as needed to restore it
+    fn placement(&self, hwnd: isize) -> Result<SavedPlacement, String>;
+
+    /// Function to change the placement of a window
+    fn set_placement(&self, hwnd: isize, placement: &SavedPlacement) -> Result<(), String>;
+
+    /// Function to get the window directly above a window in the z-order
+    fn previous_window(&self, hwnd: isize) -> Option<isize>;
+
+    /// Function to get the monitors connected to the system, in the order reported by the system
+    fn monitors(&self) -> Vec<Monitor>;
+
+    /// Function to get the small icon of a window
+    #[cfg_attr(not(windows), allow(dead_code))]
+    fn icon(&self, hwnd: isize) -> Option<Self::Icon>;
+}
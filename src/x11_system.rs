@@ -0,0 +1,633 @@
+use std::fs;
+
+use x11rb::connection::Connection as _;
+use x11rb::protocol::randr::ConnectionExt as _;
+use x11rb::protocol::xproto::{
+    Atom, AtomEnum, ClientMessageEvent, ConnectionExt as _, EventMask, MapState, PropMode, Window,
+};
+use x11rb::rust_connection::RustConnection;
+use x11rb::wrapper::ConnectionExt as _;
+
+use crate::geometry::{self, Rect};
+use crate::monitors::{Monitor, MonitorChoice, MonitorList};
+use crate::window_state::SavedPlacement;
+use crate::window_system::{TOPMOST_EX_STYLE, WindowSystem, ZOrder};
+
+x11rb::atom_manager! {
+    /// The atoms of the properties and messages used to manage the windows
+    Atoms: AtomsCookie {
+        _MOTIF_WM_HINTS,
+        _NET_ACTIVE_WINDOW,
+        _NET_CLIENT_LIST,
+        _NET_CLIENT_LIST_STACKING,
+        _NET_CURRENT_DESKTOP,
+        _NET_FRAME_EXTENTS,
+        _NET_MOVERESIZE_WINDOW,
+        _NET_RESTACK_WINDOW,
+        _NET_WM_ICON,
+        _NET_WM_NAME,
+        _NET_WM_PID,
+        _NET_WM_STATE,
+        _NET_WM_STATE_ABOVE,
+        _NET_WM_STATE_FULLSCREEN,
+        _NET_WM_STATE_HIDDEN,
+        _NET_WM_STATE_MAXIMIZED_HORZ,
+        _NET_WM_STATE_MAXIMIZED_VERT,
+        _NET_WORKAREA,
+        WM_CHANGE_STATE,
+    }
+}
+
+/// `WS_` style bits reported for a window with a border
+/// (`WS_CAPTION | WS_SYSMENU | WS_THICKFRAME | WS_MINIMIZEBOX | WS_MAXIMIZEBOX`)
+const DECORATED_STYLE: u32 = 0x00CF_0000;
+/// `WS_POPUP` style bit, reported for a window without a border
+const POPUP_STYLE: u32 = 0x8000_0000;
+/// `WS_VISIBLE` style bit
+const VISIBLE_STYLE: u32 = 0x1000_0000;
+/// `WS_MINIMIZE` style bit
+const MINIMIZE_STYLE: u32 = 0x2000_0000;
+/// `WS_MAXIMIZE` style bit
+const MAXIMIZE_STYLE: u32 = 0x0100_0000;
+/// `WS_CAPTION | WS_THICKFRAME`, the style bits deciding whether a window gets a border
+const FRAME_STYLES: u32 = 0x00C0_0000 | 0x0004_0000;
+
+/// `SW_SHOWNORMAL` show command
+const SHOW_NORMAL: i32 = 1;
+/// `SW_SHOWMINIMIZED` show command
+const SHOW_MINIMIZED: i32 = 2;
+/// `SW_SHOWMAXIMIZED` show command
+const SHOW_MAXIMIZED: i32 = 3;
+
+/// `MWM_HINTS_DECORATIONS` flag of `_MOTIF_WM_HINTS`
+const MOTIF_HINTS_DECORATIONS: u32 = 1 << 1;
+
+/// `_NET_WM_STATE` actions
+const STATE_REMOVE: u32 = 0;
+const STATE_ADD: u32 = 1;
+
+/// Source indication of the requests, as a pager acting on behalf of the user
+const SOURCE_PAGER: u32 = 2;
+
+/// Flags of `_NET_MOVERESIZE_WINDOW`: static gravity, so that the client area is placed,
+/// with the position and size given, sent by a pager
+const MOVERESIZE_FLAGS: u32 = 10 | (0xF << 8) | (SOURCE_PAGER << 12);
+
+/// `_NET_RESTACK_WINDOW` stacking details
+const STACK_ABOVE: u32 = 0;
+const STACK_BELOW: u32 = 1;
+
+/// `IconicState` of `WM_CHANGE_STATE`
+const ICONIC_STATE: u32 = 3;
+
+/// The windows of an X11 desktop, managed through EWMH requests to the window manager
+///
+/// Window properties are mapped onto the Windows styles used by the rest of Fullscreenizer:
+/// a window without decorations has the `WS_POPUP` style, and a window kept above others has `WS_EX_TOPMOST`.
+pub struct X11System {
+    conn: RustConnection,
+    root: Window,
+    atoms: Atoms,
+}
+
+impl X11System {
+    /// Function to connect to the X server named by the `DISPLAY` environment variable
+    /// # Returns
+    /// * The window system of the default screen, or an error message
+    pub fn connect() -> Result<Self, String> {
+        let (conn, screen) =
+            x11rb::connect(None).map_err(|e| format!("Failed to connect to the X server: {e}"))?;
+        let root = conn
+            .setup()
+            .roots
+            .get(screen)
+            .map(|screen| screen.root)
+            .ok_or_else(|| format!("The X server has no screen {screen}"))?;
+        let atoms = Atoms::new(&conn)
+            .map_err(|e| format!("InternAtom failed with error: {e}"))?
+            .reply()
+            .map_err(|e| format!("InternAtom failed with error: {e}"))?;
+
+        Ok(Self { conn, root, atoms })
+    }
+
+    /// Function to read a property of a window
+    /// # Returns
+    /// * The property as 32-bit values, which is empty if the window does not have it
+    fn property32(&self, window: Window, property: Atom) -> Vec<u32> {
+        self.conn
+            .get_property(false, window, property, AtomEnum::ANY, 0, u32::MAX)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .and_then(|reply| reply.value32().map(Iterator::collect))
+            .unwrap_or_default()
+    }
+
+    /// Function to get the area of the current desktop left uncovered by panels
+    /// # Returns
+    /// * The area, or `None` if the window manager does not report it
+    ///
+    /// The window manager reports a single area for all monitors, so panels on some monitors only
+    /// shrink it on every monitor.
+    fn work_area(&self) -> Option<Rect> {
+        let desktop = self
+            .property32(self.root, self.atoms._NET_CURRENT_DESKTOP)
+            .first()
+            .copied()
+            .unwrap_or(0);
+        let areas = self.property32(self.root, self.atoms._NET_WORKAREA);
+        let &[left, top, width, height] =
+            areas.chunks_exact(4).nth(usize::try_from(desktop).ok()?)?
+        else {
+            return None;
+        };
+
+        let to_i32 = |value: u32| i32::try_from(value).ok();
+        Some(Rect::new(
+            to_i32(left)?,
+            to_i32(top)?,
+            to_i32(left.checked_add(width)?)?,
+            to_i32(top.checked_add(height)?)?,
+        ))
+    }
+
+    /// Function to read a text property of a window
+    /// # Returns
+    /// * The property, or `None` if the window does not have it
+    fn property_text(&self, window: Window, property: Atom) -> Option<String> {
+        self.conn
+            .get_property(false, window, property, AtomEnum::ANY, 0, u32::MAX)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .filter(|reply| reply.format == 8)
+            .map(|reply| String::from_utf8_lossy(&reply.value).into_owned())
+    }
+
+    /// Function to get the `_NET_WM_STATE` atoms of a window
+    fn states(&self, window: Window) -> Vec<Atom> {
+        self.property32(window, self.atoms._NET_WM_STATE)
+    }
+
+    /// Function to check whether the window manager is asked to decorate a window
+    fn is_decorated(&self, window: Window) -> bool {
+        match self
+            .property32(window, self.atoms._MOTIF_WM_HINTS)
+            .as_slice()
+        {
+            [flags, _, decorations, ..] if flags & MOTIF_HINTS_DECORATIONS != 0 => {
+                *decorations != 0
+            }
+            _ => true,
+        }
+    }
+
+    /// Function to get the size of the border of a window
+    /// # Returns
+    /// * The left, right, top and bottom border sizes, which are 0 if the window is not decorated
+    fn frame_extents(&self, window: Window) -> [i32; 4] {
+        if !self.is_decorated(window) {
+            return [0; 4];
+        }
+
+        match self
+            .property32(window, self.atoms._NET_FRAME_EXTENTS)
+            .as_slice()
+        {
+            &[left, right, top, bottom] => {
+                [left, right, top, bottom].map(|size| i32::try_from(size).unwrap_or(0))
+            }
+            _ => [0; 4],
+        }
+    }
+
+    /// Function to send a request about a window to the window manager
+    /// # Arguments
+    /// * `window` - The window
+    /// * `message_type` - The atom of the request
+    /// * `data` - The arguments of the request
+    fn send_request(
+        &self,
+        window: Window,
+        message_type: Atom,
+        data: [u32; 5],
+    ) -> Result<(), String> {
+        self.conn
+            .send_event(
+                false,
+                self.root,
+                EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY,
+                ClientMessageEvent::new(32, window, message_type, data),
+            )
+            .map(drop)
+            .map_err(|e| format!("SendEvent failed with error: {e}"))
+    }
+
+    /// Function to add or remove states of a window
+    /// # Arguments
+    /// * `window` - The window
+    /// * `add` - Whether to add the states, otherwise they are removed
+    /// * `first` - The first state
+    /// * `second` - The second state, or 0
+    fn change_state(
+        &self,
+        window: Window,
+        add: bool,
+        first: Atom,
+        second: Atom,
+    ) -> Result<(), String> {
+        let action = if add { STATE_ADD } else { STATE_REMOVE };
+        self.send_request(
+            window,
+            self.atoms._NET_WM_STATE,
+            [action, first, second, SOURCE_PAGER, 0],
+        )
+    }
+
+    /// Function to move a window so that it covers a rectangle, including its border
+    fn move_resize(&self, window: Window, rect: Rect) -> Result<(), String> {
+        // The request places the client area, so the border is taken off the rectangle
+        let [left, right, top, bottom] = self.frame_extents(window);
+        self.send_request(
+            window,
+            self.atoms._NET_MOVERESIZE_WINDOW,
+            [
+                MOVERESIZE_FLAGS,
+                (rect.left + left) as u32,
+                (rect.top + top) as u32,
+                (rect.width() - left - right).max(1) as u32,
+                (rect.height() - top - bottom).max(1) as u32,
+            ],
+        )
+    }
+
+    /// Function to send the queued requests to the X server
+    fn flush(&self) -> Result<(), String> {
+        self.conn
+            .flush()
+            .map_err(|e| format!("Failed to send the requests to the X server: {e}"))
+    }
+}
+
+impl WindowSystem for X11System {
+    /// The `_NET_WM_ICON` data, made of the width, height and ARGB pixels of each icon size
+    type Icon = Vec<u32>;
+
+    fn windows(&self) -> Vec<isize> {
+        // The stacking list is ordered from bottom to top
+        let stacking = self.property32(self.root, self.atoms._NET_CLIENT_LIST_STACKING);
+        let windows = if stacking.is_empty() {
+            self.property32(self.root, self.atoms._NET_CLIENT_LIST)
+        } else {
+            stacking.into_iter().rev().collect()
+        };
+
+        windows.into_iter().map(|window| window as isize).collect()
+    }
+
+    fn is_window(&self, hwnd: isize) -> bool {
+        self.conn
+            .get_window_attributes(hwnd as Window)
+            .ok()
+            .is_some_and(|cookie| cookie.reply().is_ok())
+    }
+
+    fn is_visible(&self, hwnd: isize) -> bool {
+        let viewable = self
+            .conn
+            .get_window_attributes(hwnd as Window)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .is_some_and(|attributes| attributes.map_state == MapState::VIEWABLE);
+
+        // Minimized windows are unmapped, but are still visible in the Windows sense
+        viewable
+            || self
+                .states(hwnd as Window)
+                .contains(&self.atoms._NET_WM_STATE_HIDDEN)
+    }
+
+    fn title(&self, hwnd: isize) -> Result<String, String> {
+        let window = hwnd as Window;
+
+        self.property_text(window, self.atoms._NET_WM_NAME)
+            .or_else(|| self.property_text(window, AtomEnum::WM_NAME.into()))
+            .ok_or_else(|| format!("Window {hwnd:#x} has no title"))
+    }
+
+    fn class_name(&self, hwnd: isize) -> Result<String, String> {
+        // WM_CLASS holds the instance name followed by the class name
+        self.property_text(hwnd as Window, AtomEnum::WM_CLASS.into())
+            .and_then(|names| {
+                names
+                    .split('\0')
+                    .nth(1)
+                    .filter(|class| !class.is_empty())
+                    .map(str::to_owned)
+            })
+            .ok_or_else(|| format!("Window {hwnd:#x} has no WM_CLASS"))
+    }
+
+    fn process_id(&self, hwnd: isize) -> u32 {
+        self.property32(hwnd as Window, self.atoms._NET_WM_PID)
+            .first()
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn exe_path(&self, hwnd: isize) -> Option<String> {
+        let process_id = self.process_id(hwnd);
+        if process_id == 0 {
+            return None;
+        }
+
+        // Under Wine and Proton, the process is the loader and its first argument is the Windows executable
+        let windows_exe = fs::read(format!("/proc/{process_id}/cmdline"))
+            .ok()
+            .and_then(|cmdline| {
+                cmdline
+                    .split(|&byte| byte == 0)
+                    .next()
+                    .map(|arg| String::from_utf8_lossy(arg).into_owned())
+            })
+            .filter(|arg| arg.to_ascii_lowercase().ends_with(".exe"));
+        if windows_exe.is_some() {
+            return windows_exe;
+        }
+
+        fs::read_link(format!("/proc/{process_id}/exe"))
+            .map(|path| path.to_string_lossy().into_owned())
+            .map_err(|e| eprintln!("Failed to get the executable of process {process_id}: {e}"))
+            .ok()
+    }
+
+    fn rect(&self, hwnd: isize) -> Result<Rect, String> {
+        let window = hwnd as Window;
+        let geometry = self
+            .conn
+            .get_geometry(window)
+            .map_err(|e| format!("GetGeometry failed with error: {e}"))?
+            .reply()
+            .map_err(|e| format!("GetGeometry failed with error: {e}"))?;
+        let position = self
+            .conn
+            .translate_coordinates(window, self.root, 0, 0)
+            .map_err(|e| format!("TranslateCoordinates failed with error: {e}"))?
+            .reply()
+            .map_err(|e| format!("TranslateCoordinates failed with error: {e}"))?;
+
+        // Include the border drawn by the window manager, as the window rectangle does on Windows
+        let [left, right, top, bottom] = self.frame_extents(window);
+        let x = i32::from(position.dst_x);
+        let y = i32::from(position.dst_y);
+        Ok(Rect::new(
+            x - left,
+            y - top,
+            x + i32::from(geometry.width) + right,
+            y + i32::from(geometry.height) + bottom,
+        ))
+    }
+
+    fn client_size(&self, hwnd: isize) -> Result<(i32, i32), String> {
+        let geometry = self
+            .conn
+            .get_geometry(hwnd as Window)
+            .map_err(|e| format!("GetGeometry failed with error: {e}"))?
+            .reply()
+            .map_err(|e| format!("GetGeometry failed with error: {e}"))?;
+
+        Ok((i32::from(geometry.width), i32::from(geometry.height)))
+    }
+
+    fn monitor_name(&self, hwnd: isize) -> Option<String> {
+        let rect = self.rect(hwnd).ok()?;
+
+        MonitorList::new(self.monitors())
+            .resolve(&MonitorChoice::Nearest, rect)
+            .map(|monitor| monitor.device_name.clone())
+    }
+
+    fn style(&self, hwnd: isize) -> u32 {
+        let window = hwnd as Window;
+        let states = self.states(window);
+
+        let mut style = if self.is_decorated(window)
+            && !states.contains(&self.atoms._NET_WM_STATE_FULLSCREEN)
+        {
+            DECORATED_STYLE
+        } else {
+            POPUP_STYLE
+        };
+        if self.is_visible(hwnd) {
+            style |= VISIBLE_STYLE;
+        }
+        if states.contains(&self.atoms._NET_WM_STATE_HIDDEN) {
+            style |= MINIMIZE_STYLE;
+        }
+        if states.contains(&self.atoms._NET_WM_STATE_MAXIMIZED_VERT)
+            && states.contains(&self.atoms._NET_WM_STATE_MAXIMIZED_HORZ)
+        {
+            style |= MAXIMIZE_STYLE;
+        }
+
+        style
+    }
+
+    fn ex_style(&self, hwnd: isize) -> u32 {
+        if self
+            .states(hwnd as Window)
+            .contains(&self.atoms._NET_WM_STATE_ABOVE)
+        {
+            TOPMOST_EX_STYLE
+        } else {
+            0
+        }
+    }
+
+    fn set_style(&self, hwnd: isize, style: u32) {
+        // Only the border can be changed, the other styles are part of the placement
+        let decorations = u32::from(style & FRAME_STYLES != 0);
+        self.conn
+            .change_property32(
+                PropMode::REPLACE,
+                hwnd as Window,
+                self.atoms._MOTIF_WM_HINTS,
+                self.atoms._MOTIF_WM_HINTS,
+                &[MOTIF_HINTS_DECORATIONS, 0, decorations, 0, 0],
+            )
+            .map(drop)
+            .map_err(|e| format!("ChangeProperty failed with error: {e}"))
+            .and_then(|()| self.flush())
+            .map_err(|e| eprintln!("Failed to change the border of window {hwnd:#x}: {e}"))
+            .ok();
+    }
+
+    fn set_ex_style(&self, hwnd: isize, ex_style: u32) {
+        let above = ex_style & TOPMOST_EX_STYLE != 0;
+        self.change_state(hwnd as Window, above, self.atoms._NET_WM_STATE_ABOVE, 0)
+            .and_then(|()| self.flush())
+            .map_err(|e| eprintln!("Failed to change the stacking of window {hwnd:#x}: {e}"))
+            .ok();
+    }
+
+    fn window_rect_for_client(&self, hwnd: isize, client: Rect) -> Result<Rect, String> {
+        let [left, right, top, bottom] = self.frame_extents(hwnd as Window);
+
+        Ok(Rect::new(
+            client.left - left,
+            client.top - top,
+            client.right + right,
+            client.bottom + bottom,
+        ))
+    }
+
+    fn set_position(
+        &self,
+        hwnd: isize,
+        rect: Option<Rect>,
+        z_order: ZOrder,
+        activate: bool,
+    ) -> Result<(), String> {
+        let window = hwnd as Window;
+
+        if let Some(rect) = rect {
+            self.move_resize(window, rect)?;
+
+            // A borderless window covering a whole monitor is made fullscreen,
+            // so that the window manager also hides the panels over it
+            let fullscreen = !self.is_decorated(window)
+                && self.monitors().iter().any(|monitor| monitor.rect == rect);
+            self.change_state(window, fullscreen, self.atoms._NET_WM_STATE_FULLSCREEN, 0)?;
+        }
+
+        let restack = |sibling: Window, detail: u32| {
+            self.send_request(
+                window,
+                self.atoms._NET_RESTACK_WINDOW,
+                [SOURCE_PAGER, sibling, detail, 0, 0],
+            )
+        };
+        match z_order {
+            ZOrder::Top => restack(0, STACK_ABOVE)?,
+            ZOrder::Topmost => {
+                self.change_state(window, true, self.atoms._NET_WM_STATE_ABOVE, 0)?;
+                restack(0, STACK_ABOVE)?;
+            }
+            ZOrder::NoTopmost => {
+                self.change_state(window, false, self.atoms._NET_WM_STATE_ABOVE, 0)?;
+            }
+            ZOrder::After(prev) => restack(prev as Window, STACK_BELOW)?,
+        }
+
+        if activate {
+            self.send_request(
+                window,
+                self.atoms._NET_ACTIVE_WINDOW,
+                [SOURCE_PAGER, 0, 0, 0, 0],
+            )?;
+        }
+
+        self.flush()
+    }
+
+    fn placement(&self, hwnd: isize) -> Result<SavedPlacement, String> {
+        let style = self.style(hwnd);
+        let show_cmd = if style & MINIMIZE_STYLE != 0 {
+            SHOW_MINIMIZED
+        } else if style & MAXIMIZE_STYLE != 0 {
+            SHOW_MAXIMIZED
+        } else {
+            SHOW_NORMAL
+        };
+
+        Ok(SavedPlacement {
+            show_cmd,
+            normal_rect: self.rect(hwnd)?,
+            ..SavedPlacement::default()
+        })
+    }
+
+    fn set_placement(&self, hwnd: isize, placement: &SavedPlacement) -> Result<(), String> {
+        let window = hwnd as Window;
+        let maximized = placement.show_cmd == SHOW_MAXIMIZED;
+
+        self.change_state(window, false, self.atoms._NET_WM_STATE_FULLSCREEN, 0)?;
+        self.change_state(
+            window,
+            maximized,
+            self.atoms._NET_WM_STATE_MAXIMIZED_VERT,
+            self.atoms._NET_WM_STATE_MAXIMIZED_HORZ,
+        )?;
+        if placement.show_cmd == SHOW_MINIMIZED {
+            self.send_request(
+                window,
+                self.atoms.WM_CHANGE_STATE,
+                [ICONIC_STATE, 0, 0, 0, 0],
+            )?;
+        } else if !maximized {
+            self.move_resize(window, placement.normal_rect)?;
+        }
+
+        self.flush()
+    }
+
+    fn previous_window(&self, hwnd: isize) -> Option<isize> {
+        let windows = self.windows();
+        let index = windows.iter().position(|&window| window == hwnd)?;
+
+        index
+            .checked_sub(1)
+            .and_then(|prev| windows.get(prev))
+            .copied()
+    }
+
+    fn monitors(&self) -> Vec<Monitor> {
+        let monitors = match self
+            .conn
+            .randr_get_monitors(self.root, true)
+            .map_err(|e| e.to_string())
+            .and_then(|cookie| cookie.reply().map_err(|e| e.to_string()))
+        {
+            Ok(reply) => reply.monitors,
+            Err(e) => {
+                eprintln!("RRGetMonitors failed: {e}");
+                return Vec::new();
+            }
+        };
+
+        let work_area = self.work_area();
+        monitors
+            .into_iter()
+            .map(|monitor| {
+                let device_name = self
+                    .conn
+                    .get_atom_name(monitor.name)
+                    .ok()
+                    .and_then(|cookie| cookie.reply().ok())
+                    .map(|reply| String::from_utf8_lossy(&reply.name).into_owned())
+                    .unwrap_or_default();
+                let left = i32::from(monitor.x);
+                let top = i32::from(monitor.y);
+                let rect = Rect::new(
+                    left,
+                    top,
+                    left + i32::from(monitor.width),
+                    top + i32::from(monitor.height),
+                );
+
+                Monitor {
+                    device_name,
+                    rect,
+                    work_area: work_area
+                        .and_then(|work_area| geometry::intersection(rect, work_area))
+                        .unwrap_or(rect),
+                    is_primary: monitor.primary,
+                }
+            })
+            .collect()
+    }
+
+    fn icon(&self, hwnd: isize) -> Option<Vec<u32>> {
+        Some(self.property32(hwnd as Window, self.atoms._NET_WM_ICON))
+            .filter(|icon| !icon.is_empty())
+    }
+}
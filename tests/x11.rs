@@ -0,0 +1,240 @@
+//! Tests of the commands against a real X server
+//!
+//! The tests need a window manager supporting EWMH, and are skipped unless `DISPLAY` is set.
+//! They fail instead of being skipped when `FULLSCREENIZER_X11_TESTS=1` is set, as done by `scripts/x11-tests.sh`,
+//! which runs them on Xvfb with openbox.
+
+#![cfg(all(unix, feature = "x11"))]
+
+use core::time::Duration;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::{self, Command, Output};
+use std::thread;
+use std::time::Instant;
+
+use serde_json::Value;
+use x11rb::COPY_DEPTH_FROM_PARENT;
+use x11rb::connection::Connection as _;
+use x11rb::protocol::randr::ConnectionExt as _;
+use x11rb::protocol::xproto::{
+    AtomEnum, ConnectionExt as _, CreateWindowAux, PropMode, Window, WindowClass,
+};
+use x11rb::rust_connection::RustConnection;
+use x11rb::wrapper::ConnectionExt as _;
+
+/// Environment variable that makes the tests fail when there is no X server, instead of being skipped
+const REQUIRED_VAR: &str = "FULLSCREENIZER_X11_TESTS";
+
+/// How long to wait for the window manager to handle a request
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A rectangle, as printed by `fullscreenizer list --json`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Rect {
+    left: i64,
+    top: i64,
+    right: i64,
+    bottom: i64,
+}
+
+/// A window opened for a test, closed when this is dropped
+struct TestWindow {
+    conn: RustConnection,
+    root: Window,
+    window: Window,
+    /// The config directory the commands are run with, so that the user's settings and journal are left alone
+    config_dir: PathBuf,
+}
+
+impl TestWindow {
+    /// Function to open a window and wait until the window manager lists it
+    /// # Arguments
+    /// * `name` - The name of the test, used in the title of the window
+    /// # Returns
+    /// * The window, or `None` if there is no X server to test against and the tests are not required to run
+    fn open(name: &str) -> Option<Self> {
+        if env::var_os("DISPLAY").is_none_or(|display| display.is_empty()) {
+            assert!(
+                env::var_os(REQUIRED_VAR).is_none_or(|required| required != "1"),
+                "DISPLAY is not set, but {REQUIRED_VAR}=1 requires the X11 tests to run"
+            );
+            eprintln!("Skipping {name}: DISPLAY is not set");
+            return None;
+        }
+
+        let (conn, screen) = x11rb::connect(None).expect("Failed to connect to the X server");
+        let screen = conn.setup().roots.get(screen).expect("No screen").clone();
+        let window = conn.generate_id().expect("Failed to allocate a window id");
+        conn.create_window(
+            COPY_DEPTH_FROM_PARENT,
+            window,
+            screen.root,
+            100,
+            100,
+            640,
+            480,
+            0,
+            WindowClass::INPUT_OUTPUT,
+            0,
+            &CreateWindowAux::new().background_pixel(screen.white_pixel),
+        )
+        .expect("Failed to create the window");
+        conn.change_property8(
+            PropMode::REPLACE,
+            window,
+            AtomEnum::WM_NAME,
+            AtomEnum::STRING,
+            format!("Fullscreenizer test {name} {}", process::id()).as_bytes(),
+        )
+        .expect("Failed to set the title");
+        conn.map_window(window).expect("Failed to map the window");
+        conn.flush().expect("Failed to flush the connection");
+
+        let config_dir =
+            env::temp_dir().join(format!("fullscreenizer-x11-{name}-{}", process::id()));
+        let test_window = Self {
+            conn,
+            root: screen.root,
+            window,
+            config_dir,
+        };
+        test_window.wait_until(|| test_window.record().is_some(), "listed");
+
+        Some(test_window)
+    }
+
+    /// Function to run Fullscreenizer with a command
+    fn run(&self, args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_fullscreenizer"))
+            .args(args)
+            .env("XDG_CONFIG_HOME", &self.config_dir)
+            .output()
+            .expect("Failed to run fullscreenizer")
+    }
+
+    /// Function to run a command on this window, which must succeed
+    fn run_on_window(&self, command: &str, args: &[&str]) {
+        let hwnd = self.window.to_string();
+        let mut all_args = vec![command, "--hwnd", &hwnd];
+        all_args.extend(args);
+
+        let output = self.run(&all_args);
+        assert!(
+            output.status.success(),
+            "{command} failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    /// Function to get the record of this window printed by `list --json`
+    fn record(&self) -> Option<Value> {
+        let output = self.run(&["list", "--json"]);
+        assert!(output.status.success(), "list failed");
+
+        let windows: Value =
+            serde_json::from_slice(&output.stdout).expect("list printed invalid JSON");
+        windows
+            .as_array()
+            .expect("list did not print an array")
+            .iter()
+            .find(|window| window["hwnd"].as_u64() == Some(u64::from(self.window)))
+            .cloned()
+    }
+
+    /// Function to get the rectangle of this window printed by `list --json`
+    fn rect(&self) -> Option<Rect> {
+        let record = self.record()?;
+        let rect = &record["rect"];
+        Some(Rect {
+            left: rect["left"].as_i64()?,
+            top: rect["top"].as_i64()?,
+            right: rect["right"].as_i64()?,
+            bottom: rect["bottom"].as_i64()?,
+        })
+    }
+
+    /// Function to wait until the window manager put the window in the expected state
+    fn wait_until(&self, done: impl Fn() -> bool, description: &str) {
+        let start = Instant::now();
+        while !done() {
+            assert!(
+                start.elapsed() < TIMEOUT,
+                "The window did not get {description} in time"
+            );
+            thread::sleep(Duration::from_millis(100));
+        }
+    }
+
+    /// Function to get the full areas of the monitors
+    fn monitors(&self) -> Vec<Rect> {
+        self.conn
+            .randr_get_monitors(self.root, true)
+            .expect("RRGetMonitors failed")
+            .reply()
+            .expect("RRGetMonitors failed")
+            .monitors
+            .into_iter()
+            .map(|monitor| Rect {
+                left: monitor.x.into(),
+                top: monitor.y.into(),
+                right: i64::from(monitor.x) + i64::from(monitor.width),
+                bottom: i64::from(monitor.y) + i64::from(monitor.height),
+            })
+            .collect()
+    }
+}
+
+impl Drop for TestWindow {
+    fn drop(&mut self) {
+        self.conn.destroy_window(self.window).ok();
+        self.conn.flush().ok();
+        fs::remove_dir_all(&self.config_dir).ok();
+    }
+}
+
+#[test]
+fn lists_a_new_window() {
+    let Some(window) = TestWindow::open("list") else {
+        return;
+    };
+
+    let record = window.record().unwrap();
+    assert!(
+        record["title"]
+            .as_str()
+            .unwrap()
+            .starts_with("Fullscreenizer test list")
+    );
+    assert_eq!(record["visible"], Value::Bool(true));
+    assert!(window.rect().is_some());
+}
+
+#[test]
+fn fullscreenizes_and_restores_a_window() {
+    let Some(window) = TestWindow::open("apply") else {
+        return;
+    };
+    let original = window.rect().unwrap();
+    let monitors = window.monitors();
+
+    window.run_on_window("apply", &["--scale", "stretch"]);
+    window.wait_until(
+        || window.rect().is_some_and(|rect| monitors.contains(&rect)),
+        "fullscreenized",
+    );
+
+    window.run_on_window("restore", &[]);
+    window.wait_until(|| window.rect() == Some(original), "restored");
+}
+
+#[test]
+fn restoring_an_unmodified_window_fails() {
+    let Some(window) = TestWindow::open("restore") else {
+        return;
+    };
+
+    let output = window.run(&["restore", "--hwnd", &window.window.to_string()]);
+    assert_eq!(output.status.code(), Some(3));
+}